@@ -0,0 +1,71 @@
+use std::sync::atomic::AtomicBool;
+
+use airlock::atomic_waker::AtomicWaker;
+use airlock::slot::Slot;
+use airlock::{mpmc, mpsc};
+use criterion::{criterion_group, criterion_main, Criterion};
+use futures::future;
+
+const BUFFER_SIZE: usize = 64;
+const WAKERS_COUNT: usize = 4;
+const VALUES: usize = 10_000;
+
+/// `mpsc` against `mpmc` with a single `Rx`, at the same buffer size.
+fn one_rx(c: &mut Criterion) {
+    let rt = tokio::runtime::Builder::new_current_thread().build().expect("runtime");
+
+    let mut group = c.benchmark_group("one-rx");
+    for txs in [1, WAKERS_COUNT] {
+        group.bench_function(format!("mpsc/{}-tx", txs), |b| b.iter(|| rt.block_on(mpsc(txs))));
+        group.bench_function(format!("mpmc/{}-tx", txs), |b| b.iter(|| rt.block_on(mpmc(txs))));
+    }
+    group.finish();
+}
+
+async fn mpsc(txs: usize) {
+    let buffer = make_buffer::<BUFFER_SIZE>();
+    let tx_wakers = make_wakers::<WAKERS_COUNT>();
+    let link = mpsc::Link::<usize, _, _>::new(&buffer, &tx_wakers);
+
+    let mut rx = mpsc::Rx::new(&link);
+    let producers = future::join_all((0..txs).map(|_| {
+        let mut tx = mpsc::Tx::new(&link);
+        async move {
+            for i in 0..VALUES / txs {
+                tx.send(i).await.expect("tx.send");
+            }
+        }
+    }));
+    let consumer = async { while rx.recv().await.is_ok() {} };
+    future::join(producers, consumer).await;
+}
+
+async fn mpmc(txs: usize) {
+    let buffer = make_buffer::<BUFFER_SIZE>();
+    let tx_wakers = make_wakers::<WAKERS_COUNT>();
+    let rx_wakers = make_wakers::<1>();
+    let link = mpmc::Link::<usize, _, _, _>::new(&buffer, &tx_wakers, &rx_wakers);
+
+    let mut rx = mpmc::Rx::new(&link);
+    let producers = future::join_all((0..txs).map(|_| {
+        let mut tx = mpmc::Tx::new(&link);
+        async move {
+            for i in 0..VALUES / txs {
+                tx.send(i).await.expect("tx.send");
+            }
+        }
+    }));
+    let consumer = async { while rx.recv().await.is_ok() {} };
+    future::join(producers, consumer).await;
+}
+
+fn make_buffer<const SIZE: usize>() -> [Slot<usize>; SIZE] {
+    core::array::from_fn(|_| Default::default())
+}
+
+fn make_wakers<const SIZE: usize>() -> [(AtomicBool, AtomicWaker); SIZE] {
+    core::array::from_fn(|_| Default::default())
+}
+
+criterion_group!(benches, one_rx);
+criterion_main!(benches);
@@ -0,0 +1,325 @@
+use core::borrow::Borrow;
+use core::future;
+use core::marker::PhantomData;
+use core::sync::atomic::{AtomicUsize, Ordering};
+use core::task::{Context, Poll};
+
+use crate::error::{RecvError, RecvErrorNoWait, SendError, SendErrorNoWait};
+use crate::slot::Slot;
+use crate::spsc::buffered;
+
+/// A medium through which [`EndA`] and [`EndB`] communicate.
+///
+/// Holds two rings: `A`s travel from [`EndA`] to [`EndB`] through `BA`, `B`s travel back through
+/// `BB`. Closing either end closes both directions. Either end may close the link with a reason
+/// of type `R`.
+///
+/// Dropping an end closes only the direction it sends in, the way dropping a
+/// [`buffered::Tx`] does: the peer still receives what is buffered, then sees the link closed.
+pub struct Link<A, B, BA, BB, R = ()>
+where
+    BA: AsRef<[Slot<A>]>,
+    BB: AsRef<[Slot<B>]>,
+{
+    a_to_b: buffered::Link<A, BA, R>,
+    b_to_a: buffered::Link<B, BB, R>,
+
+    ends: AtomicUsize,
+}
+
+/// An end of a [`Link`]: sends `S::Out`s and receives `S::In`s.
+///
+/// Which ring is which is decided by the [`Side`] `S`; see [`EndA`] and [`EndB`].
+pub struct End<A, B, L, BA, BB, S, R = ()>
+where
+    L: Borrow<Link<A, B, BA, BB, R>>,
+    BA: AsRef<[Slot<A>]>,
+    BB: AsRef<[Slot<B>]>,
+    S: Side<A, B, BA, BB, R>,
+{
+    link: L,
+    _values: PhantomData<(A, B)>,
+    _buffers: PhantomData<(BA, BB)>,
+    _side: PhantomData<S>,
+    _reason: PhantomData<R>,
+}
+
+/// The end that sends `A`s and receives `B`s.
+pub type EndA<A, B, L, BA, BB, R = ()> = End<A, B, L, BA, BB, SideA, R>;
+
+/// The end that sends `B`s and receives `A`s.
+pub type EndB<A, B, L, BA, BB, R = ()> = End<A, B, L, BA, BB, SideB, R>;
+
+/// The side of a [`Link`] an [`End`] is at: the ring it sends through and the one it receives
+/// from.
+pub trait Side<A, B, BA, BB, R>: sealed::Sealed
+where
+    BA: AsRef<[Slot<A>]>,
+    BB: AsRef<[Slot<B>]>,
+{
+    /// The values sent from this side.
+    type Out;
+    /// The values received on this side.
+    type In;
+    /// The buffer of the ring the values are sent through.
+    type OutBuffer: AsRef<[Slot<Self::Out>]>;
+    /// The buffer of the ring the values are received from.
+    type InBuffer: AsRef<[Slot<Self::In>]>;
+
+    /// The outgoing ring and the incoming one.
+    #[allow(clippy::type_complexity)]
+    fn rings(
+        link: &Link<A, B, BA, BB, R>,
+    ) -> (
+        &buffered::Link<Self::Out, Self::OutBuffer, R>,
+        &buffered::Link<Self::In, Self::InBuffer, R>,
+    );
+}
+
+/// The side of [`EndA`].
+#[derive(Debug)]
+pub enum SideA {}
+
+/// The side of [`EndB`].
+#[derive(Debug)]
+pub enum SideB {}
+
+impl<A, B, BA, BB, R> Link<A, B, BA, BB, R>
+where
+    BA: AsRef<[Slot<A>]>,
+    BB: AsRef<[Slot<B>]>,
+{
+    /// Creates a new [`Link`]
+    pub fn new(buffer_a: BA, buffer_b: BB) -> Self {
+        Self {
+            a_to_b: buffered::Link::new(buffer_a),
+            b_to_a: buffered::Link::new(buffer_b),
+            ends: Default::default(),
+        }
+    }
+
+    fn attach(&self) {
+        self.ends.fetch_add(1, Ordering::SeqCst);
+    }
+    fn detach(&self) {
+        self.ends.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl<A, B, L, BA, BB, S, R> End<A, B, L, BA, BB, S, R>
+where
+    L: Borrow<Link<A, B, BA, BB, R>>,
+    BA: AsRef<[Slot<A>]>,
+    BB: AsRef<[Slot<B>]>,
+    S: Side<A, B, BA, BB, R>,
+{
+    /// Creates a new [`End`]
+    pub fn new(link: L) -> Self {
+        let duplex = link.borrow();
+        let (outgoing, incoming) = S::rings(duplex);
+        outgoing.set_tx();
+        incoming.set_rx();
+        duplex.attach();
+
+        Self {
+            link,
+            _values: Default::default(),
+            _buffers: Default::default(),
+            _side: Default::default(),
+            _reason: Default::default(),
+        }
+    }
+
+    /// Sends a value if the channel is not full.
+    pub fn send_nowait(&mut self, value: S::Out) -> Result<(), SendErrorNoWait<S::Out, R>>
+    where
+        R: Clone,
+    {
+        let (outgoing, _) = S::rings(self.link.borrow());
+        outgoing.send_nowait(value).map_err(|e| e.with_reason(|| outgoing.reason.get()))
+    }
+
+    /// Sends a value, waits if necessary.
+    pub async fn send(&mut self, value: S::Out) -> Result<(), SendError<S::Out, R>>
+    where
+        R: Clone,
+    {
+        let (outgoing, _) = S::rings(self.link.borrow());
+        let mut value = Some(value);
+        future::poll_fn(|cx| outgoing.poll_send(cx, &mut value)).await
+    }
+
+    /// Receives a value if it is ready.
+    pub fn recv_nowait(&mut self) -> Result<S::In, RecvErrorNoWait<R>>
+    where
+        R: Clone,
+    {
+        let (_, incoming) = S::rings(self.link.borrow());
+        incoming.recv_nowait().map_err(|e| e.with_reason(|| incoming.reason.get()))
+    }
+
+    /// Receives a value, waits if necessary.
+    pub async fn recv(&mut self) -> Result<S::In, RecvError<R>>
+    where
+        R: Clone,
+    {
+        let (_, incoming) = S::rings(self.link.borrow());
+        future::poll_fn(|cx| incoming.poll_recv(cx)).await
+    }
+
+    /// Closes both directions.
+    pub fn close(&mut self) {
+        let (outgoing, incoming) = S::rings(self.link.borrow());
+        outgoing.close(false, true);
+        incoming.close_rx();
+    }
+
+    /// Closes both directions with a reason.
+    ///
+    /// The reason is dropped if the channel is closed already.
+    pub fn close_with(&mut self, reason: R)
+    where
+        R: Clone,
+    {
+        let (outgoing, incoming) = S::rings(self.link.borrow());
+        outgoing.set_reason(reason.clone());
+        incoming.set_reason(reason);
+        self.close()
+    }
+}
+
+impl<A, B, BA, BB, R> Side<A, B, BA, BB, R> for SideA
+where
+    BA: AsRef<[Slot<A>]>,
+    BB: AsRef<[Slot<B>]>,
+{
+    type Out = A;
+    type In = B;
+    type OutBuffer = BA;
+    type InBuffer = BB;
+
+    fn rings(
+        link: &Link<A, B, BA, BB, R>,
+    ) -> (&buffered::Link<A, BA, R>, &buffered::Link<B, BB, R>) {
+        (&link.a_to_b, &link.b_to_a)
+    }
+}
+
+impl<A, B, BA, BB, R> Side<A, B, BA, BB, R> for SideB
+where
+    BA: AsRef<[Slot<A>]>,
+    BB: AsRef<[Slot<B>]>,
+{
+    type Out = B;
+    type In = A;
+    type OutBuffer = BB;
+    type InBuffer = BA;
+
+    fn rings(
+        link: &Link<A, B, BA, BB, R>,
+    ) -> (&buffered::Link<B, BB, R>, &buffered::Link<A, BA, R>) {
+        (&link.b_to_a, &link.a_to_b)
+    }
+}
+
+mod sealed {
+    pub trait Sealed {}
+
+    impl Sealed for super::SideA {}
+    impl Sealed for super::SideB {}
+}
+
+impl<A, B, L, BA, BB, S, R> Drop for End<A, B, L, BA, BB, S, R>
+where
+    L: Borrow<Link<A, B, BA, BB, R>>,
+    BA: AsRef<[Slot<A>]>,
+    BB: AsRef<[Slot<B>]>,
+    S: Side<A, B, BA, BB, R>,
+{
+    fn drop(&mut self) {
+        let duplex = self.link.borrow();
+        let (outgoing, incoming) = S::rings(duplex);
+        outgoing.close(false, true);
+        outgoing.detach_tx();
+        incoming.detach_rx();
+        duplex.detach();
+    }
+}
+
+impl<A, B, BA, BB, R> Drop for Link<A, B, BA, BB, R>
+where
+    BA: AsRef<[Slot<A>]>,
+    BB: AsRef<[Slot<B>]>,
+{
+    fn drop(&mut self) {
+        if *self.ends.get_mut() != 0 {
+            crate::leak::report::<Self>("Dropping Link that is still referenced?")
+        }
+
+        // Reported once above: the rings need not report the same ends again.
+        self.a_to_b.reset();
+        self.b_to_a.reset();
+    }
+}
+
+impl<A, B, BA, BB, R> crate::shutdown::Close for Link<A, B, BA, BB, R>
+where
+    BA: AsRef<[Slot<A>]>,
+    BB: AsRef<[Slot<B>]>,
+{
+    fn close(&self) {
+        self.a_to_b.close(true, true);
+        self.b_to_a.close(true, true);
+    }
+}
+
+impl<A, B, L, BA, BB, S, R> crate::select::PollRecv for End<A, B, L, BA, BB, S, R>
+where
+    L: Borrow<Link<A, B, BA, BB, R>>,
+    BA: AsRef<[Slot<A>]>,
+    BB: AsRef<[Slot<B>]>,
+    S: Side<A, B, BA, BB, R>,
+    R: Clone,
+{
+    type Item = S::In;
+    type Reason = R;
+
+    fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Result<S::In, RecvError<R>>> {
+        let (_, incoming) = S::rings(self.link.borrow());
+        incoming.poll_recv(cx)
+    }
+
+    fn close(&mut self) {
+        End::close(self)
+    }
+}
+
+impl<A, B, L, BA, BB, S, R> crate::route::PollSend for End<A, B, L, BA, BB, S, R>
+where
+    L: Borrow<Link<A, B, BA, BB, R>>,
+    BA: AsRef<[Slot<A>]>,
+    BB: AsRef<[Slot<B>]>,
+    S: Side<A, B, BA, BB, R>,
+    R: Clone,
+{
+    type Item = S::Out;
+    type Reason = R;
+
+    fn poll_send(
+        &mut self,
+        cx: &mut Context<'_>,
+        value: &mut Option<S::Out>,
+    ) -> Poll<Result<(), SendError<S::Out, R>>> {
+        let (outgoing, _) = S::rings(self.link.borrow());
+        outgoing.poll_send(cx, value)
+    }
+
+    fn poll_closed(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        let (outgoing, _) = S::rings(self.link.borrow());
+        outgoing.poll_closed(cx)
+    }
+
+    fn close(&mut self) {
+        End::close(self)
+    }
+}
@@ -4,10 +4,77 @@
 #[cfg_attr(feature = "thiserror", error("Limit reached"))]
 pub struct LimitReached;
 
+/// The link still has endpoints attached.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "thiserror", derive(thiserror::Error))]
+#[cfg_attr(feature = "thiserror", error("Link in use"))]
+pub struct InUse;
+
+/// Not enough permits available.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "thiserror", derive(thiserror::Error))]
+#[cfg_attr(feature = "thiserror", error("No permits"))]
+pub struct NoPermits;
+
+/// Error acquiring permits from a [`Semaphore`](crate::semaphore::Semaphore).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "thiserror", derive(thiserror::Error))]
+pub enum AcquireError {
+    /// More permits are asked for than the semaphore was created with.
+    #[cfg_attr(feature = "thiserror", error("Too many permits"))]
+    TooMany,
+
+    /// The permits are not available, and all the wakers are taken by other waiting tasks.
+    #[cfg_attr(feature = "thiserror", error("Limit reached"))]
+    LimitReached,
+}
+
+/// No lane with such an index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "thiserror", derive(thiserror::Error))]
+#[cfg_attr(feature = "thiserror", error("No such lane"))]
+pub struct NoSuchLane;
+
+/// Error making a call with an [`rpc::Client`](crate::rpc::Client).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "thiserror", derive(thiserror::Error))]
+pub enum RpcError {
+    /// All the reply links are in use, and too many calls wait for one already.
+    #[cfg_attr(feature = "thiserror", error("Busy"))]
+    Busy,
+
+    /// No server takes requests anymore.
+    #[cfg_attr(feature = "thiserror", error("Closed"))]
+    Closed,
+
+    /// The request was dropped without a response.
+    #[cfg_attr(feature = "thiserror", error("No reply"))]
+    NoReply,
+}
+
+/// Error resizing a [`growable::Link`](crate::spsc::buffered::growable::Link).
+///
+/// The rejected buffer is handed back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "thiserror", derive(thiserror::Error))]
+pub enum ResizeError<B> {
+    /// The buffer exceeds the maximum capacity.
+    #[cfg_attr(feature = "thiserror", error("Too large"))]
+    TooLarge(B),
+
+    /// The buffer is shorter than the current one, and the link is not empty.
+    #[cfg_attr(feature = "thiserror", error("Not empty"))]
+    NotEmpty(B),
+
+    /// The buffer has no slots.
+    #[cfg_attr(feature = "thiserror", error("Empty"))]
+    Empty(B),
+}
+
 /// Error performing non-blocking send.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(feature = "thiserror", derive(thiserror::Error))]
-pub enum SendErrorNoWait<T> {
+pub enum SendErrorNoWait<T, R = ()> {
     /// The channel is full.
     #[cfg_attr(feature = "thiserror", error("Full"))]
     Full(T),
@@ -15,21 +82,45 @@ pub enum SendErrorNoWait<T> {
     /// The channel is closed.
     #[cfg_attr(feature = "thiserror", error("Closed"))]
     Closed(T),
+
+    /// The channel is full, the value is rejected.
+    #[cfg_attr(feature = "thiserror", error("Rejected"))]
+    Rejected(T),
+
+    /// The value is sent, the oldest value is evicted from the channel to make room for it.
+    #[cfg_attr(feature = "thiserror", error("Evicted"))]
+    Evicted(T),
+
+    /// The channel is closed with a reason.
+    #[cfg_attr(feature = "thiserror", error("Closed with a reason"))]
+    ClosedWith(T, R),
 }
 
 /// Error performing blocking send.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(feature = "thiserror", derive(thiserror::Error))]
-pub enum SendError<T> {
+pub enum SendError<T, R = ()> {
     /// The channel is closed
     #[cfg_attr(feature = "thiserror", error("Closed"))]
     Closed(T),
+
+    /// The channel is full, the value is rejected.
+    #[cfg_attr(feature = "thiserror", error("Rejected"))]
+    Rejected(T),
+
+    /// The value is sent, the oldest value is evicted from the channel to make room for it.
+    #[cfg_attr(feature = "thiserror", error("Evicted"))]
+    Evicted(T),
+
+    /// The channel is closed with a reason.
+    #[cfg_attr(feature = "thiserror", error("Closed with a reason"))]
+    ClosedWith(T, R),
 }
 
 /// Error performing non-blocking recv.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(feature = "thiserror", derive(thiserror::Error))]
-pub enum RecvErrorNoWait {
+pub enum RecvErrorNoWait<R = ()> {
     /// The channel is empty.
     #[cfg_attr(feature = "thiserror", error("Empty"))]
     Empty,
@@ -37,18 +128,26 @@ pub enum RecvErrorNoWait {
     /// The channel is closed.
     #[cfg_attr(feature = "thiserror", error("Full"))]
     Closed,
+
+    /// The channel is closed with a reason.
+    #[cfg_attr(feature = "thiserror", error("Closed with a reason"))]
+    ClosedWith(R),
 }
 
 /// Error performing blocking recv.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(feature = "thiserror", derive(thiserror::Error))]
-pub enum RecvError {
+pub enum RecvError<R = ()> {
     /// The channel is closed.
     #[cfg_attr(feature = "thiserror", error("Full"))]
     Closed,
+
+    /// The channel is closed with a reason.
+    #[cfg_attr(feature = "thiserror", error("Closed with a reason"))]
+    ClosedWith(R),
 }
 
-impl<T> SendErrorNoWait<T> {
+impl<T, R> SendErrorNoWait<T, R> {
     /// Constructs [`SendErrorNoWait::Full`]
     pub fn full(value: T) -> Self {
         Self::Full(value)
@@ -59,18 +158,60 @@ impl<T> SendErrorNoWait<T> {
         Self::Closed(value)
     }
 
+    /// Constructs [`SendErrorNoWait::Rejected`]
+    pub fn rejected(value: T) -> Self {
+        Self::Rejected(value)
+    }
+
+    /// Constructs [`SendErrorNoWait::Evicted`]
+    pub fn evicted(value: T) -> Self {
+        Self::Evicted(value)
+    }
+
+    /// Constructs [`SendErrorNoWait::ClosedWith`]
+    pub fn closed_with(value: T, reason: R) -> Self {
+        Self::ClosedWith(value, reason)
+    }
+
     /// Check whether is [`SendErrorNoWait::Full`]
     pub fn is_full(&self) -> bool {
         matches!(self, Self::Full { .. })
     }
 
-    /// Check whether is [`SendErrorNoWait::Closed`]
+    /// Check whether is [`SendErrorNoWait::Closed`] or [`SendErrorNoWait::ClosedWith`]
     pub fn is_closed(&self) -> bool {
-        matches!(self, Self::Closed { .. })
+        matches!(self, Self::Closed { .. } | Self::ClosedWith { .. })
+    }
+
+    /// Check whether is [`SendErrorNoWait::Rejected`]
+    pub fn is_rejected(&self) -> bool {
+        matches!(self, Self::Rejected { .. })
+    }
+
+    /// Check whether is [`SendErrorNoWait::Evicted`]
+    pub fn is_evicted(&self) -> bool {
+        matches!(self, Self::Evicted { .. })
+    }
+
+    /// The reason the channel is closed with, if any.
+    pub fn reason(&self) -> Option<&R> {
+        match self {
+            Self::ClosedWith(_, reason) => Some(reason),
+            _ => None,
+        }
     }
 }
 
-impl RecvErrorNoWait {
+impl<B> ResizeError<B> {
+    /// The rejected buffer.
+    pub fn into_buffer(self) -> B {
+        match self {
+            Self::TooLarge(buffer) | Self::NotEmpty(buffer) | Self::Empty(buffer) => buffer,
+        }
+    }
+}
+
+impl<R> RecvErrorNoWait<R> {
     /// Constructs [`RecvErrorNoWait::Empty`]
     pub fn empty() -> Self {
         Self::Empty
@@ -81,38 +222,97 @@ impl RecvErrorNoWait {
         Self::Closed
     }
 
+    /// Constructs [`RecvErrorNoWait::ClosedWith`]
+    pub fn closed_with(reason: R) -> Self {
+        Self::ClosedWith(reason)
+    }
+
     /// Check whether is [`RecvErrorNoWait::Empty`]
     pub fn is_empty(&self) -> bool {
         matches!(self, Self::Empty { .. })
     }
 
-    /// Check whether is [`RecvErrorNoWait::Closed`]
+    /// Check whether is [`RecvErrorNoWait::Closed`] or [`RecvErrorNoWait::ClosedWith`]
     pub fn is_closed(&self) -> bool {
-        matches!(self, Self::Closed { .. })
+        matches!(self, Self::Closed { .. } | Self::ClosedWith { .. })
+    }
+
+    /// The reason the channel is closed with, if any.
+    pub fn reason(&self) -> Option<&R> {
+        match self {
+            Self::ClosedWith(reason) => Some(reason),
+            _ => None,
+        }
     }
 }
 
-impl<T> SendError<T> {
+impl<T, R> SendError<T, R> {
     /// Constructs [`SendError::Closed`]
     pub fn closed(value: T) -> Self {
         Self::Closed(value)
     }
 
-    /// Check whether is [`SendError::Closed`]
+    /// Constructs [`SendError::Rejected`]
+    pub fn rejected(value: T) -> Self {
+        Self::Rejected(value)
+    }
+
+    /// Constructs [`SendError::Evicted`]
+    pub fn evicted(value: T) -> Self {
+        Self::Evicted(value)
+    }
+
+    /// Constructs [`SendError::ClosedWith`]
+    pub fn closed_with(value: T, reason: R) -> Self {
+        Self::ClosedWith(value, reason)
+    }
+
+    /// Check whether is [`SendError::Closed`] or [`SendError::ClosedWith`]
     pub fn is_closed(&self) -> bool {
-        matches!(self, Self::Closed { .. })
+        matches!(self, Self::Closed { .. } | Self::ClosedWith { .. })
+    }
+
+    /// Check whether is [`SendError::Rejected`]
+    pub fn is_rejected(&self) -> bool {
+        matches!(self, Self::Rejected { .. })
+    }
+
+    /// Check whether is [`SendError::Evicted`]
+    pub fn is_evicted(&self) -> bool {
+        matches!(self, Self::Evicted { .. })
+    }
+
+    /// The reason the channel is closed with, if any.
+    pub fn reason(&self) -> Option<&R> {
+        match self {
+            Self::ClosedWith(_, reason) => Some(reason),
+            _ => None,
+        }
     }
 }
 
-impl RecvError {
+impl<R> RecvError<R> {
     /// Constructs [`RecvError::Closed`]
     pub fn closed() -> Self {
         Self::Closed
     }
 
-    /// Check whether is [`RecvError::Closed`]
+    /// Constructs [`RecvError::ClosedWith`]
+    pub fn closed_with(reason: R) -> Self {
+        Self::ClosedWith(reason)
+    }
+
+    /// Check whether is [`RecvError::Closed`] or [`RecvError::ClosedWith`]
     pub fn is_closed(&self) -> bool {
-        matches!(self, Self::Closed { .. })
+        matches!(self, Self::Closed { .. } | Self::ClosedWith { .. })
+    }
+
+    /// The reason the channel is closed with, if any.
+    pub fn reason(&self) -> Option<&R> {
+        match self {
+            Self::ClosedWith(reason) => Some(reason),
+            _ => None,
+        }
     }
 }
 
@@ -121,6 +321,38 @@ impl<T> SendErrorNoWait<T> {
         match self {
             Self::Closed { .. } => SendErrorNoWait::Closed(value),
             Self::Full { .. } => SendErrorNoWait::Full(value),
+            Self::Rejected { .. } => SendErrorNoWait::Rejected(value),
+            Self::Evicted { .. } => SendErrorNoWait::Evicted(value),
+            Self::ClosedWith(_, reason) => SendErrorNoWait::ClosedWith(value, reason),
+        }
+    }
+
+    /// Turns [`SendErrorNoWait::Closed`] into [`SendErrorNoWait::ClosedWith`] if there is a reason.
+    pub(crate) fn with_reason<R>(
+        self,
+        reason: impl FnOnce() -> Option<R>,
+    ) -> SendErrorNoWait<T, R> {
+        match self {
+            Self::Full(value) => SendErrorNoWait::Full(value),
+            Self::Rejected(value) => SendErrorNoWait::Rejected(value),
+            Self::Evicted(value) => SendErrorNoWait::Evicted(value),
+            Self::Closed(value) | Self::ClosedWith(value, ()) => match reason() {
+                Some(reason) => SendErrorNoWait::ClosedWith(value, reason),
+                None => SendErrorNoWait::Closed(value),
+            },
+        }
+    }
+}
+
+impl RecvErrorNoWait {
+    /// Turns [`RecvErrorNoWait::Closed`] into [`RecvErrorNoWait::ClosedWith`] if there is a reason.
+    pub(crate) fn with_reason<R>(self, reason: impl FnOnce() -> Option<R>) -> RecvErrorNoWait<R> {
+        match self {
+            Self::Empty => RecvErrorNoWait::Empty,
+            Self::Closed | Self::ClosedWith(()) => match reason() {
+                Some(reason) => RecvErrorNoWait::ClosedWith(reason),
+                None => RecvErrorNoWait::Closed,
+            },
         }
     }
 }
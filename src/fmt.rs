@@ -6,31 +6,31 @@ use futures::task::AtomicWaker;
 
 use crate::slot::Slot;
 
-impl<T> fmt::Debug for crate::spsc::direct::Link<T> {
+impl<T, R> fmt::Debug for crate::spsc::direct::Link<T, R> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct(core::any::type_name::<Self>()).finish()
     }
 }
 
-impl<T, L> fmt::Debug for crate::spsc::direct::Tx<T, L>
+impl<T, L, R> fmt::Debug for crate::spsc::direct::Tx<T, L, R>
 where
-    L: Borrow<crate::spsc::direct::Link<T>>,
+    L: Borrow<crate::spsc::direct::Link<T, R>>,
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct(core::any::type_name::<Self>()).finish()
     }
 }
 
-impl<T, L> fmt::Debug for crate::spsc::direct::Rx<T, L>
+impl<T, L, R> fmt::Debug for crate::spsc::direct::Rx<T, L, R>
 where
-    L: Borrow<crate::spsc::direct::Link<T>>,
+    L: Borrow<crate::spsc::direct::Link<T, R>>,
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct(core::any::type_name::<Self>()).finish()
     }
 }
 
-impl<T, B> fmt::Debug for crate::spsc::buffered::Link<T, B>
+impl<T, B, R> fmt::Debug for crate::spsc::buffered::Link<T, B, R>
 where
     B: AsRef<[Slot<T>]>,
 {
@@ -39,30 +39,158 @@ where
     }
 }
 
-impl<T, L, B> fmt::Debug for crate::spsc::buffered::Tx<T, L, B>
+impl<T, L, B, R> fmt::Debug for crate::spsc::buffered::Tx<T, L, B, R>
 where
     B: AsRef<[Slot<T>]>,
-    L: Borrow<crate::spsc::buffered::Link<T, B>>,
+    L: Borrow<crate::spsc::buffered::Link<T, B, R>>,
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct(core::any::type_name::<Self>()).finish()
     }
 }
 
-impl<T, L, B> fmt::Debug for crate::spsc::buffered::Rx<T, L, B>
+impl<T, L, B, R> fmt::Debug for crate::spsc::buffered::Rx<T, L, B, R>
 where
     B: AsRef<[Slot<T>]>,
-    L: Borrow<crate::spsc::buffered::Link<T, B>>,
+    L: Borrow<crate::spsc::buffered::Link<T, B, R>>,
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct(core::any::type_name::<Self>()).finish()
     }
 }
 
-impl<T, B, TW, RW> fmt::Debug for crate::mpmc::Link<T, B, TW, RW>
+impl<T, B, R> fmt::Debug for crate::spsc::buffered::growable::Link<T, B, R>
 where
     B: AsRef<[Slot<T>]>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct(core::any::type_name::<Self>()).finish()
+    }
+}
+
+impl<T, L, B, R> fmt::Debug for crate::spsc::buffered::growable::Tx<T, L, B, R>
+where
+    B: AsRef<[Slot<T>]>,
+    L: Borrow<crate::spsc::buffered::growable::Link<T, B, R>>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct(core::any::type_name::<Self>()).finish()
+    }
+}
+
+impl<T, L, B, R> fmt::Debug for crate::spsc::buffered::growable::Rx<T, L, B, R>
+where
+    B: AsRef<[Slot<T>]>,
+    L: Borrow<crate::spsc::buffered::growable::Link<T, B, R>>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct(core::any::type_name::<Self>()).finish()
+    }
+}
+
+impl<T, L, B, R> fmt::Debug for crate::spsc::buffered::growable::Resizer<T, L, B, R>
+where
+    B: AsRef<[Slot<T>]>,
+    L: Borrow<crate::spsc::buffered::growable::Link<T, B, R>>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct(core::any::type_name::<Self>()).finish()
+    }
+}
+
+impl<T, B, TW, RW, R> fmt::Debug for crate::mpmc::Link<T, B, TW, RW, R>
+where
+    B: AsRef<[Slot<T>]>,
+    TW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    RW: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct(core::any::type_name::<Self>()).finish()
+    }
+}
+
+impl<T, L, B, TW, RW, R> fmt::Debug for crate::mpmc::Tx<T, L, B, TW, RW, R>
+where
+    L: Borrow<crate::mpmc::Link<T, B, TW, RW, R>>,
+    B: AsRef<[Slot<T>]>,
+    TW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    RW: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct(core::any::type_name::<Self>()).finish()
+    }
+}
+
+impl<T, L, B, TW, RW, R> fmt::Debug for crate::mpmc::WeakTx<T, L, B, TW, RW, R>
+where
+    L: Borrow<crate::mpmc::Link<T, B, TW, RW, R>>,
+    B: AsRef<[Slot<T>]>,
+    TW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    RW: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct(core::any::type_name::<Self>()).finish()
+    }
+}
+
+impl<T, L, B, TW, RW, R> fmt::Debug for crate::mpmc::Rx<T, L, B, TW, RW, R>
+where
+    L: Borrow<crate::mpmc::Link<T, B, TW, RW, R>>,
+    B: AsRef<[Slot<T>]>,
+    TW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    RW: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct(core::any::type_name::<Self>()).finish()
+    }
+}
+
+impl<T, B, TW, R> fmt::Debug for crate::mpsc::Link<T, B, TW, R>
+where
+    B: AsRef<[Slot<T>]>,
+    TW: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct(core::any::type_name::<Self>()).finish()
+    }
+}
+
+impl<T, L, B, TW, R> fmt::Debug for crate::mpsc::Tx<T, L, B, TW, R>
+where
+    L: Borrow<crate::mpsc::Link<T, B, TW, R>>,
+    B: AsRef<[Slot<T>]>,
+    TW: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct(core::any::type_name::<Self>()).finish()
+    }
+}
+
+impl<T, L, B, TW, R> fmt::Debug for crate::mpsc::Rx<T, L, B, TW, R>
+where
+    L: Borrow<crate::mpsc::Link<T, B, TW, R>>,
+    B: AsRef<[Slot<T>]>,
     TW: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct(core::any::type_name::<Self>()).finish()
+    }
+}
+
+impl<T, B, RW, R> fmt::Debug for crate::spmc::Link<T, B, RW, R>
+where
+    B: AsRef<[Slot<T>]>,
+    RW: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct(core::any::type_name::<Self>()).finish()
+    }
+}
+
+impl<T, L, B, RW, R> fmt::Debug for crate::spmc::Tx<T, L, B, RW, R>
+where
+    L: Borrow<crate::spmc::Link<T, B, RW, R>>,
+    B: AsRef<[Slot<T>]>,
     RW: AsRef<[(AtomicBool, AtomicWaker)]>,
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -70,9 +198,19 @@ where
     }
 }
 
-impl<T, L, B, TW, RW> fmt::Debug for crate::mpmc::Tx<T, L, B, TW, RW>
+impl<T, L, B, RW, R> fmt::Debug for crate::spmc::Rx<T, L, B, RW, R>
+where
+    L: Borrow<crate::spmc::Link<T, B, RW, R>>,
+    B: AsRef<[Slot<T>]>,
+    RW: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct(core::any::type_name::<Self>()).finish()
+    }
+}
+
+impl<T, B, TW, RW, const K: usize, R> fmt::Debug for crate::mpmc::prio::Link<T, B, TW, RW, K, R>
 where
-    L: Borrow<crate::mpmc::Link<T, B, TW, RW>>,
     B: AsRef<[Slot<T>]>,
     TW: AsRef<[(AtomicBool, AtomicWaker)]>,
     RW: AsRef<[(AtomicBool, AtomicWaker)]>,
@@ -82,14 +220,309 @@ where
     }
 }
 
-impl<T, L, B, TW, RW> fmt::Debug for crate::mpmc::Rx<T, L, B, TW, RW>
+impl<T, L, B, TW, RW, const K: usize, R> fmt::Debug for crate::mpmc::prio::Tx<T, L, B, TW, RW, K, R>
+where
+    L: Borrow<crate::mpmc::prio::Link<T, B, TW, RW, K, R>>,
+    B: AsRef<[Slot<T>]>,
+    TW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    RW: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct(core::any::type_name::<Self>()).finish()
+    }
+}
+
+impl<T, L, B, TW, RW, const K: usize, R> fmt::Debug for crate::mpmc::prio::Rx<T, L, B, TW, RW, K, R>
+where
+    L: Borrow<crate::mpmc::prio::Link<T, B, TW, RW, K, R>>,
+    B: AsRef<[Slot<T>]>,
+    TW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    RW: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct(core::any::type_name::<Self>()).finish()
+    }
+}
+
+impl<T, B, R> fmt::Debug for crate::heap::Link<T, B, R>
+where
+    B: AsRef<[Slot<T>]>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct(core::any::type_name::<Self>()).finish()
+    }
+}
+
+impl<T, L, B, R> fmt::Debug for crate::heap::Tx<T, L, B, R>
+where
+    B: AsRef<[Slot<T>]>,
+    L: Borrow<crate::heap::Link<T, B, R>>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct(core::any::type_name::<Self>()).finish()
+    }
+}
+
+impl<T, L, B, R> fmt::Debug for crate::heap::Rx<T, L, B, R>
+where
+    B: AsRef<[Slot<T>]>,
+    L: Borrow<crate::heap::Link<T, B, R>>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct(core::any::type_name::<Self>()).finish()
+    }
+}
+
+impl<W> fmt::Debug for crate::notify::Notify<W>
+where
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct(core::any::type_name::<Self>()).finish()
+    }
+}
+
+impl<T, B, W> fmt::Debug for crate::pool::Pool<T, B, W>
+where
+    B: AsRef<[Slot<T>]>,
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct(core::any::type_name::<Self>()).finish()
+    }
+}
+
+impl<T, B, W> fmt::Debug for crate::pool::PoolGuard<'_, T, B, W>
 where
-    L: Borrow<crate::mpmc::Link<T, B, TW, RW>>,
     B: AsRef<[Slot<T>]>,
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct(core::any::type_name::<Self>()).finish()
+    }
+}
+
+impl<Resp, P, W> fmt::Debug for crate::rpc::Replies<Resp, P, W>
+where
+    P: AsRef<[crate::rpc::ReplyLink<Resp>]>,
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct(core::any::type_name::<Self>()).finish()
+    }
+}
+
+impl<'r, Req, Resp, B, TW, RW, P, W> fmt::Debug
+    for crate::rpc::Client<'_, 'r, Req, Resp, B, TW, RW, P, W>
+where
+    B: AsRef<[Slot<crate::rpc::Request<'r, Req, Resp>>]>,
     TW: AsRef<[(AtomicBool, AtomicWaker)]>,
     RW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    P: AsRef<[crate::rpc::ReplyLink<Resp>]>,
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct(core::any::type_name::<Self>()).finish()
+    }
+}
+
+impl<'r, Req, Resp, B, TW, RW> fmt::Debug for crate::rpc::Server<'_, 'r, Req, Resp, B, TW, RW>
+where
+    B: AsRef<[Slot<crate::rpc::Request<'r, Req, Resp>>]>,
+    TW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    RW: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct(core::any::type_name::<Self>()).finish()
+    }
+}
+
+impl<Resp> fmt::Debug for crate::rpc::Responder<'_, Resp> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct(core::any::type_name::<Self>()).finish()
+    }
+}
+
+impl<W> fmt::Debug for crate::semaphore::Semaphore<W>
+where
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct(core::any::type_name::<Self>()).finish()
+    }
+}
+
+impl<W> fmt::Debug for crate::semaphore::Permit<'_, W>
+where
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct(core::any::type_name::<Self>()).finish()
+    }
+}
+
+impl<T, W> fmt::Debug for crate::sync::Mutex<T, W>
+where
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct(core::any::type_name::<Self>()).finish()
+    }
+}
+
+impl<T, W> fmt::Debug for crate::sync::MutexGuard<'_, T, W>
+where
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct(core::any::type_name::<Self>()).finish()
+    }
+}
+
+impl<T, W> fmt::Debug for crate::sync::RwLock<T, W>
+where
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct(core::any::type_name::<Self>()).finish()
+    }
+}
+
+impl<T, W> fmt::Debug for crate::sync::RwLockReadGuard<'_, T, W>
+where
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct(core::any::type_name::<Self>()).finish()
+    }
+}
+
+impl<T, W> fmt::Debug for crate::sync::RwLockWriteGuard<'_, T, W>
+where
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct(core::any::type_name::<Self>()).finish()
+    }
+}
+
+impl<W> fmt::Debug for crate::sync::Barrier<W>
+where
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct(core::any::type_name::<Self>()).finish()
+    }
+}
+
+impl<W> fmt::Debug for crate::sync::CountDownLatch<W>
+where
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct(core::any::type_name::<Self>()).finish()
     }
 }
+
+impl<W> fmt::Debug for crate::sync::WaitGroup<W>
+where
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct(core::any::type_name::<Self>()).finish()
+    }
+}
+
+impl<'a, S, W> fmt::Debug for crate::shutdown::Token<'a, S, W>
+where
+    S: AsRef<[crate::shutdown::Registration<'a>]>,
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct(core::any::type_name::<Self>()).finish()
+    }
+}
+
+impl fmt::Debug for crate::scope::Scope<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct(core::any::type_name::<Self>()).finish()
+    }
+}
+
+impl<L> fmt::Debug for crate::scope::Storage<L> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct(core::any::type_name::<Self>()).finish()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T, W, R> fmt::Debug for crate::unbounded::Link<T, W, R>
+where
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct(core::any::type_name::<Self>()).finish()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T, L, W, R> fmt::Debug for crate::unbounded::Tx<T, L, W, R>
+where
+    L: Borrow<crate::unbounded::Link<T, W, R>>,
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct(core::any::type_name::<Self>()).finish()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T, L, W, R> fmt::Debug for crate::unbounded::Rx<T, L, W, R>
+where
+    L: Borrow<crate::unbounded::Link<T, W, R>>,
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct(core::any::type_name::<Self>()).finish()
+    }
+}
+
+impl<A, B, BA, BB, R> fmt::Debug for crate::duplex::Link<A, B, BA, BB, R>
+where
+    BA: AsRef<[Slot<A>]>,
+    BB: AsRef<[Slot<B>]>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct(core::any::type_name::<Self>()).finish()
+    }
+}
+
+impl<A, B, L, BA, BB, S, R> fmt::Debug for crate::duplex::End<A, B, L, BA, BB, S, R>
+where
+    L: Borrow<crate::duplex::Link<A, B, BA, BB, R>>,
+    BA: AsRef<[Slot<A>]>,
+    BB: AsRef<[Slot<B>]>,
+    S: crate::duplex::Side<A, B, BA, BB, R>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct(core::any::type_name::<Self>()).finish()
+    }
+}
+
+impl<X, const N: usize> fmt::Debug for crate::select::Merge<X, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct(core::any::type_name::<Self>()).finish()
+    }
+}
+
+impl fmt::Debug for crate::route::RoundRobin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct(core::any::type_name::<Self>()).finish()
+    }
+}
+
+impl<F, S> fmt::Debug for crate::route::KeyHash<F, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct(core::any::type_name::<Self>()).finish()
+    }
+}
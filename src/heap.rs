@@ -0,0 +1,556 @@
+use core::borrow::Borrow;
+use core::convert::Infallible;
+use core::future;
+use core::marker::PhantomData;
+use core::sync::atomic::{AtomicUsize, Ordering};
+use core::task::{Context, Poll};
+
+use crate::atomic_waker::AtomicWaker;
+
+use crate::error::{RecvError, RecvErrorNoWait, SendError, SendErrorNoWait};
+use crate::reason::Reason;
+use crate::slot::Slot;
+use crate::utils;
+use crate::utils::AtomicUpdate;
+
+/// A medium through which [`Rx`] and [`Tx`] communicate.
+///
+/// The buffer is kept as a binary max-heap: [`Rx`] receives the greatest of the buffered values.
+/// Either side may close the link with a reason of type `R`.
+pub struct Link<T, B, R = ()>
+where
+    B: AsRef<[Slot<T>]>,
+{
+    /// 1bit — closed
+    /// 1bit — tx is set
+    /// 1bit — rx is set
+    /// 1bit — locked (either side is rearranging the heap)
+    ///
+    /// 28bit / 60bit — len
+    bits: AtomicUsize,
+
+    tx_waker: AtomicWaker,
+    rx_waker: AtomicWaker,
+
+    reason: Reason<R>,
+
+    _value: PhantomData<T>,
+
+    buffer: B,
+}
+
+/// The sending side of the channel
+pub struct Tx<T, L, B, R = ()>
+where
+    B: AsRef<[Slot<T>]>,
+    L: Borrow<Link<T, B, R>>,
+{
+    link: L,
+    _value: PhantomData<T>,
+    _buffer: PhantomData<B>,
+    _reason: PhantomData<R>,
+}
+
+/// The receiving side of the channel
+pub struct Rx<T, L, B, R = ()>
+where
+    B: AsRef<[Slot<T>]>,
+    L: Borrow<Link<T, B, R>>,
+{
+    link: L,
+    _value: PhantomData<T>,
+    _buffer: PhantomData<B>,
+    _reason: PhantomData<R>,
+}
+
+/// Keeps the heap locked, unlocks it setting the new length on drop.
+struct Locked<'a, T, B, R>
+where
+    B: AsRef<[Slot<T>]>,
+{
+    link: &'a Link<T, B, R>,
+    is_closed: bool,
+    len: usize,
+}
+
+impl<T, B, R> Link<T, B, R>
+where
+    B: AsRef<[Slot<T>]>,
+{
+    /// Creates a new ['Link`]
+    pub fn new(buffer: B) -> Self {
+        assert!(buffer.as_ref().len() <= bits::max_len());
+
+        Self {
+            buffer,
+            bits: Default::default(),
+            tx_waker: Default::default(),
+            rx_waker: Default::default(),
+            reason: Default::default(),
+            _value: Default::default(),
+        }
+    }
+}
+
+impl<T, L, B, R> Tx<T, L, B, R>
+where
+    B: AsRef<[Slot<T>]>,
+    L: Borrow<Link<T, B, R>>,
+{
+    /// Creates a new [`Tx`]
+    pub fn new(link: L) -> Self {
+        link.borrow().set_tx();
+        Self {
+            link,
+            _value: Default::default(),
+            _buffer: Default::default(),
+            _reason: Default::default(),
+        }
+    }
+
+    /// Sends a value if the channel is not full.
+    pub fn send_nowait(&mut self, value: T) -> Result<(), SendErrorNoWait<T, R>>
+    where
+        T: Ord,
+        R: Clone,
+    {
+        let link = self.link.borrow();
+        link.send_nowait(value).map_err(|e| e.with_reason(|| link.reason.get()))
+    }
+
+    /// Sends a value, waits if necessary.
+    pub async fn send(&mut self, value: T) -> Result<(), SendError<T, R>>
+    where
+        T: Ord,
+        R: Clone,
+    {
+        let mut value = Some(value);
+        let link = self.link.borrow();
+        future::poll_fn(|cx| link.poll_send(cx, &mut value)).await
+    }
+
+    /// Closes the channel.
+    pub fn close(&mut self) {
+        self.link.borrow().close(false, true)
+    }
+
+    /// Closes the channel with a reason.
+    ///
+    /// The reason is dropped if the channel is closed already.
+    pub fn close_with(&mut self, reason: R) {
+        self.link.borrow().close_with(reason, false, true)
+    }
+}
+
+impl<T, L, B, R> Rx<T, L, B, R>
+where
+    B: AsRef<[Slot<T>]>,
+    L: Borrow<Link<T, B, R>>,
+{
+    /// Creates a new [`Rx`]
+    pub fn new(link: L) -> Self {
+        link.borrow().set_rx();
+        Self {
+            link,
+            _value: Default::default(),
+            _buffer: Default::default(),
+            _reason: Default::default(),
+        }
+    }
+
+    /// Receives the greatest value if there is any.
+    pub fn recv_nowait(&mut self) -> Result<T, RecvErrorNoWait<R>>
+    where
+        T: Ord,
+        R: Clone,
+    {
+        let link = self.link.borrow();
+        link.recv_nowait().map_err(|e| e.with_reason(|| link.reason.get()))
+    }
+
+    /// Receives the greatest value, waits if necessary.
+    pub async fn recv(&mut self) -> Result<T, RecvError<R>>
+    where
+        T: Ord,
+        R: Clone,
+    {
+        let link = self.link.borrow();
+        future::poll_fn(|cx| link.poll_recv(cx)).await
+    }
+
+    /// Closes the channel.
+    pub fn close(&mut self) {
+        self.link.borrow().close(true, false)
+    }
+
+    /// Closes the channel with a reason.
+    ///
+    /// The reason is dropped if the channel is closed already.
+    pub fn close_with(&mut self, reason: R) {
+        self.link.borrow().close_with(reason, true, false)
+    }
+}
+
+impl<T, B, R> Link<T, B, R>
+where
+    T: Ord,
+    B: AsRef<[Slot<T>]>,
+    R: Clone,
+{
+    fn poll_recv(&self, cx: &mut Context) -> Poll<Result<T, RecvError<R>>> {
+        self.rx_waker.register(cx.waker());
+        match self.recv_nowait().map_err(|e| e.with_reason(|| self.reason.get())) {
+            Ok(value) => Poll::Ready(Ok(value)),
+            Err(RecvErrorNoWait::Closed) => Poll::Ready(Err(RecvError::closed())),
+            Err(RecvErrorNoWait::ClosedWith(reason)) =>
+                Poll::Ready(Err(RecvError::closed_with(reason))),
+            Err(RecvErrorNoWait::Empty) => Poll::Pending,
+        }
+    }
+
+    fn poll_send(
+        &self,
+        cx: &mut Context,
+        value: &mut Option<T>,
+    ) -> Poll<Result<(), SendError<T, R>>> {
+        self.tx_waker.register(cx.waker());
+        match self
+            .send_nowait(value.take().expect("stolen value"))
+            .map_err(|e| e.with_reason(|| self.reason.get()))
+        {
+            Ok(()) => Poll::Ready(Ok(())),
+            Err(SendErrorNoWait::Closed(rejected)) => Poll::Ready(Err(SendError::closed(rejected))),
+            Err(SendErrorNoWait::ClosedWith(rejected, reason)) =>
+                Poll::Ready(Err(SendError::closed_with(rejected, reason))),
+            Err(SendErrorNoWait::Rejected(rejected)) =>
+                Poll::Ready(Err(SendError::rejected(rejected))),
+            Err(SendErrorNoWait::Evicted(evicted)) => Poll::Ready(Err(SendError::evicted(evicted))),
+            Err(SendErrorNoWait::Full(rejected)) => {
+                *value = Some(rejected);
+                Poll::Pending
+            },
+        }
+    }
+
+    fn poll_closed(&self, cx: &mut Context) -> Poll<()> {
+        self.tx_waker.register(cx.waker());
+        if bits::is_closed::is_set(self.bits.load(Ordering::SeqCst)) {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+impl<T, B, R> Link<T, B, R>
+where
+    T: Ord,
+    B: AsRef<[Slot<T>]>,
+{
+    fn recv_nowait(&self) -> Result<T, RecvErrorNoWait> {
+        let mut locked = self.lock();
+        let buffer = self.buffer.as_ref();
+        let len = locked.len;
+
+        match (len == 0, locked.is_closed) {
+            (true, true) => Err(RecvErrorNoWait::closed()),
+            (true, false) => Err(RecvErrorNoWait::empty()),
+            (false, _) => {
+                unsafe { swap(buffer, 0, len - 1) };
+                let value = unsafe { buffer[len - 1].as_maybe_uninit_mut().assume_init_read() };
+                locked.len = len - 1;
+
+                sift_down(buffer, len - 1);
+                core::mem::drop(locked);
+
+                self.tx_waker.wake();
+                Ok(value)
+            },
+        }
+    }
+
+    fn send_nowait(&self, value: T) -> Result<(), SendErrorNoWait<T>> {
+        let mut locked = self.lock();
+        let buffer = self.buffer.as_ref();
+        let len = locked.len;
+
+        match (locked.is_closed, len == buffer.len()) {
+            (true, _) => Err(SendErrorNoWait::Closed(value)),
+            (false, true) => Err(SendErrorNoWait::Full(value)),
+            (false, false) => {
+                unsafe { buffer[len].as_maybe_uninit_mut() }.write(value);
+                locked.len = len + 1;
+
+                sift_up(buffer, len);
+                core::mem::drop(locked);
+
+                self.rx_waker.wake();
+                Ok(())
+            },
+        }
+    }
+}
+
+impl<T, B, R> Link<T, B, R>
+where
+    B: AsRef<[Slot<T>]>,
+{
+    /// Spins while the other side keeps the heap locked.
+    fn lock(&self) -> Locked<'_, T, B, R> {
+        loop {
+            let mut unlocked = 0;
+            match utils::compare_exchange_loop(
+                &self.bits,
+                self.max_iterations_for_atomic_update(),
+                None,
+                |old_bits| {
+                    if bits::is_locked::is_set(old_bits) {
+                        Ok::<_, Infallible>(AtomicUpdate::Retry)
+                    } else {
+                        unlocked = old_bits;
+                        Ok(AtomicUpdate::Set(bits::is_locked::set(old_bits)))
+                    }
+                },
+            ) {
+                Ok(_) =>
+                    return Locked {
+                        link: self,
+                        is_closed: bits::is_closed::is_set(unlocked),
+                        len: bits::len::get(unlocked),
+                    },
+                Err(_) => core::hint::spin_loop(),
+            }
+        }
+    }
+
+    fn unlock(&self, len: usize) {
+        utils::compare_exchange_loop(
+            &self.bits,
+            self.max_iterations_for_atomic_update(),
+            None,
+            |old_bits| {
+                Ok::<_, Infallible>(AtomicUpdate::Set(bits::is_locked::unset(bits::len::set(
+                    old_bits, len,
+                ))))
+            },
+        )
+        .expect("failed to perform atomic update");
+    }
+
+    fn close_with(&self, reason: R, notify_tx: bool, notify_rx: bool) {
+        if !bits::is_closed::is_set(self.bits.load(Ordering::SeqCst)) {
+            let _ = self.reason.set(reason);
+        }
+        self.close(notify_tx, notify_rx)
+    }
+
+    fn close(&self, notify_tx: bool, notify_rx: bool) {
+        utils::compare_exchange_loop(
+            &self.bits,
+            self.max_iterations_for_atomic_update(),
+            None,
+            |old_bits| Ok::<_, Infallible>(AtomicUpdate::Set(bits::is_closed::set(old_bits))),
+        )
+        .expect("failed to perform atomic update");
+
+        if notify_tx {
+            self.tx_waker.wake();
+        }
+        if notify_rx {
+            self.rx_waker.wake();
+        }
+    }
+
+    fn set_tx(&self) {
+        if let Err(err) = utils::compare_exchange_loop(
+            &self.bits,
+            self.max_iterations_for_atomic_update(),
+            None,
+            |old_bits| {
+                if bits::tx_is_set::is_set(old_bits) {
+                    Err("this link already has a Tx")
+                } else {
+                    Ok(AtomicUpdate::Set(bits::tx_is_set::set(old_bits)))
+                }
+            },
+        ) {
+            panic!("{}", err.unwrap_or("failed to perform atomic update"))
+        }
+    }
+    fn set_rx(&self) {
+        if let Err(err) = utils::compare_exchange_loop(
+            &self.bits,
+            self.max_iterations_for_atomic_update(),
+            None,
+            |old_bits| {
+                if bits::rx_is_set::is_set(old_bits) {
+                    Err("this link already has an Rx")
+                } else {
+                    Ok(AtomicUpdate::Set(bits::rx_is_set::set(old_bits)))
+                }
+            },
+        ) {
+            panic!("{}", err.unwrap_or("failed to perform atomic update"))
+        }
+    }
+
+    fn max_iterations_for_atomic_update(&self) -> usize {
+        utils::ATOMIC_UPDATE_MAX_ITERATIONS
+    }
+}
+
+fn sift_up<T: Ord>(buffer: &[Slot<T>], mut idx: usize) {
+    while idx > 0 {
+        let parent = (idx - 1) / 2;
+        if unsafe { get(buffer, idx) <= get(buffer, parent) } {
+            break
+        }
+        unsafe { swap(buffer, idx, parent) };
+        idx = parent;
+    }
+}
+
+fn sift_down<T: Ord>(buffer: &[Slot<T>], len: usize) {
+    let mut idx = 0;
+    loop {
+        let left = idx * 2 + 1;
+        let right = left + 1;
+        if left >= len {
+            break
+        }
+        let child = if right < len && unsafe { get(buffer, right) > get(buffer, left) } {
+            right
+        } else {
+            left
+        };
+        if unsafe { get(buffer, child) <= get(buffer, idx) } {
+            break
+        }
+        unsafe { swap(buffer, idx, child) };
+        idx = child;
+    }
+}
+
+unsafe fn get<T>(buffer: &[Slot<T>], idx: usize) -> &T {
+    unsafe { buffer[idx].as_maybe_uninit_mut().assume_init_ref() }
+}
+
+unsafe fn swap<T>(buffer: &[Slot<T>], a: usize, b: usize) {
+    if a != b {
+        unsafe { core::mem::swap(buffer[a].as_maybe_uninit_mut(), buffer[b].as_maybe_uninit_mut()) }
+    }
+}
+
+impl<T, B, R> Drop for Locked<'_, T, B, R>
+where
+    B: AsRef<[Slot<T>]>,
+{
+    fn drop(&mut self) {
+        self.link.unlock(self.len)
+    }
+}
+
+impl<T, B, R> Drop for Link<T, B, R>
+where
+    B: AsRef<[Slot<T>]>,
+{
+    fn drop(&mut self) {
+        let bits = self.bits.load(Ordering::SeqCst);
+
+        let is_closed = bits::is_closed::is_set(bits);
+        let tx_is_set = bits::tx_is_set::is_set(bits);
+        let rx_is_set = bits::rx_is_set::is_set(bits);
+
+        if !is_closed && (tx_is_set || rx_is_set) {
+            crate::leak::report::<Self>("Dropping unclosed Link")
+        }
+
+        let slots = self.buffer.as_ref();
+
+        for slot in &slots[..bits::len::get(bits)] {
+            unsafe {
+                slot.as_maybe_uninit_mut().assume_init_drop();
+            }
+        }
+    }
+}
+
+impl<T, L, B, R> Drop for Tx<T, L, B, R>
+where
+    B: AsRef<[Slot<T>]>,
+    L: Borrow<Link<T, B, R>>,
+{
+    fn drop(&mut self) {
+        self.link.borrow().close(/* notify_tx: */ false, /* notify_rx: */ true)
+    }
+}
+
+impl<T, L, B, R> Drop for Rx<T, L, B, R>
+where
+    B: AsRef<[Slot<T>]>,
+    L: Borrow<Link<T, B, R>>,
+{
+    fn drop(&mut self) {
+        self.link.borrow().close(/* notify_tx: */ true, /* notify_rx: */ false)
+    }
+}
+
+mod bits;
+
+impl<T, B, R> crate::shutdown::Close for Link<T, B, R>
+where
+    B: AsRef<[Slot<T>]>,
+{
+    fn close(&self) {
+        Link::close(self, true, true)
+    }
+}
+
+impl<T, L, B, R> crate::select::PollRecv for Rx<T, L, B, R>
+where
+    B: AsRef<[Slot<T>]>,
+    L: Borrow<Link<T, B, R>>,
+    T: Ord,
+    R: Clone,
+{
+    type Item = T;
+    type Reason = R;
+
+    fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Result<T, RecvError<R>>> {
+        let link = self.link.borrow();
+        link.poll_recv(cx)
+    }
+
+    fn close(&mut self) {
+        Rx::close(self)
+    }
+}
+
+impl<T, L, B, R> crate::route::PollSend for Tx<T, L, B, R>
+where
+    B: AsRef<[Slot<T>]>,
+    L: Borrow<Link<T, B, R>>,
+    T: Ord,
+    R: Clone,
+{
+    type Item = T;
+    type Reason = R;
+
+    fn poll_send(
+        &mut self,
+        cx: &mut Context<'_>,
+        value: &mut Option<T>,
+    ) -> Poll<Result<(), SendError<T, R>>> {
+        let link = self.link.borrow();
+        link.poll_send(cx, value)
+    }
+
+    fn poll_closed(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        let link = self.link.borrow();
+        link.poll_closed(cx)
+    }
+
+    fn close(&mut self) {
+        Tx::close(self)
+    }
+}
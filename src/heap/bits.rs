@@ -0,0 +1,124 @@
+use core::sync::atomic::AtomicUsize;
+
+use crate::utils;
+
+type Usize = <AtomicUsize as crate::utils::AtomicValue>::Value;
+
+const USIZE_BITS: u8 = Usize::BITS as u8;
+
+const POS_IS_CLOSED: u8 = 0;
+const POS_TX_IS_SET: u8 = 1;
+const POS_RX_IS_SET: u8 = 2;
+const POS_IS_LOCKED: u8 = 3;
+
+const FLAGS_COUNT: u8 = 4;
+
+const LEN_BIT_COUNT: u8 = USIZE_BITS - FLAGS_COUNT;
+
+const ONES: Usize = Usize::MAX;
+const MASK_LEN: Usize = !(ONES << LEN_BIT_COUNT);
+
+pub(super) fn max_len() -> Usize {
+    MASK_LEN
+}
+
+pub(super) mod is_closed {
+    use super::*;
+
+    pub fn is_set(bits: Usize) -> bool {
+        utils::bits::flag::<Usize, POS_IS_CLOSED>(bits) != 0
+    }
+
+    pub fn set(bits: Usize) -> Usize {
+        bits | utils::bits::flag::<Usize, POS_IS_CLOSED>(utils::bits::ones::<Usize>())
+    }
+}
+pub(super) mod tx_is_set {
+    use super::*;
+
+    pub fn is_set(bits: Usize) -> bool {
+        utils::bits::flag::<Usize, POS_TX_IS_SET>(bits) != 0
+    }
+
+    pub fn set(bits: Usize) -> Usize {
+        bits | utils::bits::flag::<Usize, POS_TX_IS_SET>(utils::bits::ones::<Usize>())
+    }
+}
+pub(super) mod rx_is_set {
+    use super::*;
+
+    pub fn is_set(bits: Usize) -> bool {
+        utils::bits::flag::<Usize, POS_RX_IS_SET>(bits) != 0
+    }
+
+    pub fn set(bits: Usize) -> Usize {
+        bits | utils::bits::flag::<Usize, POS_RX_IS_SET>(utils::bits::ones::<Usize>())
+    }
+}
+pub(super) mod is_locked {
+    use super::*;
+
+    pub fn is_set(bits: Usize) -> bool {
+        utils::bits::flag::<Usize, POS_IS_LOCKED>(bits) != 0
+    }
+
+    pub fn set(bits: Usize) -> Usize {
+        bits | utils::bits::flag::<Usize, POS_IS_LOCKED>(utils::bits::ones::<Usize>())
+    }
+
+    pub fn unset(bits: Usize) -> Usize {
+        bits & !utils::bits::flag::<Usize, POS_IS_LOCKED>(utils::bits::ones::<Usize>())
+    }
+}
+
+pub(super) mod len {
+    use super::*;
+
+    const START: u8 = FLAGS_COUNT;
+    const LEN: u8 = LEN_BIT_COUNT;
+
+    pub fn get(bits: Usize) -> Usize {
+        utils::bits::unpack::<Usize, START, LEN>(bits)
+    }
+    pub fn set(bits: Usize, len: Usize) -> Usize {
+        utils::bits::pack::<Usize, START, LEN>(bits, len)
+    }
+}
+
+#[test]
+fn test() {
+    const N: Usize = 0xFF;
+
+    for len in (0..N).chain((MASK_LEN - N)..=MASK_LEN) {
+        for closed in [true, false] {
+            for tx_is_set in [true, false] {
+                for rx_is_set in [true, false] {
+                    for is_locked in [true, false] {
+                        let bits = 0;
+
+                        let bits = if closed { is_closed::set(bits) } else { bits };
+
+                        let bits = if tx_is_set { tx_is_set::set(bits) } else { bits };
+
+                        let bits = if rx_is_set { rx_is_set::set(bits) } else { bits };
+
+                        let bits = if is_locked { is_locked::set(bits) } else { bits };
+
+                        let bits = len::set(bits, len);
+
+                        assert_eq!(closed, is_closed::is_set(bits));
+                        assert_eq!(tx_is_set, tx_is_set::is_set(bits));
+                        assert_eq!(rx_is_set, rx_is_set::is_set(bits));
+                        assert_eq!(is_locked, is_locked::is_set(bits));
+                        assert_eq!(len, len::get(bits));
+
+                        let bits = is_locked::unset(bits);
+                        assert!(!is_locked::is_set(bits));
+                        assert_eq!(closed, is_closed::is_set(bits));
+                        assert_eq!(len, len::get(bits));
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use core::sync::atomic::{AtomicPtr, AtomicU8, Ordering};
+
+static POLICY: AtomicU8 = AtomicU8::new(Policy::Panic as u8);
+static HOOK: AtomicPtr<()> = AtomicPtr::new(core::ptr::null_mut());
+
+/// What a link does when it is dropped while an endpoint is still attached to it.
+///
+/// That only happens if an endpoint has been leaked, e.g. with [`core::mem::forget`]: a leaked
+/// endpoint is never going to use the link again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[repr(u8)]
+pub enum Policy {
+    /// Panic.
+    #[default]
+    Panic = 0,
+
+    /// Panic in debug builds, close the link implicitly in release builds.
+    DebugPanic = 1,
+
+    /// Close the link implicitly and drop it as usual.
+    Close = 2,
+}
+
+/// Sets the [`Policy`] for all links.
+pub fn set_policy(policy: Policy) {
+    POLICY.store(policy as u8, Ordering::SeqCst);
+}
+
+/// The current [`Policy`].
+pub fn policy() -> Policy {
+    match POLICY.load(Ordering::SeqCst) {
+        0 => Policy::Panic,
+        1 => Policy::DebugPanic,
+        _ => Policy::Close,
+    }
+}
+
+/// Registers a hook called with the type name of a link dropped while still in use.
+///
+/// The hook is called before the [`Policy`] applies. `None` unregisters the hook.
+pub fn set_hook(hook: Option<fn(&'static str)>) {
+    HOOK.store(hook.map_or(core::ptr::null_mut(), |hook| hook as *mut ()), Ordering::SeqCst);
+}
+
+pub(crate) fn report<L>(message: &'static str) {
+    let hook = HOOK.load(Ordering::SeqCst);
+    if !hook.is_null() {
+        let hook = unsafe { core::mem::transmute::<*mut (), fn(&'static str)>(hook) };
+        hook(core::any::type_name::<L>());
+    }
+
+    match policy() {
+        Policy::Panic => panic!("{}", message),
+        Policy::DebugPanic => debug_assert!(false, "{}", message),
+        Policy::Close => (),
+    }
+}
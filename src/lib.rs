@@ -5,18 +5,54 @@
 
 /// Atomic Waker
 pub mod atomic_waker;
+/// Bidirectional channel made of two buffered links.
+pub mod duplex;
 /// Errors.
 pub mod error;
+/// Single producer single consumer priority-queue channel.
+pub mod heap;
+/// What links do when dropped while still in use.
+pub mod leak;
 /// Multiple producers multiple consumers buffered channel.
 pub mod mpmc;
+/// Multiple producers single consumer buffered channel.
+pub mod mpsc;
+/// Signalling without a payload.
+pub mod notify;
+/// Bounded pool of reusable objects.
+pub mod pool;
+/// Routing values between links.
+pub mod route;
+/// Request-response calls over an mpmc link.
+pub mod rpc;
+/// Links living in a scope.
+pub mod scope;
+/// Waiting on several receivers at once.
+pub mod select;
+/// Counting semaphore.
+pub mod semaphore;
+/// Closing groups of links at once.
+pub mod shutdown;
 /// Wrapper around unsafe-cell carrying a value.
 pub mod slot;
+/// Single producer multiple consumers buffered channel.
+pub mod spmc;
 /// Single producer single consumer channels
 pub mod spsc;
+/// Async locks and coordination primitives.
+pub mod sync;
+/// Multiple producers multiple consumers unbounded channel.
+#[cfg(feature = "alloc")]
+pub mod unbounded;
+
+pub use scope::scope;
 
 mod fmt;
+mod reason;
 mod send_sync;
 mod utils;
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
 #[cfg(feature = "std")]
 extern crate std;
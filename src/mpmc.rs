@@ -1,5 +1,4 @@
 use core::borrow::Borrow;
-use core::convert::Infallible;
 use core::future;
 use core::marker::PhantomData;
 use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
@@ -8,13 +7,42 @@ use core::task::{Context, Poll};
 use futures::task::AtomicWaker;
 
 use crate::error::{LimitReached, RecvError, RecvErrorNoWait, SendError, SendErrorNoWait};
+use crate::reason::Reason;
 use crate::slot::Slot;
-use crate::utils::{self, AtomicUpdate};
+use crate::utils;
+use crate::utils::AtomicUpdate;
+
+/// Multiple producers multiple consumers buffered channel with priority lanes.
+pub mod prio;
 
 mod bits;
+pub(crate) mod ring;
+pub(crate) mod wakers;
+
+/// What happens when sending into a full [`Link`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Overflow {
+    /// [`Tx::send_nowait`] fails with [`SendErrorNoWait::Full`], [`Tx::send`] waits.
+    #[default]
+    Block,
+
+    /// The value is rejected with [`SendErrorNoWait::Rejected`] and counted in
+    /// [`Rx::rejected`].
+    DropNewest,
+
+    /// The value is sent, the oldest value is taken out of the channel and handed back to the
+    /// sender as [`SendErrorNoWait::Evicted`]. Every evicted value is counted in [`Rx::evicted`].
+    ///
+    /// While the values in the channel are still being written by other [`Tx`]s, there is nothing
+    /// to evict: [`Tx::send_nowait`] fails with [`SendErrorNoWait::Full`], [`Tx::send`] waits.
+    DropOldest,
+}
 
 /// A medium through which [`Rx`] and [`Tx`] communicate.
-pub struct Link<T, B, TW, RW>
+///
+/// Any endpoint may close the link with a reason of type `R`. The link is closed when the last
+/// [`Tx`] is dropped. The receiving side is closed when the last [`Rx`] is dropped.
+pub struct Link<T, B, TW, RW, R = ()>
 where
     B: AsRef<[Slot<T>]>,
     TW: AsRef<[(AtomicBool, AtomicWaker)]>,
@@ -25,13 +53,23 @@ where
     buffer: B,
 
     refs: AtomicUsize,
+    txs: AtomicUsize,
+    rxs: AtomicUsize,
+
+    overflow: Overflow,
+    rejected: AtomicUsize,
+    evicted: AtomicUsize,
+
+    reason: Reason<R>,
 
     /// 1bit closed flag [0]
+    /// 1bit aborted flag [1]
+    /// 1bit rx-closed flag [2]
     /// four indexes (15/7bit):
-    /// - head-taken     [ 1..=15 / 1..=7  ]
-    /// - head-available [16..=30 / 8..=14 ]
-    /// - tail-taken     [31..=45 / 15..=21]
-    /// - tail-available [46..=61 / 22..=29]
+    /// - head-taken     [ 3..=17 / 3..=9  ]
+    /// - head-available [18..=32 / 10..=16]
+    /// - tail-taken     [33..=47 / 17..=23]
+    /// - tail-available [48..=62 / 24..=30]
     ///
     /// for 64bit usize max capacity — 32768-1
     /// for 32bit usize max capacity - 128-1
@@ -42,9 +80,9 @@ where
 }
 
 /// The sending side of the channel
-pub struct Tx<T, L, B, TW, RW>
+pub struct Tx<T, L, B, TW, RW, R = ()>
 where
-    L: Borrow<Link<T, B, TW, RW>>,
+    L: Borrow<Link<T, B, TW, RW, R>>,
     B: AsRef<[Slot<T>]>,
     TW: AsRef<[(AtomicBool, AtomicWaker)]>,
     RW: AsRef<[(AtomicBool, AtomicWaker)]>,
@@ -53,15 +91,35 @@ where
     _buffer: PhantomData<B>,
     _tx_wakers: PhantomData<TW>,
     _rx_waker: PhantomData<RW>,
+    _reason: PhantomData<R>,
 
     link: L,
     idx: usize,
 }
 
+/// A handle that can be upgraded to a [`Tx`] while the channel is open.
+///
+/// Unlike a [`Tx`], it takes no tx-waker and does not keep the channel open.
+pub struct WeakTx<T, L, B, TW, RW, R = ()>
+where
+    L: Borrow<Link<T, B, TW, RW, R>>,
+    B: AsRef<[Slot<T>]>,
+    TW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    RW: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    _value: PhantomData<T>,
+    _buffer: PhantomData<B>,
+    _tx_wakers: PhantomData<TW>,
+    _rx_waker: PhantomData<RW>,
+    _reason: PhantomData<R>,
+
+    link: L,
+}
+
 /// The receiving side of the channel
-pub struct Rx<T, L, B, TW, RW>
+pub struct Rx<T, L, B, TW, RW, R = ()>
 where
-    L: Borrow<Link<T, B, TW, RW>>,
+    L: Borrow<Link<T, B, TW, RW, R>>,
     B: AsRef<[Slot<T>]>,
     TW: AsRef<[(AtomicBool, AtomicWaker)]>,
     RW: AsRef<[(AtomicBool, AtomicWaker)]>,
@@ -70,14 +128,15 @@ where
     _buffer: PhantomData<B>,
     _tx_wakers: PhantomData<TW>,
     _rx_waker: PhantomData<RW>,
+    _reason: PhantomData<R>,
 
     link: L,
     idx: usize,
 }
 
-impl<T, L, B, TW, RW> Tx<T, L, B, TW, RW>
+impl<T, L, B, TW, RW, R> Tx<T, L, B, TW, RW, R>
 where
-    L: Borrow<Link<T, B, TW, RW>>,
+    L: Borrow<Link<T, B, TW, RW, R>>,
     B: AsRef<[Slot<T>]>,
     TW: AsRef<[(AtomicBool, AtomicWaker)]>,
     RW: AsRef<[(AtomicBool, AtomicWaker)]>,
@@ -85,15 +144,7 @@ where
     /// Creates a new [`Tx`]
     pub fn new(link: L) -> Self {
         let idx = link.borrow().try_attach_tx().expect("all tx-wakers are taken");
-
-        Self {
-            _value: Default::default(),
-            _buffer: Default::default(),
-            _tx_wakers: Default::default(),
-            _rx_waker: Default::default(),
-            link,
-            idx,
-        }
+        Self::attached(link, idx)
     }
 
     /// Try cloning this [`Tx`].
@@ -104,24 +155,38 @@ where
         L: Clone,
     {
         let idx = self.link.borrow().try_attach_tx().map_err(|()| LimitReached)?;
+        Ok(Self::attached(self.link.clone(), idx))
+    }
 
-        Ok(Self {
+    /// Makes a [`WeakTx`] for this channel.
+    pub fn downgrade(&self) -> WeakTx<T, L, B, TW, RW, R>
+    where
+        L: Clone,
+    {
+        WeakTx {
             _value: Default::default(),
             _buffer: Default::default(),
             _tx_wakers: Default::default(),
             _rx_waker: Default::default(),
+            _reason: Default::default(),
             link: self.link.clone(),
-            idx,
-        })
+        }
     }
 
     /// Sends a value if the channel is not full.
-    pub fn send_nowait(&mut self, value: T) -> Result<(), SendErrorNoWait<T>> {
-        self.link.borrow().send_nowait(value)
+    pub fn send_nowait(&mut self, value: T) -> Result<(), SendErrorNoWait<T, R>>
+    where
+        R: Clone,
+    {
+        let link = self.link.borrow();
+        link.send_nowait(value).map_err(|e| e.with_reason(|| link.reason.get()))
     }
 
     /// Sends a value, waits if necessary.
-    pub async fn send(&mut self, value: T) -> Result<(), SendError<T>> {
+    pub async fn send(&mut self, value: T) -> Result<(), SendError<T, R>>
+    where
+        R: Clone,
+    {
         let mut value = Some(value);
         let link = self.link.borrow();
         future::poll_fn(|cx| link.poll_send(cx, self.idx, &mut value)).await
@@ -131,11 +196,81 @@ where
     pub fn close(&mut self) {
         self.link.borrow().close()
     }
+
+    /// Closes the channel with a reason.
+    ///
+    /// The reason is dropped if the channel is closed already.
+    pub fn close_with(&mut self, reason: R) {
+        self.link.borrow().close_with(reason)
+    }
+
+    /// Closes the channel, dropping the values that have not been received yet.
+    pub fn abort(&mut self) {
+        self.link.borrow().abort()
+    }
+
+    /// Takes back the values that have not been received.
+    ///
+    /// Yields nothing until the receiving side is closed: the last [`Rx`] is dropped, or one is
+    /// closed with [`Rx::close_for_reclaim`]. Only then the values are surely not going to be
+    /// received.
+    pub fn reclaim(&mut self) -> impl Iterator<Item = T> + '_ {
+        let link = self.link.borrow();
+        core::iter::from_fn(move || link.reclaim())
+    }
+
+    fn attached(link: L, idx: usize) -> Self {
+        Self {
+            _value: Default::default(),
+            _buffer: Default::default(),
+            _tx_wakers: Default::default(),
+            _rx_waker: Default::default(),
+            _reason: Default::default(),
+            link,
+            idx,
+        }
+    }
+}
+
+impl<T, L, B, TW, RW, R> WeakTx<T, L, B, TW, RW, R>
+where
+    L: Borrow<Link<T, B, TW, RW, R>>,
+    B: AsRef<[Slot<T>]>,
+    TW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    RW: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    /// Makes a [`Tx`] unless the channel is closed or all tx-wakers are taken.
+    pub fn upgrade(&self) -> Option<Tx<T, L, B, TW, RW, R>>
+    where
+        L: Clone,
+    {
+        let idx = self.link.borrow().try_upgrade_tx()?;
+        Some(Tx::attached(self.link.clone(), idx))
+    }
 }
 
-impl<T, L, B, TW, RW> Rx<T, L, B, TW, RW>
+impl<T, L, B, TW, RW, R> Clone for WeakTx<T, L, B, TW, RW, R>
 where
-    L: Borrow<Link<T, B, TW, RW>>,
+    L: Borrow<Link<T, B, TW, RW, R>> + Clone,
+    B: AsRef<[Slot<T>]>,
+    TW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    RW: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn clone(&self) -> Self {
+        Self {
+            _value: Default::default(),
+            _buffer: Default::default(),
+            _tx_wakers: Default::default(),
+            _rx_waker: Default::default(),
+            _reason: Default::default(),
+            link: self.link.clone(),
+        }
+    }
+}
+
+impl<T, L, B, TW, RW, R> Rx<T, L, B, TW, RW, R>
+where
+    L: Borrow<Link<T, B, TW, RW, R>>,
     B: AsRef<[Slot<T>]>,
     TW: AsRef<[(AtomicBool, AtomicWaker)]>,
     RW: AsRef<[(AtomicBool, AtomicWaker)]>,
@@ -149,6 +284,7 @@ where
             _buffer: Default::default(),
             _tx_wakers: Default::default(),
             _rx_waker: Default::default(),
+            _reason: Default::default(),
             link,
             idx,
         }
@@ -168,29 +304,69 @@ where
             _buffer: Default::default(),
             _tx_wakers: Default::default(),
             _rx_waker: Default::default(),
+            _reason: Default::default(),
             link: self.link.clone(),
             idx,
         })
     }
 
     /// Receives a value if it is ready.
-    pub fn recv_nowait(&mut self) -> Result<T, RecvErrorNoWait> {
-        self.link.borrow().recv_nowait()
+    pub fn recv_nowait(&mut self) -> Result<T, RecvErrorNoWait<R>>
+    where
+        R: Clone,
+    {
+        let link = self.link.borrow();
+        link.recv_nowait().map_err(|e| e.with_reason(|| link.reason.get()))
     }
 
     /// Receives a value, waits if necessary.
-    pub async fn recv(&mut self) -> Result<T, RecvError> {
+    pub async fn recv(&mut self) -> Result<T, RecvError<R>>
+    where
+        R: Clone,
+    {
         let link = self.link.borrow();
         future::poll_fn(|cx| link.poll_recv(cx, self.idx)).await
     }
 
+    /// The number of values rejected by a [`Overflow::DropNewest`] link so far.
+    pub fn rejected(&self) -> usize {
+        self.link.borrow().rejected.load(Ordering::SeqCst)
+    }
+
+    /// The number of values evicted by a [`Overflow::DropOldest`] link so far.
+    ///
+    /// Counts the values handed back to the senders as well as the ones dropped when racing
+    /// [`Tx`]s made a send evict more than one value.
+    pub fn evicted(&self) -> usize {
+        self.link.borrow().evicted.load(Ordering::SeqCst)
+    }
+
     /// Closes the channel.
     pub fn close(&mut self) {
         self.link.borrow().close()
     }
+
+    /// Closes the channel with a reason.
+    ///
+    /// The reason is dropped if the channel is closed already.
+    pub fn close_with(&mut self, reason: R) {
+        self.link.borrow().close_with(reason)
+    }
+
+    /// Closes the channel and stops receiving, in all the [`Rx`]s.
+    ///
+    /// The values that have not been received yet can be taken back with [`Tx::reclaim`].
+    pub fn close_for_reclaim(&mut self) {
+        self.link.borrow().close_rx()
+    }
+
+    /// Closes the channel, dropping the values that have not been received yet.
+    pub fn abort(&mut self) {
+        self.link.borrow().abort()
+    }
 }
 
-impl<T, B, TW, RW> Link<T, B, TW, RW>
+impl<T, B, TW, RW, R> Link<T, B, TW, RW, R>
 where
     B: AsRef<[Slot<T>]>,
     TW: AsRef<[(AtomicBool, AtomicWaker)]>,
@@ -198,28 +374,68 @@ where
 {
     /// Creates a new [`Link`]
     pub fn new(buffer: B, tx_wakers: TW, rx_wakers: RW) -> Self {
+        Self::with_overflow(buffer, tx_wakers, rx_wakers, Overflow::Block)
+    }
+
+    /// Calls `f` with a [`Tx`] and an [`Rx`] of a link that lives for the duration of the call.
+    ///
+    /// More endpoints can be made with `try_clone`. The endpoints cannot outlive the call. An
+    /// endpoint leaked inside `f` is not reported to [`leak`](crate::leak) as it is detached
+    /// before the link is dropped.
+    pub fn scoped<F, O>(buffer: B, tx_wakers: TW, rx_wakers: RW, f: F) -> O
+    where
+        F: for<'a> FnOnce(Tx<T, &'a Self, B, TW, RW, R>, Rx<T, &'a Self, B, TW, RW, R>) -> O,
+    {
+        let scope = Scope(Self::with_overflow(buffer, tx_wakers, rx_wakers, Overflow::Block));
+        f(Tx::new(&scope.0), Rx::new(&scope.0))
+    }
+
+    /// Creates a new [`Link`] with the specified [`Overflow`] policy.
+    pub fn with_overflow(buffer: B, tx_wakers: TW, rx_wakers: RW, overflow: Overflow) -> Self {
         Self {
             _value: Default::default(),
             buffer,
             refs: Default::default(),
+            txs: Default::default(),
+            rxs: Default::default(),
+            overflow,
+            rejected: Default::default(),
+            evicted: Default::default(),
+            reason: Default::default(),
             bits: Default::default(),
             tx_wakers,
             rx_wakers,
         }
     }
+
+    /// Closes the link and detaches the endpoints that are still attached to it (leaked ones).
+    pub(crate) fn detach_all(&mut self) {
+        if *self.refs.get_mut() != 0 {
+            for (taken, _) in self.tx_wakers.as_ref().iter().chain(self.rx_wakers.as_ref()) {
+                taken.store(false, Ordering::SeqCst);
+            }
+            *self.refs.get_mut() = 0;
+            *self.txs.get_mut() = 0;
+            *self.rxs.get_mut() = 0;
+            self.close_rx();
+        }
+    }
 }
 
-impl<T, B, TW, RW> Link<T, B, TW, RW>
+impl<T, B, TW, RW, R> Link<T, B, TW, RW, R>
 where
     B: AsRef<[Slot<T>]>,
     TW: AsRef<[(AtomicBool, AtomicWaker)]>,
     RW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    R: Clone,
 {
-    fn poll_recv(&self, cx: &mut Context, idx: usize) -> Poll<Result<T, RecvError>> {
+    fn poll_recv(&self, cx: &mut Context, idx: usize) -> Poll<Result<T, RecvError<R>>> {
         self.rx_wakers.as_ref()[idx].1.register(cx.waker());
-        match self.recv_nowait() {
+        match self.recv_nowait().map_err(|e| e.with_reason(|| self.reason.get())) {
             Ok(value) => Poll::Ready(Ok(value)),
             Err(RecvErrorNoWait::Closed) => Poll::Ready(Err(RecvError::closed())),
+            Err(RecvErrorNoWait::ClosedWith(reason)) =>
+                Poll::Ready(Err(RecvError::closed_with(reason))),
             Err(RecvErrorNoWait::Empty) => Poll::Pending,
         }
     }
@@ -229,11 +445,19 @@ where
         cx: &mut Context,
         idx: usize,
         value: &mut Option<T>,
-    ) -> Poll<Result<(), SendError<T>>> {
+    ) -> Poll<Result<(), SendError<T, R>>> {
         self.tx_wakers.as_ref()[idx].1.register(cx.waker());
-        match self.send_nowait(value.take().expect("stolen value")) {
+        match self
+            .send_nowait(value.take().expect("stolen value"))
+            .map_err(|e| e.with_reason(|| self.reason.get()))
+        {
             Ok(()) => Poll::Ready(Ok(())),
             Err(SendErrorNoWait::Closed(rejected)) => Poll::Ready(Err(SendError::closed(rejected))),
+            Err(SendErrorNoWait::ClosedWith(rejected, reason)) =>
+                Poll::Ready(Err(SendError::closed_with(rejected, reason))),
+            Err(SendErrorNoWait::Rejected(rejected)) =>
+                Poll::Ready(Err(SendError::rejected(rejected))),
+            Err(SendErrorNoWait::Evicted(evicted)) => Poll::Ready(Err(SendError::evicted(evicted))),
             Err(SendErrorNoWait::Full(rejected)) => {
                 *value = Some(rejected);
                 Poll::Pending
@@ -241,197 +465,211 @@ where
         }
     }
 
+    fn poll_closed(&self, cx: &mut Context, idx: usize) -> Poll<()> {
+        self.tx_wakers.as_ref()[idx].1.register(cx.waker());
+        if bits::is_closed(self.bits.load(Ordering::SeqCst)) {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+impl<T, B, TW, RW, R> Link<T, B, TW, RW, R>
+where
+    B: AsRef<[Slot<T>]>,
+    TW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    RW: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
     fn send_nowait(&self, value: T) -> Result<(), SendErrorNoWait<T>> {
-        let buffer = self.buffer.as_ref();
-        let buffer_len = buffer.len();
-
-        let (tail_this, tail_next) = {
-            let mut output = None;
-
-            match utils::compare_exchange_loop(
-                &self.bits,
-                self.max_iterations_for_atomic_update(),
-                None,
-                |bits| {
-                    let head_avail = bits::head_avail(bits);
-                    let tail_taken = bits::tail_taken(bits);
-                    let tail_taken_next = (tail_taken + 1) % buffer_len;
-                    let tail_if_full = (head_avail + buffer_len - 1) % buffer_len;
-
-                    let is_full = tail_taken == tail_if_full;
-                    let is_closed = bits::is_closed(bits);
-
-                    match (is_closed, is_full) {
-                        (true, _) => Err(SendErrorNoWait::closed(())),
-                        (false, true) => Err(SendErrorNoWait::full(())),
-                        (false, false) => {
-                            output = Some((tail_taken, tail_taken_next));
-                            let new_bits = bits::set_tail_taken(bits, tail_taken_next);
-                            Ok(AtomicUpdate::Set(new_bits))
-                        },
-                    }
+        match (self.overflow, self.try_send(value)) {
+            (Overflow::DropNewest, Err(SendErrorNoWait::Full(rejected))) => {
+                self.rejected.fetch_add(1, Ordering::SeqCst);
+                Err(SendErrorNoWait::rejected(rejected))
+            },
+            (Overflow::DropOldest, Err(SendErrorNoWait::Full(value))) => self.send_evicting(value),
+            (_, result) => result,
+        }
+    }
+
+    /// Takes the oldest value out of the channel until there is room for the new one.
+    ///
+    /// Other [`Tx`]s may take the freed slot first, in which case more than one value gets
+    /// evicted: the first one is handed back, the rest are dropped. All of them are counted.
+    /// Fails with [`SendErrorNoWait::Full`] rather than waiting when there is nothing to evict.
+    fn send_evicting(&self, mut value: T) -> Result<(), SendErrorNoWait<T>> {
+        let mut evicted = None;
+
+        loop {
+            let is_empty = match self.recv_nowait() {
+                Ok(oldest) => {
+                    self.evicted.fetch_add(1, Ordering::SeqCst);
+                    evicted.get_or_insert(oldest);
+                    false
                 },
-            ) {
-                Ok(_) => output.unwrap(),
-                Err(None) => panic!("Failed to perform atomic update"),
-                Err(Some(e)) => return Err(e.map_value(value)),
+                Err(RecvErrorNoWait::Closed | RecvErrorNoWait::ClosedWith(())) =>
+                    return Err(SendErrorNoWait::closed(value)),
+                Err(RecvErrorNoWait::Empty) => true,
+            };
+
+            match self.try_send(value) {
+                Ok(()) => return evicted.map_or(Ok(()), |e| Err(SendErrorNoWait::evicted(e))),
+                Err(SendErrorNoWait::Full(rejected)) if !is_empty => value = rejected,
+                Err(err) => return Err(err),
             }
-        };
-
-        unsafe { buffer[tail_this].as_maybe_uninit_mut() }.write(value);
-
-        utils::compare_exchange_loop(
-            &self.bits,
-            self.max_iterations_for_atomic_update(),
-            None,
-            |old_bits| {
-                if bits::tail_avail(old_bits) == tail_this {
-                    let new_bits = bits::set_tail_avail(old_bits, tail_next);
-                    Ok::<_, Infallible>(AtomicUpdate::Set(new_bits))
-                } else {
-                    Ok::<_, Infallible>(AtomicUpdate::Retry)
-                }
-            },
-        )
-        .expect("Failed to perform atomic update");
+        }
+    }
 
+    fn try_send(&self, value: T) -> Result<(), SendErrorNoWait<T>> {
+        ring::send_nowait(&self.bits, self.buffer.as_ref(), value)?;
         self.notify_rxs();
-
         Ok(())
     }
 
     fn recv_nowait(&self) -> Result<T, RecvErrorNoWait> {
-        let buffer = self.buffer.as_ref();
-        let buffer_len = buffer.len();
-
-        let (head_this, head_next) = {
-            let mut output = None;
-
-            match utils::compare_exchange_loop(
-                &self.bits,
-                self.max_iterations_for_atomic_update(),
-                None,
-                |bits| {
-                    let head_taken = bits::head_taken(bits);
-                    let tail_avail = bits::tail_avail(bits);
-                    let head_taken_next = (head_taken + 1) % buffer_len;
-                    let is_empty = tail_avail == head_taken;
-                    let is_closed = bits::is_closed(bits);
-
-                    match (is_empty, is_closed) {
-                        (true, true) => Err(RecvErrorNoWait::closed()),
-                        (true, false) => Err(RecvErrorNoWait::empty()),
-                        (false, _) => {
-                            output = Some((head_taken, head_taken_next));
-                            let new_bits = bits::set_head_taken(bits, head_taken_next);
-                            Ok(AtomicUpdate::Set(new_bits))
-                        },
-                    }
-                },
-            ) {
-                Ok(_) => output.unwrap(),
-                Err(None) => panic!("Failed to perform atomic update"),
-                Err(Some(e)) => return Err(e),
-            }
-        };
-
-        let value = unsafe { buffer[head_this].as_maybe_uninit_mut().assume_init_read() };
-
-        utils::compare_exchange_loop(
-            &self.bits,
-            self.max_iterations_for_atomic_update(),
-            None,
-            |old_bits| {
-                if bits::head_avail(old_bits) == head_this {
-                    let new_bits = bits::set_head_avail(old_bits, head_next);
-                    Ok::<_, Infallible>(AtomicUpdate::Set(new_bits))
-                } else {
-                    Ok::<_, Infallible>(AtomicUpdate::Retry)
-                }
-            },
-        )
-        .expect("Failed to perform atomic update");
+        let bits = self.bits.load(Ordering::SeqCst);
+        if bits::is_rx_closed(bits) {
+            return Err(RecvErrorNoWait::closed())
+        }
+        if bits::is_aborted(bits) {
+            self.discard();
+            return Err(RecvErrorNoWait::closed())
+        }
 
+        let value = ring::recv_nowait(&self.bits, self.buffer.as_ref())?;
         self.notify_txs();
-
         Ok(value)
     }
 
+    pub(crate) fn reclaim(&self) -> Option<T> {
+        if bits::is_rx_closed(self.bits.load(Ordering::SeqCst)) {
+            ring::recv_nowait(&self.bits, self.buffer.as_ref()).ok()
+        } else {
+            None
+        }
+    }
+
     fn try_attach_tx(&self) -> Result<usize, ()> {
-        self.try_attach(self.tx_wakers.as_ref())
+        let idx = wakers::try_attach(&self.refs, self.tx_wakers.as_ref())?;
+        self.txs.fetch_add(1, Ordering::SeqCst);
+        Ok(idx)
     }
     fn try_attach_rx(&self) -> Result<usize, ()> {
-        self.try_attach(self.rx_wakers.as_ref())
+        let idx = wakers::try_attach(&self.refs, self.rx_wakers.as_ref())?;
+        self.rxs.fetch_add(1, Ordering::SeqCst);
+        Ok(idx)
     }
     fn detach_tx(&self, idx: usize) {
-        self.detach(self.tx_wakers.as_ref(), idx)
-    }
-    fn detach_rx(&self, idx: usize) {
-        self.detach(self.rx_wakers.as_ref(), idx)
+        if self.txs.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.close();
+        }
+        wakers::detach(&self.refs, self.tx_wakers.as_ref(), idx)
     }
 
-    fn try_attach(&self, wakers: &[(AtomicBool, AtomicWaker)]) -> Result<usize, ()> {
-        for (idx, (taken, _waker)) in wakers.iter().enumerate() {
-            if !taken.swap(true, Ordering::SeqCst) {
-                self.ref_inc();
-                return Ok(idx)
+    /// Attaches a [`Tx`] only while another one is attached and the link is open.
+    fn try_upgrade_tx(&self) -> Option<usize> {
+        utils::compare_exchange_loop(&self.txs, utils::ATOMIC_UPDATE_MAX_ITERATIONS, None, |txs| {
+            if txs == 0 {
+                Err(())
+            } else {
+                Ok(AtomicUpdate::Set(txs + 1))
             }
-        }
-        Err(())
-    }
+        })
+        .ok()?;
 
-    fn detach(&self, wakers: &[(AtomicBool, AtomicWaker)], idx: usize) {
-        let (taken, _) = &wakers[idx];
-        if !taken.swap(false, Ordering::SeqCst) {
-            panic!("attempt to detach from unoccupied waker")
+        match wakers::try_attach(&self.refs, self.tx_wakers.as_ref()) {
+            Ok(idx) if !bits::is_closed(self.bits.load(Ordering::SeqCst)) => Some(idx),
+            Ok(idx) => {
+                self.detach_tx(idx);
+                None
+            },
+            Err(()) => {
+                if self.txs.fetch_sub(1, Ordering::SeqCst) == 1 {
+                    self.close();
+                }
+                None
+            },
         }
-        self.ref_dec();
     }
-
-    fn ref_inc(&self) {
-        if self.refs.fetch_add(1, Ordering::SeqCst) == usize::MAX {
-            panic!("ref-inc overflow")
+    fn detach_rx(&self, idx: usize) {
+        if self.rxs.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.close_rx();
         }
+        wakers::detach(&self.refs, self.rx_wakers.as_ref(), idx)
     }
-    fn ref_dec(&self) {
-        if self.refs.fetch_sub(1, Ordering::SeqCst) == 0 {
-            panic!("ref-dec overflow")
-        }
+
+    /// Whether an [`Rx`] is attached.
+    pub(crate) fn has_rxs(&self) -> bool {
+        self.rxs.load(Ordering::SeqCst) != 0
     }
 
     fn notify_rxs(&self) {
-        Self::notify(self.rx_wakers.as_ref());
+        wakers::notify(self.rx_wakers.as_ref());
     }
     fn notify_txs(&self) {
-        Self::notify(self.tx_wakers.as_ref());
+        wakers::notify(self.tx_wakers.as_ref());
     }
-    fn notify(wakers: &[(AtomicBool, AtomicWaker)]) {
-        for (_, waker) in wakers {
-            waker.wake();
+
+    fn close_with(&self, reason: R) {
+        if !bits::is_closed(self.bits.load(Ordering::SeqCst)) {
+            let _ = self.reason.set(reason);
         }
+        self.close()
     }
 
-    fn close(&self) {
-        utils::compare_exchange_loop(
-            &self.bits,
-            self.max_iterations_for_atomic_update(),
-            None,
-            |bits| Ok::<_, Infallible>(AtomicUpdate::Set(bits::set_closed(bits))),
-        )
-        .expect("failed to perform atomic update");
+    pub(crate) fn close(&self) {
+        ring::close(&self.bits);
 
         self.notify_txs();
         self.notify_rxs();
     }
 
-    fn max_iterations_for_atomic_update(&self) -> usize {
-        utils::ATOMIC_UPDATE_MAX_ITERATIONS
+    /// Closes the link, the [`Rx`]s stop receiving.
+    fn close_rx(&self) {
+        ring::close_rx(&self.bits);
+
+        self.notify_txs();
+        self.notify_rxs();
+    }
+
+    fn abort(&self) {
+        ring::abort(&self.bits);
+        self.discard();
+
+        self.notify_txs();
+        self.notify_rxs();
+    }
+
+    /// Drops the values in the buffer.
+    ///
+    /// A value a [`Tx`] was in the middle of sending during the abort is dropped on the next
+    /// attempt to receive, or along with the [`Link`].
+    fn discard(&self) {
+        while ring::recv_nowait(&self.bits, self.buffer.as_ref()).is_ok() {}
+    }
+}
+
+/// Detaches the leaked endpoints before the link is dropped, so that they go unreported.
+struct Scope<T, B, TW, RW, R>(Link<T, B, TW, RW, R>)
+where
+    B: AsRef<[Slot<T>]>,
+    TW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    RW: AsRef<[(AtomicBool, AtomicWaker)]>;
+
+impl<T, B, TW, RW, R> Drop for Scope<T, B, TW, RW, R>
+where
+    B: AsRef<[Slot<T>]>,
+    TW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    RW: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn drop(&mut self) {
+        self.0.detach_all();
     }
 }
 
-impl<T, L, B, TW, RW> Drop for Tx<T, L, B, TW, RW>
+impl<T, L, B, TW, RW, R> Drop for Tx<T, L, B, TW, RW, R>
 where
-    L: Borrow<Link<T, B, TW, RW>>,
+    L: Borrow<Link<T, B, TW, RW, R>>,
     B: AsRef<[Slot<T>]>,
     TW: AsRef<[(AtomicBool, AtomicWaker)]>,
     RW: AsRef<[(AtomicBool, AtomicWaker)]>,
@@ -441,9 +679,9 @@ where
     }
 }
 
-impl<T, L, B, TW, RW> Drop for Rx<T, L, B, TW, RW>
+impl<T, L, B, TW, RW, R> Drop for Rx<T, L, B, TW, RW, R>
 where
-    L: Borrow<Link<T, B, TW, RW>>,
+    L: Borrow<Link<T, B, TW, RW, R>>,
     B: AsRef<[Slot<T>]>,
     TW: AsRef<[(AtomicBool, AtomicWaker)]>,
     RW: AsRef<[(AtomicBool, AtomicWaker)]>,
@@ -453,7 +691,7 @@ where
     }
 }
 
-impl<T, B, TW, RW> Drop for Link<T, B, TW, RW>
+impl<T, B, TW, RW, R> Drop for Link<T, B, TW, RW, R>
 where
     B: AsRef<[Slot<T>]>,
     TW: AsRef<[(AtomicBool, AtomicWaker)]>,
@@ -462,25 +700,71 @@ where
     fn drop(&mut self) {
         let refs = self.refs.load(Ordering::SeqCst);
         if refs != 0 {
-            panic!("Dropping Link that is still referenced?")
+            crate::leak::report::<Self>("Dropping Link that is still referenced?")
         }
 
-        let bits = self.bits.load(Ordering::SeqCst);
-        let mut head = bits::head_avail(bits);
-        let tail = bits::tail_avail(bits);
-        assert_eq!(head, bits::head_taken(bits));
-        assert_eq!(tail, bits::tail_taken(bits));
+        ring::drop_values(&self.bits, self.buffer.as_ref());
+    }
+}
 
-        let buffer = self.buffer.as_ref();
-        let buffer_len = buffer.len();
+impl<T, B, TW, RW, R> crate::shutdown::Close for Link<T, B, TW, RW, R>
+where
+    B: AsRef<[Slot<T>]>,
+    TW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    RW: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn close(&self) {
+        Link::close(self)
+    }
+}
 
-        while head != tail {
-            unsafe {
-                buffer[head].as_maybe_uninit_mut().assume_init_drop();
-            }
+impl<T, L, B, TW, RW, R> crate::select::PollRecv for Rx<T, L, B, TW, RW, R>
+where
+    L: Borrow<Link<T, B, TW, RW, R>>,
+    B: AsRef<[Slot<T>]>,
+    TW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    RW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    R: Clone,
+{
+    type Item = T;
+    type Reason = R;
 
-            head += 1;
-            head %= buffer_len;
-        }
+    fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Result<T, RecvError<R>>> {
+        let link = self.link.borrow();
+        link.poll_recv(cx, self.idx)
+    }
+
+    fn close(&mut self) {
+        Rx::close(self)
+    }
+}
+
+impl<T, L, B, TW, RW, R> crate::route::PollSend for Tx<T, L, B, TW, RW, R>
+where
+    L: Borrow<Link<T, B, TW, RW, R>>,
+    B: AsRef<[Slot<T>]>,
+    TW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    RW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    R: Clone,
+{
+    type Item = T;
+    type Reason = R;
+
+    fn poll_send(
+        &mut self,
+        cx: &mut Context<'_>,
+        value: &mut Option<T>,
+    ) -> Poll<Result<(), SendError<T, R>>> {
+        let link = self.link.borrow();
+        link.poll_send(cx, self.idx, value)
+    }
+
+    fn poll_closed(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        let link = self.link.borrow();
+        link.poll_closed(cx, self.idx)
+    }
+
+    fn close(&mut self) {
+        Tx::close(self)
     }
 }
@@ -3,7 +3,9 @@ use core::sync::atomic::AtomicUsize;
 use crate::utils;
 
 const POS_IS_CLOSED: u8 = 0;
-const FLAGS_COUNT: u8 = 1;
+const POS_IS_ABORTED: u8 = 1;
+const POS_IS_RX_CLOSED: u8 = 2;
+const FLAGS_COUNT: u8 = 3;
 
 type Usize = <AtomicUsize as crate::utils::AtomicValue>::Value;
 const USIZE_BITS: u8 = Usize::BITS as u8;
@@ -21,6 +23,20 @@ pub(super) fn set_closed(bits: Usize) -> Usize {
     bits | utils::bits::flag::<Usize, POS_IS_CLOSED>(utils::bits::ones())
 }
 
+pub(super) fn is_aborted(bits: Usize) -> bool {
+    utils::bits::flag::<Usize, POS_IS_ABORTED>(bits) != 0
+}
+pub(super) fn set_aborted(bits: Usize) -> Usize {
+    bits | utils::bits::flag::<Usize, POS_IS_ABORTED>(utils::bits::ones())
+}
+
+pub(super) fn is_rx_closed(bits: Usize) -> bool {
+    utils::bits::flag::<Usize, POS_IS_RX_CLOSED>(bits) != 0
+}
+pub(super) fn set_rx_closed(bits: Usize) -> Usize {
+    bits | utils::bits::flag::<Usize, POS_IS_RX_CLOSED>(utils::bits::ones())
+}
+
 pub(super) fn head_taken(bits: Usize) -> Usize {
     utils::bits::unpack::<Usize, START_HEAD_TAKEN, INDEX_BIT_COUNT>(bits)
 }
@@ -0,0 +1,473 @@
+use core::borrow::Borrow;
+use core::future;
+use core::marker::PhantomData;
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use core::task::{Context, Poll};
+
+use crate::atomic_waker::AtomicWaker;
+use crate::error::{
+    LimitReached, NoSuchLane, RecvError, RecvErrorNoWait, SendError, SendErrorNoWait,
+};
+use crate::reason::Reason;
+use crate::slot::Slot;
+
+use super::{ring, wakers};
+
+/// A medium through which [`Rx`] and [`Tx`] communicate.
+///
+/// The values are sent into one of the `K` lanes, each lane has its own buffer. The lane with
+/// the greater index has the greater priority. Any endpoint may close the link with a reason of
+/// type `R`. The link is closed when the last [`Tx`] is dropped.
+pub struct Link<T, B, TW, RW, const K: usize, R = ()>
+where
+    B: AsRef<[Slot<T>]>,
+    TW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    RW: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    _value: PhantomData<T>,
+
+    buffers: [B; K],
+
+    refs: AtomicUsize,
+    txs: AtomicUsize,
+
+    /// set before the lanes get closed one by one: no value gets into a lane once a send has
+    /// seen the link closed
+    is_closed: AtomicBool,
+
+    /// a bits-word per lane, laid out the same way as in [`mpmc::Link`](super::Link)
+    bits: [AtomicUsize; K],
+
+    reason: Reason<R>,
+
+    tx_wakers: TW,
+    rx_wakers: RW,
+}
+
+/// One of the `K` lanes of a [`Link`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Lane<const K: usize>(usize);
+
+/// The sending side of the channel
+pub struct Tx<T, L, B, TW, RW, const K: usize, R = ()>
+where
+    L: Borrow<Link<T, B, TW, RW, K, R>>,
+    B: AsRef<[Slot<T>]>,
+    TW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    RW: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    _value: PhantomData<T>,
+    _buffer: PhantomData<B>,
+    _tx_wakers: PhantomData<TW>,
+    _rx_waker: PhantomData<RW>,
+    _reason: PhantomData<R>,
+
+    link: L,
+    idx: usize,
+}
+
+/// The receiving side of the channel
+pub struct Rx<T, L, B, TW, RW, const K: usize, R = ()>
+where
+    L: Borrow<Link<T, B, TW, RW, K, R>>,
+    B: AsRef<[Slot<T>]>,
+    TW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    RW: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    _value: PhantomData<T>,
+    _buffer: PhantomData<B>,
+    _tx_wakers: PhantomData<TW>,
+    _rx_waker: PhantomData<RW>,
+    _reason: PhantomData<R>,
+
+    link: L,
+    idx: usize,
+}
+
+impl<const K: usize> Lane<K> {
+    /// The lane with the lowest priority.
+    pub const LOWEST: Self = Self(0);
+
+    /// The lane with the highest priority.
+    pub const HIGHEST: Self = Self(K - 1);
+
+    /// The lane at `index`, fails unless `index < K`.
+    pub fn new(index: usize) -> Result<Self, NoSuchLane> {
+        if index < K {
+            Ok(Self(index))
+        } else {
+            Err(NoSuchLane)
+        }
+    }
+
+    /// The index of the lane.
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+impl<T, L, B, TW, RW, const K: usize, R> Tx<T, L, B, TW, RW, K, R>
+where
+    L: Borrow<Link<T, B, TW, RW, K, R>>,
+    B: AsRef<[Slot<T>]>,
+    TW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    RW: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    /// Creates a new [`Tx`]
+    pub fn new(link: L) -> Self {
+        let idx = link.borrow().try_attach_tx().expect("all tx-wakers are taken");
+
+        Self {
+            _value: Default::default(),
+            _buffer: Default::default(),
+            _tx_wakers: Default::default(),
+            _rx_waker: Default::default(),
+            _reason: Default::default(),
+            link,
+            idx,
+        }
+    }
+
+    /// Try cloning this [`Tx`].
+    ///
+    /// Fails when all wakers are taken.
+    pub fn try_clone(&self) -> Result<Self, LimitReached>
+    where
+        L: Clone,
+    {
+        let idx = self.link.borrow().try_attach_tx().map_err(|()| LimitReached)?;
+
+        Ok(Self {
+            _value: Default::default(),
+            _buffer: Default::default(),
+            _tx_wakers: Default::default(),
+            _rx_waker: Default::default(),
+            _reason: Default::default(),
+            link: self.link.clone(),
+            idx,
+        })
+    }
+
+    /// Sends a value into the specified lane if that lane is not full.
+    pub fn send_nowait(&mut self, lane: Lane<K>, value: T) -> Result<(), SendErrorNoWait<T, R>>
+    where
+        R: Clone,
+    {
+        let link = self.link.borrow();
+        link.send_nowait(lane, value).map_err(|e| e.with_reason(|| link.reason.get()))
+    }
+
+    /// Sends a value into the specified lane, waits if necessary.
+    pub async fn send(&mut self, lane: Lane<K>, value: T) -> Result<(), SendError<T, R>>
+    where
+        R: Clone,
+    {
+        let mut value = Some(value);
+        let link = self.link.borrow();
+        future::poll_fn(|cx| link.poll_send(cx, self.idx, lane, &mut value)).await
+    }
+
+    /// Closes the channel.
+    pub fn close(&mut self) {
+        self.link.borrow().close()
+    }
+
+    /// Closes the channel with a reason.
+    ///
+    /// The reason is dropped if the channel is closed already.
+    pub fn close_with(&mut self, reason: R) {
+        self.link.borrow().close_with(reason)
+    }
+}
+
+impl<T, L, B, TW, RW, const K: usize, R> Rx<T, L, B, TW, RW, K, R>
+where
+    L: Borrow<Link<T, B, TW, RW, K, R>>,
+    B: AsRef<[Slot<T>]>,
+    TW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    RW: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    /// Creates a new [`Rx`]
+    pub fn new(link: L) -> Self {
+        let idx = link.borrow().try_attach_rx().expect("all rx-wakers are taken");
+
+        Self {
+            _value: Default::default(),
+            _buffer: Default::default(),
+            _tx_wakers: Default::default(),
+            _rx_waker: Default::default(),
+            _reason: Default::default(),
+            link,
+            idx,
+        }
+    }
+
+    /// Try cloning this [`Rx`].
+    ///
+    /// Fails when all wakers are taken.
+    pub fn try_clone(&self) -> Result<Self, LimitReached>
+    where
+        L: Clone,
+    {
+        let idx = self.link.borrow().try_attach_rx().map_err(|()| LimitReached)?;
+
+        Ok(Self {
+            _value: Default::default(),
+            _buffer: Default::default(),
+            _tx_wakers: Default::default(),
+            _rx_waker: Default::default(),
+            _reason: Default::default(),
+            link: self.link.clone(),
+            idx,
+        })
+    }
+
+    /// Receives a value from the highest non-empty lane if there is one.
+    pub fn recv_nowait(&mut self) -> Result<T, RecvErrorNoWait<R>>
+    where
+        R: Clone,
+    {
+        let link = self.link.borrow();
+        link.recv_nowait().map_err(|e| e.with_reason(|| link.reason.get()))
+    }
+
+    /// Receives a value from the highest non-empty lane, waits if necessary.
+    pub async fn recv(&mut self) -> Result<T, RecvError<R>>
+    where
+        R: Clone,
+    {
+        let link = self.link.borrow();
+        future::poll_fn(|cx| link.poll_recv(cx, self.idx)).await
+    }
+
+    /// Closes the channel.
+    pub fn close(&mut self) {
+        self.link.borrow().close()
+    }
+
+    /// Closes the channel with a reason.
+    ///
+    /// The reason is dropped if the channel is closed already.
+    pub fn close_with(&mut self, reason: R) {
+        self.link.borrow().close_with(reason)
+    }
+}
+
+impl<T, B, TW, RW, const K: usize, R> Link<T, B, TW, RW, K, R>
+where
+    B: AsRef<[Slot<T>]>,
+    TW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    RW: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    /// Creates a new [`Link`]
+    pub fn new(buffers: [B; K], tx_wakers: TW, rx_wakers: RW) -> Self {
+        assert!(K > 0, "a Link needs at least one lane");
+
+        Self {
+            _value: Default::default(),
+            buffers,
+            refs: Default::default(),
+            txs: Default::default(),
+            is_closed: Default::default(),
+            bits: core::array::from_fn(|_| Default::default()),
+            reason: Default::default(),
+            tx_wakers,
+            rx_wakers,
+        }
+    }
+}
+
+impl<T, B, TW, RW, const K: usize, R> Link<T, B, TW, RW, K, R>
+where
+    B: AsRef<[Slot<T>]>,
+    TW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    RW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    R: Clone,
+{
+    fn poll_recv(&self, cx: &mut Context, idx: usize) -> Poll<Result<T, RecvError<R>>> {
+        self.rx_wakers.as_ref()[idx].1.register(cx.waker());
+        match self.recv_nowait().map_err(|e| e.with_reason(|| self.reason.get())) {
+            Ok(value) => Poll::Ready(Ok(value)),
+            Err(RecvErrorNoWait::Closed) => Poll::Ready(Err(RecvError::closed())),
+            Err(RecvErrorNoWait::ClosedWith(reason)) =>
+                Poll::Ready(Err(RecvError::closed_with(reason))),
+            Err(RecvErrorNoWait::Empty) => Poll::Pending,
+        }
+    }
+
+    fn poll_send(
+        &self,
+        cx: &mut Context,
+        idx: usize,
+        lane: Lane<K>,
+        value: &mut Option<T>,
+    ) -> Poll<Result<(), SendError<T, R>>> {
+        self.tx_wakers.as_ref()[idx].1.register(cx.waker());
+        match self
+            .send_nowait(lane, value.take().expect("stolen value"))
+            .map_err(|e| e.with_reason(|| self.reason.get()))
+        {
+            Ok(()) => Poll::Ready(Ok(())),
+            Err(SendErrorNoWait::Closed(rejected)) => Poll::Ready(Err(SendError::closed(rejected))),
+            Err(SendErrorNoWait::ClosedWith(rejected, reason)) =>
+                Poll::Ready(Err(SendError::closed_with(rejected, reason))),
+            Err(SendErrorNoWait::Rejected(rejected)) =>
+                Poll::Ready(Err(SendError::rejected(rejected))),
+            Err(SendErrorNoWait::Evicted(evicted)) => Poll::Ready(Err(SendError::evicted(evicted))),
+            Err(SendErrorNoWait::Full(rejected)) => {
+                *value = Some(rejected);
+                Poll::Pending
+            },
+        }
+    }
+}
+
+impl<T, B, TW, RW, const K: usize, R> Link<T, B, TW, RW, K, R>
+where
+    B: AsRef<[Slot<T>]>,
+    TW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    RW: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn send_nowait(&self, lane: Lane<K>, value: T) -> Result<(), SendErrorNoWait<T>> {
+        if self.is_closed.load(Ordering::SeqCst) {
+            return Err(SendErrorNoWait::closed(value))
+        }
+
+        ring::send_nowait(&self.bits[lane.0], self.buffers[lane.0].as_ref(), value)?;
+        self.notify_rxs();
+        Ok(())
+    }
+
+    fn recv_nowait(&self) -> Result<T, RecvErrorNoWait> {
+        let mut output = Err(RecvErrorNoWait::closed());
+
+        for (bits, buffer) in self.bits.iter().zip(&self.buffers).rev() {
+            match ring::recv_nowait(bits, buffer.as_ref()) {
+                Ok(value) => {
+                    self.notify_txs();
+                    return Ok(value)
+                },
+                Err(RecvErrorNoWait::Empty) => output = Err(RecvErrorNoWait::empty()),
+                Err(RecvErrorNoWait::Closed | RecvErrorNoWait::ClosedWith(())) => (),
+            }
+        }
+
+        output
+    }
+
+    fn try_attach_tx(&self) -> Result<usize, ()> {
+        let idx = wakers::try_attach(&self.refs, self.tx_wakers.as_ref())?;
+        self.txs.fetch_add(1, Ordering::SeqCst);
+        Ok(idx)
+    }
+    fn try_attach_rx(&self) -> Result<usize, ()> {
+        wakers::try_attach(&self.refs, self.rx_wakers.as_ref())
+    }
+    fn detach_tx(&self, idx: usize) {
+        if self.txs.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.close();
+        }
+        wakers::detach(&self.refs, self.tx_wakers.as_ref(), idx)
+    }
+    fn detach_rx(&self, idx: usize) {
+        wakers::detach(&self.refs, self.rx_wakers.as_ref(), idx)
+    }
+
+    fn notify_rxs(&self) {
+        wakers::notify(self.rx_wakers.as_ref());
+    }
+    fn notify_txs(&self) {
+        wakers::notify(self.tx_wakers.as_ref());
+    }
+
+    fn close_with(&self, reason: R) {
+        if !self.is_closed.load(Ordering::SeqCst) {
+            let _ = self.reason.set(reason);
+        }
+        self.close()
+    }
+
+    fn close(&self) {
+        self.is_closed.store(true, Ordering::SeqCst);
+        for bits in &self.bits {
+            ring::close(bits);
+        }
+
+        self.notify_txs();
+        self.notify_rxs();
+    }
+}
+
+impl<T, L, B, TW, RW, const K: usize, R> Drop for Tx<T, L, B, TW, RW, K, R>
+where
+    L: Borrow<Link<T, B, TW, RW, K, R>>,
+    B: AsRef<[Slot<T>]>,
+    TW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    RW: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn drop(&mut self) {
+        self.link.borrow().detach_tx(self.idx);
+    }
+}
+
+impl<T, L, B, TW, RW, const K: usize, R> Drop for Rx<T, L, B, TW, RW, K, R>
+where
+    L: Borrow<Link<T, B, TW, RW, K, R>>,
+    B: AsRef<[Slot<T>]>,
+    TW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    RW: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn drop(&mut self) {
+        self.link.borrow().detach_rx(self.idx);
+    }
+}
+
+impl<T, B, TW, RW, const K: usize, R> Drop for Link<T, B, TW, RW, K, R>
+where
+    B: AsRef<[Slot<T>]>,
+    TW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    RW: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn drop(&mut self) {
+        let refs = self.refs.load(Ordering::SeqCst);
+        if refs != 0 {
+            crate::leak::report::<Self>("Dropping Link that is still referenced?")
+        }
+
+        for (bits, buffer) in self.bits.iter().zip(&self.buffers) {
+            ring::drop_values(bits, buffer.as_ref());
+        }
+    }
+}
+
+impl<T, B, TW, RW, const K: usize, R> crate::shutdown::Close for Link<T, B, TW, RW, K, R>
+where
+    B: AsRef<[Slot<T>]>,
+    TW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    RW: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn close(&self) {
+        Link::close(self)
+    }
+}
+
+impl<T, L, B, TW, RW, const K: usize, R> crate::select::PollRecv for Rx<T, L, B, TW, RW, K, R>
+where
+    L: Borrow<Link<T, B, TW, RW, K, R>>,
+    B: AsRef<[Slot<T>]>,
+    TW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    RW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    R: Clone,
+{
+    type Item = T;
+    type Reason = R;
+
+    fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Result<T, RecvError<R>>> {
+        let link = self.link.borrow();
+        link.poll_recv(cx, self.idx)
+    }
+
+    fn close(&mut self) {
+        Rx::close(self)
+    }
+}
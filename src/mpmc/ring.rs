@@ -0,0 +1,155 @@
+use core::convert::Infallible;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::error::{RecvErrorNoWait, SendErrorNoWait};
+use crate::slot::Slot;
+use crate::utils::{self, AtomicUpdate};
+
+use super::bits;
+
+/// The cursors of an end of a ring shared by several parties.
+///
+/// A party first takes a slot by moving the "taken" cursor, then accesses the slot, then gives it
+/// to the other end by moving the "avail" cursor. The slots are given in the order they were taken.
+pub(crate) struct Cursors {
+    pub(crate) taken: fn(usize) -> usize,
+    pub(crate) set_taken: fn(usize, usize) -> usize,
+    pub(crate) avail: fn(usize) -> usize,
+    pub(crate) set_avail: fn(usize, usize) -> usize,
+}
+
+const TAIL: Cursors = Cursors {
+    taken: bits::tail_taken,
+    set_taken: bits::set_tail_taken,
+    avail: bits::tail_avail,
+    set_avail: bits::set_tail_avail,
+};
+
+const HEAD: Cursors = Cursors {
+    taken: bits::head_taken,
+    set_taken: bits::set_head_taken,
+    avail: bits::head_avail,
+    set_avail: bits::set_head_avail,
+};
+
+pub(crate) fn send_nowait<T>(
+    bits: &AtomicUsize,
+    buffer: &[Slot<T>],
+    value: T,
+) -> Result<(), SendErrorNoWait<T>> {
+    let buffer_len = buffer.len();
+
+    let tail_this = match take_slot(bits, buffer_len, &TAIL, |bits, tail_taken| {
+        let tail_if_full = (bits::head_avail(bits) + buffer_len - 1) % buffer_len;
+
+        match (bits::is_closed(bits), tail_taken == tail_if_full) {
+            (true, _) => Err(SendErrorNoWait::closed(())),
+            (false, true) => Err(SendErrorNoWait::full(())),
+            (false, false) => Ok(()),
+        }
+    }) {
+        Ok(tail_this) => tail_this,
+        Err(e) => return Err(e.map_value(value)),
+    };
+
+    unsafe { buffer[tail_this].as_maybe_uninit_mut() }.write(value);
+    give_slot(bits, buffer_len, &TAIL, tail_this);
+
+    Ok(())
+}
+
+pub(crate) fn recv_nowait<T>(bits: &AtomicUsize, buffer: &[Slot<T>]) -> Result<T, RecvErrorNoWait> {
+    let buffer_len = buffer.len();
+
+    let head_this = take_slot(bits, buffer_len, &HEAD, |bits, head_taken| {
+        match (bits::tail_avail(bits) == head_taken, bits::is_closed(bits)) {
+            (true, true) => Err(RecvErrorNoWait::closed()),
+            (true, false) => Err(RecvErrorNoWait::empty()),
+            (false, _) => Ok(()),
+        }
+    })?;
+
+    let value = unsafe { buffer[head_this].as_maybe_uninit_mut().assume_init_read() };
+    give_slot(bits, buffer_len, &HEAD, head_this);
+
+    Ok(value)
+}
+
+/// Takes the next slot at the end of the ring, unless `check` fails for the current bits and the
+/// "taken" cursor.
+pub(crate) fn take_slot<E>(
+    bits: &AtomicUsize,
+    buffer_len: usize,
+    cursors: &Cursors,
+    mut check: impl FnMut(usize, usize) -> Result<(), E>,
+) -> Result<usize, E> {
+    let mut output = None;
+
+    match utils::compare_exchange_loop(bits, utils::ATOMIC_UPDATE_MAX_ITERATIONS, None, |bits| {
+        let taken = (cursors.taken)(bits);
+        check(bits, taken)?;
+
+        output = Some(taken);
+        Ok(AtomicUpdate::Set((cursors.set_taken)(bits, (taken + 1) % buffer_len)))
+    }) {
+        Ok(_) => Ok(output.unwrap()),
+        Err(None) => panic!("Failed to perform atomic update"),
+        Err(Some(e)) => Err(e),
+    }
+}
+
+/// Gives the slot taken with [`take_slot`] to the other end of the ring, once the slots taken
+/// before it are given.
+pub(crate) fn give_slot(bits: &AtomicUsize, buffer_len: usize, cursors: &Cursors, this: usize) {
+    let next = (this + 1) % buffer_len;
+
+    utils::compare_exchange_loop(bits, utils::ATOMIC_UPDATE_MAX_ITERATIONS, None, |old_bits| {
+        if (cursors.avail)(old_bits) == this {
+            Ok::<_, Infallible>(AtomicUpdate::Set((cursors.set_avail)(old_bits, next)))
+        } else {
+            Ok::<_, Infallible>(AtomicUpdate::Retry)
+        }
+    })
+    .expect("Failed to perform atomic update");
+}
+
+pub(crate) fn close(bits: &AtomicUsize) {
+    utils::compare_exchange_loop(bits, utils::ATOMIC_UPDATE_MAX_ITERATIONS, None, |bits| {
+        Ok::<_, Infallible>(AtomicUpdate::Set(bits::set_closed(bits)))
+    })
+    .expect("failed to perform atomic update");
+}
+
+pub(crate) fn close_rx(bits: &AtomicUsize) {
+    utils::compare_exchange_loop(bits, utils::ATOMIC_UPDATE_MAX_ITERATIONS, None, |bits| {
+        Ok::<_, Infallible>(AtomicUpdate::Set(bits::set_rx_closed(bits::set_closed(bits))))
+    })
+    .expect("failed to perform atomic update");
+}
+
+pub(crate) fn abort(bits: &AtomicUsize) {
+    utils::compare_exchange_loop(bits, utils::ATOMIC_UPDATE_MAX_ITERATIONS, None, |bits| {
+        Ok::<_, Infallible>(AtomicUpdate::Set(bits::set_aborted(bits::set_closed(bits))))
+    })
+    .expect("failed to perform atomic update");
+}
+
+/// Drops the values left in the ring. Requires exclusive access to the ring.
+pub(crate) fn drop_values<T>(bits: &AtomicUsize, buffer: &[Slot<T>]) {
+    let bits = bits.load(Ordering::SeqCst);
+    let mut head = bits::head_avail(bits);
+    let tail = bits::tail_avail(bits);
+    assert_eq!(head, bits::head_taken(bits));
+    assert_eq!(tail, bits::tail_taken(bits));
+
+    let buffer_len = buffer.len();
+
+    while head != tail {
+        unsafe {
+            buffer[head].as_maybe_uninit_mut().assume_init_drop();
+        }
+
+        head += 1;
+        head %= buffer_len;
+    }
+}
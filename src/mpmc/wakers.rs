@@ -0,0 +1,107 @@
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use core::task::{Poll, Waker};
+
+use crate::atomic_waker::AtomicWaker;
+use crate::error::LimitReached;
+
+pub(crate) fn try_attach(
+    refs: &AtomicUsize,
+    wakers: &[(AtomicBool, AtomicWaker)],
+) -> Result<usize, ()> {
+    for (idx, (taken, _waker)) in wakers.iter().enumerate() {
+        if !taken.swap(true, Ordering::SeqCst) {
+            ref_inc(refs);
+            return Ok(idx)
+        }
+    }
+    Err(())
+}
+
+pub(crate) fn detach(refs: &AtomicUsize, wakers: &[(AtomicBool, AtomicWaker)], idx: usize) {
+    let (taken, waker) = &wakers[idx];
+    // the next one to attach must not inherit a stale waker
+    drop(waker.take());
+    if !taken.swap(false, Ordering::SeqCst) {
+        panic!("attempt to detach from unoccupied waker")
+    }
+    ref_dec(refs);
+}
+
+pub(crate) fn notify(wakers: &[(AtomicBool, AtomicWaker)]) {
+    for (_, waker) in wakers {
+        waker.wake();
+    }
+}
+
+/// Wakes the first attached waiter that has registered a waker since it was last woken.
+pub(crate) fn notify_one(wakers: &[(AtomicBool, AtomicWaker)]) {
+    for (taken, waker) in wakers {
+        if !taken.load(Ordering::SeqCst) {
+            continue
+        }
+        if let Some(waker) = waker.take() {
+            waker.wake();
+            return
+        }
+    }
+}
+
+/// A waker taken for the duration of a wait, given back when dropped.
+pub(crate) struct Waiter<'a> {
+    refs: &'a AtomicUsize,
+    wakers: &'a [(AtomicBool, AtomicWaker)],
+    idx: Option<usize>,
+}
+
+impl<'a> Waiter<'a> {
+    pub(crate) fn new(refs: &'a AtomicUsize, wakers: &'a [(AtomicBool, AtomicWaker)]) -> Self {
+        Self { refs, wakers, idx: None }
+    }
+
+    /// Makes an attempt; if it fails, registers the waker and makes another one, so that a wake-up
+    /// between the two is not missed.
+    ///
+    /// Fails with [`LimitReached`] if the attempts fail and all the wakers are taken.
+    pub(crate) fn poll<O>(
+        &mut self,
+        waker: &Waker,
+        mut attempt: impl FnMut() -> Option<O>,
+    ) -> Poll<Result<O, LimitReached>> {
+        if let Some(output) = attempt() {
+            return Poll::Ready(Ok(output))
+        }
+
+        let idx = match self.idx {
+            Some(idx) => idx,
+            None => match try_attach(self.refs, self.wakers) {
+                Ok(idx) => *self.idx.insert(idx),
+                Err(()) => return Poll::Ready(Err(LimitReached)),
+            },
+        };
+        self.wakers[idx].1.register(waker);
+
+        match attempt() {
+            Some(output) => Poll::Ready(Ok(output)),
+            None => Poll::Pending,
+        }
+    }
+}
+
+impl Drop for Waiter<'_> {
+    fn drop(&mut self) {
+        if let Some(idx) = self.idx {
+            detach(self.refs, self.wakers, idx);
+        }
+    }
+}
+
+fn ref_inc(refs: &AtomicUsize) {
+    if refs.fetch_add(1, Ordering::SeqCst) == usize::MAX {
+        panic!("ref-inc overflow")
+    }
+}
+fn ref_dec(refs: &AtomicUsize) {
+    if refs.fetch_sub(1, Ordering::SeqCst) == 0 {
+        panic!("ref-dec overflow")
+    }
+}
@@ -0,0 +1,449 @@
+use core::borrow::Borrow;
+use core::convert::Infallible;
+use core::future;
+use core::marker::PhantomData;
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use core::task::{Context, Poll};
+
+use crate::atomic_waker::AtomicWaker;
+use crate::error::{LimitReached, RecvError, RecvErrorNoWait, SendError, SendErrorNoWait};
+use crate::mpmc::wakers;
+use crate::reason::Reason;
+use crate::slot::Slot;
+use crate::utils;
+use crate::utils::AtomicUpdate;
+
+mod bits;
+mod ring;
+
+/// A medium through which [`Rx`] and [`Tx`]s communicate.
+///
+/// Same as [`mpmc::Link`](crate::mpmc::Link) on the sending side, but there is only one [`Rx`]:
+/// it has a single waker, and takes values off the head without coordinating with other
+/// receivers.
+///
+/// Any endpoint may close the link with a reason of type `R`. The link is closed when the [`Rx`]
+/// or the last [`Tx`] is dropped.
+pub struct Link<T, B, TW, R = ()>
+where
+    B: AsRef<[Slot<T>]>,
+    TW: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    _value: PhantomData<T>,
+
+    buffer: B,
+
+    refs: AtomicUsize,
+    txs: AtomicUsize,
+
+    reason: Reason<R>,
+
+    /// 1bit closed flag [0]
+    /// 1bit rx is set flag [1]
+    /// three indexes (20/10bit):
+    /// - head       [ 2..=21 / 2..=11 ]
+    /// - tail-taken [22..=41 / 12..=21]
+    /// - tail-avail [42..=61 / 22..=31]
+    ///
+    /// for 64bit usize max capacity — 1_048_576-1
+    /// for 32bit usize max capacity — 1_024-1
+    bits: AtomicUsize,
+
+    tx_wakers: TW,
+    rx_waker: AtomicWaker,
+}
+
+/// The sending side of the channel
+pub struct Tx<T, L, B, TW, R = ()>
+where
+    L: Borrow<Link<T, B, TW, R>>,
+    B: AsRef<[Slot<T>]>,
+    TW: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    _value: PhantomData<T>,
+    _buffer: PhantomData<B>,
+    _tx_wakers: PhantomData<TW>,
+    _reason: PhantomData<R>,
+
+    link: L,
+    idx: usize,
+}
+
+/// The receiving side of the channel
+pub struct Rx<T, L, B, TW, R = ()>
+where
+    L: Borrow<Link<T, B, TW, R>>,
+    B: AsRef<[Slot<T>]>,
+    TW: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    _value: PhantomData<T>,
+    _buffer: PhantomData<B>,
+    _tx_wakers: PhantomData<TW>,
+    _reason: PhantomData<R>,
+
+    link: L,
+}
+
+impl<T, L, B, TW, R> Tx<T, L, B, TW, R>
+where
+    L: Borrow<Link<T, B, TW, R>>,
+    B: AsRef<[Slot<T>]>,
+    TW: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    /// Creates a new [`Tx`]
+    pub fn new(link: L) -> Self {
+        let idx = link.borrow().try_attach_tx().expect("all tx-wakers are taken");
+        Self::attached(link, idx)
+    }
+
+    /// Try cloning this [`Tx`].
+    ///
+    /// Fails when all wakers are taken.
+    pub fn try_clone(&self) -> Result<Self, LimitReached>
+    where
+        L: Clone,
+    {
+        let idx = self.link.borrow().try_attach_tx().map_err(|()| LimitReached)?;
+        Ok(Self::attached(self.link.clone(), idx))
+    }
+
+    /// Sends a value if the channel is not full.
+    pub fn send_nowait(&mut self, value: T) -> Result<(), SendErrorNoWait<T, R>>
+    where
+        R: Clone,
+    {
+        let link = self.link.borrow();
+        link.send_nowait(value).map_err(|e| e.with_reason(|| link.reason.get()))
+    }
+
+    /// Sends a value, waits if necessary.
+    pub async fn send(&mut self, value: T) -> Result<(), SendError<T, R>>
+    where
+        R: Clone,
+    {
+        let mut value = Some(value);
+        let link = self.link.borrow();
+        future::poll_fn(|cx| link.poll_send(cx, self.idx, &mut value)).await
+    }
+
+    /// Closes the channel.
+    pub fn close(&mut self) {
+        self.link.borrow().close()
+    }
+
+    /// Closes the channel with a reason.
+    ///
+    /// The reason is dropped if the channel is closed already.
+    pub fn close_with(&mut self, reason: R) {
+        self.link.borrow().close_with(reason)
+    }
+
+    fn attached(link: L, idx: usize) -> Self {
+        Self {
+            _value: Default::default(),
+            _buffer: Default::default(),
+            _tx_wakers: Default::default(),
+            _reason: Default::default(),
+            link,
+            idx,
+        }
+    }
+}
+
+impl<T, L, B, TW, R> Rx<T, L, B, TW, R>
+where
+    L: Borrow<Link<T, B, TW, R>>,
+    B: AsRef<[Slot<T>]>,
+    TW: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    /// Creates a new [`Rx`]
+    pub fn new(link: L) -> Self {
+        link.borrow().set_rx();
+
+        Self {
+            _value: Default::default(),
+            _buffer: Default::default(),
+            _tx_wakers: Default::default(),
+            _reason: Default::default(),
+            link,
+        }
+    }
+
+    /// Receives a value if it is ready.
+    pub fn recv_nowait(&mut self) -> Result<T, RecvErrorNoWait<R>>
+    where
+        R: Clone,
+    {
+        let link = self.link.borrow();
+        link.recv_nowait().map_err(|e| e.with_reason(|| link.reason.get()))
+    }
+
+    /// Receives a value, waits if necessary.
+    pub async fn recv(&mut self) -> Result<T, RecvError<R>>
+    where
+        R: Clone,
+    {
+        let link = self.link.borrow();
+        future::poll_fn(|cx| link.poll_recv(cx)).await
+    }
+
+    /// Closes the channel.
+    pub fn close(&mut self) {
+        self.link.borrow().close()
+    }
+
+    /// Closes the channel with a reason.
+    ///
+    /// The reason is dropped if the channel is closed already.
+    pub fn close_with(&mut self, reason: R) {
+        self.link.borrow().close_with(reason)
+    }
+}
+
+impl<T, B, TW, R> Link<T, B, TW, R>
+where
+    B: AsRef<[Slot<T>]>,
+    TW: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    /// Creates a new [`Link`]
+    pub fn new(buffer: B, tx_wakers: TW) -> Self {
+        assert!(buffer.as_ref().len() < bits::max_len());
+
+        Self {
+            _value: Default::default(),
+            buffer,
+            refs: Default::default(),
+            txs: Default::default(),
+            reason: Default::default(),
+            bits: Default::default(),
+            tx_wakers,
+            rx_waker: Default::default(),
+        }
+    }
+}
+
+impl<T, B, TW, R> Link<T, B, TW, R>
+where
+    B: AsRef<[Slot<T>]>,
+    TW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    R: Clone,
+{
+    fn poll_recv(&self, cx: &mut Context) -> Poll<Result<T, RecvError<R>>> {
+        self.rx_waker.register(cx.waker());
+        match self.recv_nowait().map_err(|e| e.with_reason(|| self.reason.get())) {
+            Ok(value) => Poll::Ready(Ok(value)),
+            Err(RecvErrorNoWait::Closed) => Poll::Ready(Err(RecvError::closed())),
+            Err(RecvErrorNoWait::ClosedWith(reason)) =>
+                Poll::Ready(Err(RecvError::closed_with(reason))),
+            Err(RecvErrorNoWait::Empty) => Poll::Pending,
+        }
+    }
+
+    fn poll_send(
+        &self,
+        cx: &mut Context,
+        idx: usize,
+        value: &mut Option<T>,
+    ) -> Poll<Result<(), SendError<T, R>>> {
+        self.tx_wakers.as_ref()[idx].1.register(cx.waker());
+        match self
+            .send_nowait(value.take().expect("stolen value"))
+            .map_err(|e| e.with_reason(|| self.reason.get()))
+        {
+            Ok(()) => Poll::Ready(Ok(())),
+            Err(SendErrorNoWait::Closed(rejected)) => Poll::Ready(Err(SendError::closed(rejected))),
+            Err(SendErrorNoWait::ClosedWith(rejected, reason)) =>
+                Poll::Ready(Err(SendError::closed_with(rejected, reason))),
+            Err(SendErrorNoWait::Rejected(rejected)) =>
+                Poll::Ready(Err(SendError::rejected(rejected))),
+            Err(SendErrorNoWait::Evicted(evicted)) => Poll::Ready(Err(SendError::evicted(evicted))),
+            Err(SendErrorNoWait::Full(rejected)) => {
+                *value = Some(rejected);
+                Poll::Pending
+            },
+        }
+    }
+
+    fn poll_closed(&self, cx: &mut Context, idx: usize) -> Poll<()> {
+        self.tx_wakers.as_ref()[idx].1.register(cx.waker());
+        if bits::is_closed(self.bits.load(Ordering::SeqCst)) {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+impl<T, B, TW, R> Link<T, B, TW, R>
+where
+    B: AsRef<[Slot<T>]>,
+    TW: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn send_nowait(&self, value: T) -> Result<(), SendErrorNoWait<T>> {
+        ring::send_nowait(&self.bits, self.buffer.as_ref(), value)?;
+        self.rx_waker.wake();
+        Ok(())
+    }
+
+    fn recv_nowait(&self) -> Result<T, RecvErrorNoWait> {
+        let value = ring::recv_nowait(&self.bits, self.buffer.as_ref())?;
+        self.notify_txs();
+        Ok(value)
+    }
+
+    fn try_attach_tx(&self) -> Result<usize, ()> {
+        let idx = wakers::try_attach(&self.refs, self.tx_wakers.as_ref())?;
+        self.txs.fetch_add(1, Ordering::SeqCst);
+        Ok(idx)
+    }
+    fn detach_tx(&self, idx: usize) {
+        if self.txs.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.close();
+        }
+        wakers::detach(&self.refs, self.tx_wakers.as_ref(), idx)
+    }
+
+    fn set_rx(&self) {
+        if let Err(err) = utils::compare_exchange_loop(
+            &self.bits,
+            utils::ATOMIC_UPDATE_MAX_ITERATIONS,
+            None,
+            |old_bits| {
+                if bits::rx_is_set(old_bits) {
+                    Err("this link already has an Rx")
+                } else {
+                    Ok(AtomicUpdate::Set(bits::set_rx(old_bits)))
+                }
+            },
+        ) {
+            panic!("{}", err.unwrap_or("failed to perform atomic update"))
+        }
+        self.refs.fetch_add(1, Ordering::SeqCst);
+    }
+    fn detach_rx(&self) {
+        utils::compare_exchange_loop(
+            &self.bits,
+            utils::ATOMIC_UPDATE_MAX_ITERATIONS,
+            None,
+            |old_bits| Ok::<_, Infallible>(AtomicUpdate::Set(bits::unset_rx(old_bits))),
+        )
+        .expect("failed to perform atomic update");
+        self.refs.fetch_sub(1, Ordering::SeqCst);
+    }
+
+    fn notify_txs(&self) {
+        wakers::notify(self.tx_wakers.as_ref());
+    }
+
+    fn close_with(&self, reason: R) {
+        if !bits::is_closed(self.bits.load(Ordering::SeqCst)) {
+            let _ = self.reason.set(reason);
+        }
+        self.close()
+    }
+
+    fn close(&self) {
+        ring::close(&self.bits);
+
+        self.notify_txs();
+        self.rx_waker.wake();
+    }
+}
+
+impl<T, L, B, TW, R> Drop for Tx<T, L, B, TW, R>
+where
+    L: Borrow<Link<T, B, TW, R>>,
+    B: AsRef<[Slot<T>]>,
+    TW: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn drop(&mut self) {
+        self.link.borrow().detach_tx(self.idx);
+    }
+}
+
+impl<T, L, B, TW, R> Drop for Rx<T, L, B, TW, R>
+where
+    L: Borrow<Link<T, B, TW, R>>,
+    B: AsRef<[Slot<T>]>,
+    TW: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn drop(&mut self) {
+        let link = self.link.borrow();
+        link.close();
+        link.detach_rx();
+    }
+}
+
+impl<T, B, TW, R> Drop for Link<T, B, TW, R>
+where
+    B: AsRef<[Slot<T>]>,
+    TW: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn drop(&mut self) {
+        let refs = self.refs.load(Ordering::SeqCst);
+        if refs != 0 {
+            crate::leak::report::<Self>("Dropping Link that is still referenced?")
+        }
+
+        ring::drop_values(&self.bits, self.buffer.as_ref());
+    }
+}
+
+impl<T, B, TW, R> crate::shutdown::Close for Link<T, B, TW, R>
+where
+    B: AsRef<[Slot<T>]>,
+    TW: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn close(&self) {
+        Link::close(self)
+    }
+}
+
+impl<T, L, B, TW, R> crate::select::PollRecv for Rx<T, L, B, TW, R>
+where
+    L: Borrow<Link<T, B, TW, R>>,
+    B: AsRef<[Slot<T>]>,
+    TW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    R: Clone,
+{
+    type Item = T;
+    type Reason = R;
+
+    fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Result<T, RecvError<R>>> {
+        let link = self.link.borrow();
+        link.poll_recv(cx)
+    }
+
+    fn close(&mut self) {
+        Rx::close(self)
+    }
+}
+
+impl<T, L, B, TW, R> crate::route::PollSend for Tx<T, L, B, TW, R>
+where
+    L: Borrow<Link<T, B, TW, R>>,
+    B: AsRef<[Slot<T>]>,
+    TW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    R: Clone,
+{
+    type Item = T;
+    type Reason = R;
+
+    fn poll_send(
+        &mut self,
+        cx: &mut Context<'_>,
+        value: &mut Option<T>,
+    ) -> Poll<Result<(), SendError<T, R>>> {
+        let link = self.link.borrow();
+        link.poll_send(cx, self.idx, value)
+    }
+
+    fn poll_closed(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        let link = self.link.borrow();
+        link.poll_closed(cx, self.idx)
+    }
+
+    fn close(&mut self) {
+        Tx::close(self)
+    }
+}
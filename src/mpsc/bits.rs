@@ -0,0 +1,90 @@
+use core::sync::atomic::AtomicUsize;
+
+use crate::utils;
+
+const POS_IS_CLOSED: u8 = 0;
+const POS_RX_IS_SET: u8 = 1;
+const FLAGS_COUNT: u8 = 2;
+
+type Usize = <AtomicUsize as crate::utils::AtomicValue>::Value;
+const USIZE_BITS: u8 = Usize::BITS as u8;
+
+const INDEX_BIT_COUNT: u8 = (USIZE_BITS - FLAGS_COUNT) / 3;
+const START_HEAD: u8 = FLAGS_COUNT;
+const START_TAIL_TAKEN: u8 = FLAGS_COUNT + INDEX_BIT_COUNT;
+const START_TAIL_AVAIL: u8 = FLAGS_COUNT + INDEX_BIT_COUNT * 2;
+
+pub(super) fn max_len() -> Usize {
+    !(Usize::MAX << INDEX_BIT_COUNT)
+}
+
+pub(super) fn is_closed(bits: Usize) -> bool {
+    utils::bits::flag::<Usize, POS_IS_CLOSED>(bits) != 0
+}
+pub(super) fn set_closed(bits: Usize) -> Usize {
+    bits | utils::bits::flag::<Usize, POS_IS_CLOSED>(utils::bits::ones())
+}
+
+pub(super) fn rx_is_set(bits: Usize) -> bool {
+    utils::bits::flag::<Usize, POS_RX_IS_SET>(bits) != 0
+}
+pub(super) fn set_rx(bits: Usize) -> Usize {
+    bits | utils::bits::flag::<Usize, POS_RX_IS_SET>(utils::bits::ones())
+}
+pub(super) fn unset_rx(bits: Usize) -> Usize {
+    bits & !utils::bits::flag::<Usize, POS_RX_IS_SET>(utils::bits::ones())
+}
+
+pub(super) fn head(bits: Usize) -> Usize {
+    utils::bits::unpack::<Usize, START_HEAD, INDEX_BIT_COUNT>(bits)
+}
+pub(super) fn set_head(bits: Usize, value: Usize) -> Usize {
+    utils::bits::pack::<Usize, START_HEAD, INDEX_BIT_COUNT>(bits, value)
+}
+
+pub(super) fn tail_taken(bits: Usize) -> Usize {
+    utils::bits::unpack::<Usize, START_TAIL_TAKEN, INDEX_BIT_COUNT>(bits)
+}
+pub(super) fn set_tail_taken(bits: Usize, value: Usize) -> Usize {
+    utils::bits::pack::<Usize, START_TAIL_TAKEN, INDEX_BIT_COUNT>(bits, value)
+}
+
+pub(super) fn tail_avail(bits: Usize) -> Usize {
+    utils::bits::unpack::<Usize, START_TAIL_AVAIL, INDEX_BIT_COUNT>(bits)
+}
+pub(super) fn set_tail_avail(bits: Usize, value: Usize) -> Usize {
+    utils::bits::pack::<Usize, START_TAIL_AVAIL, INDEX_BIT_COUNT>(bits, value)
+}
+
+#[test]
+fn test() {
+    let max = max_len();
+
+    for head in [0, 1, max / 2, max - 1, max] {
+        for tail_taken in [0, 1, max / 2, max - 1, max] {
+            for tail_avail in [0, 1, max / 2, max - 1, max] {
+                for closed in [true, false] {
+                    for rx in [true, false] {
+                        let bits = 0;
+                        let bits = if closed { set_closed(bits) } else { bits };
+                        let bits = if rx { set_rx(bits) } else { bits };
+                        let bits = set_head(bits, head);
+                        let bits = set_tail_taken(bits, tail_taken);
+                        let bits = set_tail_avail(bits, tail_avail);
+
+                        assert_eq!(closed, is_closed(bits));
+                        assert_eq!(rx, rx_is_set(bits));
+                        assert_eq!(head, self::head(bits));
+                        assert_eq!(tail_taken, self::tail_taken(bits));
+                        assert_eq!(tail_avail, self::tail_avail(bits));
+
+                        let bits = unset_rx(bits);
+                        assert!(!rx_is_set(bits));
+                        assert_eq!(closed, is_closed(bits));
+                        assert_eq!(head, self::head(bits));
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use core::convert::Infallible;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::error::{RecvErrorNoWait, SendErrorNoWait};
+use crate::mpmc::ring::{give_slot, take_slot, Cursors};
+use crate::slot::Slot;
+use crate::utils::{self, AtomicUpdate};
+
+use super::bits;
+
+const TAIL: Cursors = Cursors {
+    taken: bits::tail_taken,
+    set_taken: bits::set_tail_taken,
+    avail: bits::tail_avail,
+    set_avail: bits::set_tail_avail,
+};
+
+pub(super) fn send_nowait<T>(
+    bits: &AtomicUsize,
+    buffer: &[Slot<T>],
+    value: T,
+) -> Result<(), SendErrorNoWait<T>> {
+    let buffer_len = buffer.len();
+
+    let tail_this = match take_slot(bits, buffer_len, &TAIL, |bits, tail_taken| {
+        let tail_if_full = (bits::head(bits) + buffer_len - 1) % buffer_len;
+
+        match (bits::is_closed(bits), tail_taken == tail_if_full) {
+            (true, _) => Err(SendErrorNoWait::closed(())),
+            (false, true) => Err(SendErrorNoWait::full(())),
+            (false, false) => Ok(()),
+        }
+    }) {
+        Ok(tail_this) => tail_this,
+        Err(e) => return Err(e.map_value(value)),
+    };
+
+    unsafe { buffer[tail_this].as_maybe_uninit_mut() }.write(value);
+    give_slot(bits, buffer_len, &TAIL, tail_this);
+
+    Ok(())
+}
+
+/// Only the single consumer may call this: nobody else moves the head.
+pub(super) fn recv_nowait<T>(bits: &AtomicUsize, buffer: &[Slot<T>]) -> Result<T, RecvErrorNoWait> {
+    let current = bits.load(Ordering::SeqCst);
+
+    let head = bits::head(current);
+    let is_empty = head == bits::tail_avail(current);
+
+    match (is_empty, bits::is_closed(current)) {
+        (true, true) => return Err(RecvErrorNoWait::closed()),
+        (true, false) => return Err(RecvErrorNoWait::empty()),
+        (false, _) => (),
+    }
+
+    let value = unsafe { buffer[head].as_maybe_uninit_mut().assume_init_read() };
+
+    let head_next = (head + 1) % buffer.len();
+    utils::compare_exchange_loop(
+        bits,
+        utils::ATOMIC_UPDATE_MAX_ITERATIONS,
+        Some(current),
+        |bits| Ok::<_, Infallible>(AtomicUpdate::Set(bits::set_head(bits, head_next))),
+    )
+    .expect("Failed to perform atomic update");
+
+    Ok(value)
+}
+
+pub(super) fn close(bits: &AtomicUsize) {
+    utils::compare_exchange_loop(bits, utils::ATOMIC_UPDATE_MAX_ITERATIONS, None, |bits| {
+        Ok::<_, Infallible>(AtomicUpdate::Set(bits::set_closed(bits)))
+    })
+    .expect("failed to perform atomic update");
+}
+
+/// Drops the values left in the ring. Requires exclusive access to the ring.
+pub(super) fn drop_values<T>(bits: &AtomicUsize, buffer: &[Slot<T>]) {
+    let bits = bits.load(Ordering::SeqCst);
+    let mut head = bits::head(bits);
+    let tail = bits::tail_avail(bits);
+    assert_eq!(tail, bits::tail_taken(bits));
+
+    let buffer_len = buffer.len();
+
+    while head != tail {
+        unsafe {
+            buffer[head].as_maybe_uninit_mut().assume_init_drop();
+        }
+
+        head += 1;
+        head %= buffer_len;
+    }
+}
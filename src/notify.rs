@@ -0,0 +1,126 @@
+use core::future::{self, Future};
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use crate::atomic_waker::AtomicWaker;
+use crate::error::LimitReached;
+use crate::mpmc::wakers;
+use crate::utils::{self, AtomicUpdate};
+
+const PERMIT: usize = 1;
+const GENERATION: usize = 2;
+
+/// Wakes tasks without passing them a value.
+///
+/// [`Notify::notify_one`] leaves a single permit and wakes one waiter to take it; if there are
+/// none, the next call to [`Notify::notified`] takes it right away. [`Notify::notify_all`] wakes
+/// everyone already waiting, and leaves no permit.
+///
+/// A waiter registers in one of the wakers of `W`, so at most as many tasks as there are wakers
+/// can wait at the same time.
+pub struct Notify<W>
+where
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    /// 1bit permit flag [0]
+    /// generation, bumped by every `notify_all` [1..]
+    state: AtomicUsize,
+
+    refs: AtomicUsize,
+
+    wakers: W,
+}
+
+impl<W> Notify<W>
+where
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    /// Creates a new [`Notify`]
+    pub fn new(wakers: W) -> Self {
+        Self { state: Default::default(), refs: Default::default(), wakers }
+    }
+
+    /// Leaves a permit and wakes one of the waiters. A permit already left is not doubled.
+    pub fn notify_one(&self) {
+        self.state.fetch_or(PERMIT, Ordering::SeqCst);
+        wakers::notify_one(self.wakers.as_ref());
+    }
+
+    /// Wakes all the waiters, i.e. the futures returned by [`Notify::notified`] before this
+    /// call.
+    pub fn notify_all(&self) {
+        self.state.fetch_add(GENERATION, Ordering::SeqCst);
+        wakers::notify(self.wakers.as_ref());
+    }
+
+    /// Waits for a notification.
+    ///
+    /// Fails with [`LimitReached`] if it has to wait while all the wakers are taken. If the future
+    /// is dropped after being woken by [`Notify::notify_one`], the permit goes to another waiter.
+    pub fn notified(&self) -> impl Future<Output = Result<(), LimitReached>> + '_ {
+        let generation = self.state.load(Ordering::SeqCst) & !PERMIT;
+
+        async move {
+            let mut forward = Forward(Some(self));
+            let mut waiter = wakers::Waiter::new(&self.refs, self.wakers.as_ref());
+            let notified = future::poll_fn(|cx| {
+                waiter.poll(cx.waker(), || self.try_take(generation).then_some(()))
+            })
+            .await;
+            forward.0 = None;
+            notified
+        }
+    }
+
+    fn try_take(&self, generation: usize) -> bool {
+        match utils::compare_exchange_loop(
+            &self.state,
+            utils::ATOMIC_UPDATE_MAX_ITERATIONS,
+            None,
+            |state| {
+                if state & !PERMIT != generation {
+                    Ok(AtomicUpdate::Set(state))
+                } else if state & PERMIT != 0 {
+                    Ok(AtomicUpdate::Set(state & !PERMIT))
+                } else {
+                    Err(())
+                }
+            },
+        ) {
+            Ok(_) => true,
+            Err(None) => panic!("Failed to perform atomic update"),
+            Err(Some(())) => false,
+        }
+    }
+}
+
+/// Wakes another waiter if a permit is left when a waiting future is dropped, since that future
+/// may have been the one [`Notify::notify_one`] woke.
+///
+/// Declared before the [`wakers::Waiter`] so that the waker is given back first.
+struct Forward<'a, W>(Option<&'a Notify<W>>)
+where
+    W: AsRef<[(AtomicBool, AtomicWaker)]>;
+
+impl<W> Drop for Forward<'_, W>
+where
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn drop(&mut self) {
+        if let Some(notify) = self.0 {
+            if notify.state.load(Ordering::SeqCst) & PERMIT != 0 {
+                wakers::notify_one(notify.wakers.as_ref());
+            }
+        }
+    }
+}
+
+impl<W> Drop for Notify<W>
+where
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn drop(&mut self) {
+        if *self.refs.get_mut() != 0 {
+            crate::leak::report::<Self>("Dropping Notify that is still referenced?")
+        }
+    }
+}
@@ -0,0 +1,195 @@
+use core::future;
+use core::marker::PhantomData;
+use core::mem::ManuallyDrop;
+use core::ops::{Deref, DerefMut};
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use crate::atomic_waker::AtomicWaker;
+use crate::error::{LimitReached, RecvErrorNoWait, SendErrorNoWait};
+use crate::mpmc::{ring, wakers};
+use crate::slot::Slot;
+use crate::utils::{self, AtomicUpdate};
+
+/// A bounded pool of objects.
+///
+/// The idle objects are kept in the ring of an [`mpmc::Link`](crate::mpmc::Link): a buffer of
+/// `N` slots holds up to `N - 1` objects. An object taken out with [`Pool::get`] returns to the
+/// pool when its [`PoolGuard`] is dropped.
+///
+/// A task that finds no idle object waits in one of the wakers of `W`; with all of them taken,
+/// [`Pool::get`] fails rather than wait.
+pub struct Pool<T, B, W>
+where
+    B: AsRef<[Slot<T>]>,
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    _value: PhantomData<T>,
+
+    buffer: B,
+
+    /// laid out the same way as in [`mpmc::Link`](crate::mpmc::Link)
+    bits: AtomicUsize,
+
+    /// the objects that belong to the pool: both idle and taken out
+    objects: AtomicUsize,
+
+    refs: AtomicUsize,
+
+    wakers: W,
+}
+
+/// An object taken out of a [`Pool`], returned to it when dropped.
+pub struct PoolGuard<'a, T, B, W>
+where
+    B: AsRef<[Slot<T>]>,
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    pool: &'a Pool<T, B, W>,
+    value: ManuallyDrop<T>,
+}
+
+impl<T, B, W> Pool<T, B, W>
+where
+    B: AsRef<[Slot<T>]>,
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    /// Creates a new empty [`Pool`]
+    pub fn new(buffer: B, wakers: W) -> Self {
+        assert!(!buffer.as_ref().is_empty());
+
+        Self {
+            _value: Default::default(),
+            buffer,
+            bits: Default::default(),
+            objects: Default::default(),
+            refs: Default::default(),
+            wakers,
+        }
+    }
+
+    /// The number of objects the pool can hold.
+    pub fn capacity(&self) -> usize {
+        self.buffer.as_ref().len() - 1
+    }
+
+    /// The number of objects that belong to the pool, including the ones taken out.
+    pub fn len(&self) -> usize {
+        self.objects.load(Ordering::SeqCst)
+    }
+
+    /// Whether no object belongs to the pool.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Adds an object to the pool.
+    ///
+    /// Fails, handing the object back, if the pool is at capacity.
+    pub fn put(&self, value: T) -> Result<(), T> {
+        let capacity = self.capacity();
+        match utils::compare_exchange_loop(
+            &self.objects,
+            utils::ATOMIC_UPDATE_MAX_ITERATIONS,
+            None,
+            |objects| {
+                if objects < capacity {
+                    Ok(AtomicUpdate::Set(objects + 1))
+                } else {
+                    Err(())
+                }
+            },
+        ) {
+            Ok(_) => (),
+            Err(None) => panic!("Failed to perform atomic update"),
+            Err(Some(())) => return Err(value),
+        }
+
+        self.give_back(value);
+        Ok(())
+    }
+
+    /// Takes an idle object if there is one.
+    pub fn try_get(&self) -> Option<PoolGuard<'_, T, B, W>> {
+        match ring::recv_nowait(&self.bits, self.buffer.as_ref()) {
+            Ok(value) => Some(PoolGuard { pool: self, value: ManuallyDrop::new(value) }),
+            Err(RecvErrorNoWait::Empty) => None,
+            Err(_) => unreachable!("pool ring closed"),
+        }
+    }
+
+    /// Takes an idle object, waits if necessary.
+    ///
+    /// Fails with [`LimitReached`] if it has to wait while all the wakers are taken.
+    pub async fn get(&self) -> Result<PoolGuard<'_, T, B, W>, LimitReached> {
+        let mut waiter = wakers::Waiter::new(&self.refs, self.wakers.as_ref());
+        future::poll_fn(|cx| waiter.poll(cx.waker(), || self.try_get())).await
+    }
+
+    fn give_back(&self, value: T) {
+        match ring::send_nowait(&self.bits, self.buffer.as_ref(), value) {
+            Ok(()) => wakers::notify(self.wakers.as_ref()),
+            Err(SendErrorNoWait::Full(_)) => unreachable!("pool ring overflow"),
+            Err(_) => unreachable!("pool ring closed"),
+        }
+    }
+}
+
+impl<T, B, W> PoolGuard<'_, T, B, W>
+where
+    B: AsRef<[Slot<T>]>,
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    /// Takes the object out of the pool for good, making room for another one.
+    pub fn detach(self) -> T {
+        let mut this = ManuallyDrop::new(self);
+        this.pool.objects.fetch_sub(1, Ordering::SeqCst);
+        unsafe { ManuallyDrop::take(&mut this.value) }
+    }
+}
+
+impl<T, B, W> Deref for PoolGuard<'_, T, B, W>
+where
+    B: AsRef<[Slot<T>]>,
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<T, B, W> DerefMut for PoolGuard<'_, T, B, W>
+where
+    B: AsRef<[Slot<T>]>,
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.value
+    }
+}
+
+impl<T, B, W> Drop for PoolGuard<'_, T, B, W>
+where
+    B: AsRef<[Slot<T>]>,
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn drop(&mut self) {
+        let value = unsafe { ManuallyDrop::take(&mut self.value) };
+        self.pool.give_back(value);
+    }
+}
+
+impl<T, B, W> Drop for Pool<T, B, W>
+where
+    B: AsRef<[Slot<T>]>,
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn drop(&mut self) {
+        if *self.refs.get_mut() != 0 {
+            crate::leak::report::<Self>("Dropping Pool that is still referenced?")
+        }
+
+        ring::drop_values(&self.bits, self.buffer.as_ref());
+    }
+}
@@ -0,0 +1,86 @@
+use core::convert::Infallible;
+use core::sync::atomic::{AtomicU8, Ordering};
+
+use crate::slot::Slot;
+use crate::utils;
+use crate::utils::AtomicUpdate;
+
+const FLAG_IS_TAKEN: u8 = 0b01;
+const FLAG_IS_SET: u8 = 0b10;
+
+/// The reason a link is closed with.
+pub(crate) struct Reason<R> {
+    flags: AtomicU8,
+    slot: Slot<R>,
+}
+
+impl<R> Reason<R> {
+    /// Stores the reason, unless some reason has been stored already.
+    pub(crate) fn set(&self, reason: R) -> Result<(), R> {
+        if utils::compare_exchange_loop(
+            &self.flags,
+            utils::ATOMIC_UPDATE_MAX_ITERATIONS,
+            None,
+            |old_flags| {
+                if old_flags & FLAG_IS_TAKEN != 0 {
+                    Err(())
+                } else {
+                    Ok(AtomicUpdate::Set(old_flags | FLAG_IS_TAKEN))
+                }
+            },
+        )
+        .is_err()
+        {
+            return Err(reason)
+        }
+
+        unsafe { self.slot.as_maybe_uninit_mut() }.write(reason);
+
+        utils::compare_exchange_loop(
+            &self.flags,
+            utils::ATOMIC_UPDATE_MAX_ITERATIONS,
+            None,
+            |old_flags| Ok::<_, Infallible>(AtomicUpdate::Set(old_flags | FLAG_IS_SET)),
+        )
+        .expect("failed to perform atomic update");
+
+        Ok(())
+    }
+
+    pub(crate) fn get(&self) -> Option<R>
+    where
+        R: Clone,
+    {
+        if self.flags.load(Ordering::SeqCst) & FLAG_IS_SET != 0 {
+            Some(unsafe { self.slot.as_maybe_uninit_mut().assume_init_ref() }.clone())
+        } else {
+            None
+        }
+    }
+
+    /// Drops the stored reason, if any.
+    ///
+    /// # Safety
+    /// Nothing may access the reason concurrently.
+    pub(crate) unsafe fn clear(&self) {
+        if self.flags.swap(0, Ordering::SeqCst) & FLAG_IS_SET != 0 {
+            unsafe { self.slot.as_maybe_uninit_mut().assume_init_drop() };
+        }
+    }
+}
+
+impl<R> Default for Reason<R> {
+    fn default() -> Self {
+        Self { flags: Default::default(), slot: Default::default() }
+    }
+}
+
+impl<R> Drop for Reason<R> {
+    fn drop(&mut self) {
+        if *self.flags.get_mut() & FLAG_IS_SET != 0 {
+            unsafe {
+                self.slot.as_maybe_uninit_mut().assume_init_drop();
+            }
+        }
+    }
+}
@@ -0,0 +1,211 @@
+use core::future;
+use core::hash::{BuildHasher, Hash};
+use core::task::{Context, Poll};
+
+use crate::error::SendError;
+use crate::select::{Merge, PollRecv};
+
+/// A sending side that can be polled until it accepts a value.
+pub trait PollSend {
+    /// The values sent.
+    type Item;
+    /// The reason the link may be closed with.
+    type Reason;
+
+    /// Sends the value if there is room for it, registers the waker otherwise.
+    ///
+    /// The value is taken out of `value` once the poll is ready, and left in place while pending.
+    #[allow(clippy::type_complexity)]
+    fn poll_send(
+        &mut self,
+        cx: &mut Context<'_>,
+        value: &mut Option<Self::Item>,
+    ) -> Poll<Result<(), SendError<Self::Item, Self::Reason>>>;
+
+    /// Resolves once the channel is closed, registers the waker otherwise.
+    fn poll_closed(&mut self, cx: &mut Context<'_>) -> Poll<()>;
+
+    /// Closes the channel.
+    fn close(&mut self);
+}
+
+impl<X> PollSend for &mut X
+where
+    X: PollSend + ?Sized,
+{
+    type Item = X::Item;
+    type Reason = X::Reason;
+
+    fn poll_send(
+        &mut self,
+        cx: &mut Context<'_>,
+        value: &mut Option<Self::Item>,
+    ) -> Poll<Result<(), SendError<Self::Item, Self::Reason>>> {
+        X::poll_send(self, cx, value)
+    }
+
+    fn poll_closed(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        X::poll_closed(self, cx)
+    }
+
+    fn close(&mut self) {
+        X::close(self)
+    }
+}
+
+/// Picks the output [`fan_out`] routes a value to.
+pub trait Strategy<T> {
+    /// The index of the output, less than `outputs`.
+    fn pick(&mut self, value: &T, outputs: usize) -> usize;
+}
+
+/// Routes the values to the outputs in turn.
+#[derive(Default)]
+pub struct RoundRobin {
+    next: usize,
+}
+
+/// Routes the values with equal keys to the same output.
+pub struct KeyHash<F, S> {
+    key: F,
+    hasher: S,
+}
+
+impl RoundRobin {
+    /// Creates a new [`RoundRobin`]
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
+impl<T> Strategy<T> for RoundRobin {
+    fn pick(&mut self, _value: &T, outputs: usize) -> usize {
+        let idx = self.next % outputs;
+        self.next = idx + 1;
+        idx
+    }
+}
+
+impl<F, S> KeyHash<F, S> {
+    /// Creates a new [`KeyHash`], hashing the keys `key` extracts with `hasher`.
+    pub fn new(key: F, hasher: S) -> Self {
+        Self { key, hasher }
+    }
+}
+
+impl<T, K, F, S> Strategy<T> for KeyHash<F, S>
+where
+    F: FnMut(&T) -> K,
+    K: Hash,
+    S: BuildHasher,
+{
+    fn pick(&mut self, value: &T, outputs: usize) -> usize {
+        (self.hasher.hash_one((self.key)(value)) % outputs as u64) as usize
+    }
+}
+
+/// Routes the values received from `rx` to `txs`, `strategy` picks the output for each value.
+///
+/// Resolves to `None` once `rx` is closed, closing all the outputs. If an output turns out to be
+/// closed, closes `rx` and the other outputs and resolves to the value that could not be delivered,
+/// or to `None` if the output got closed while the router was waiting on `rx`. The values rejected
+/// or evicted by an output's overflow policy count as delivered.
+///
+/// With no outputs at all, closes `rx` and resolves to `None` right away.
+pub async fn fan_out<RX, TX, S, const N: usize>(
+    mut rx: RX,
+    mut txs: [TX; N],
+    mut strategy: S,
+) -> Option<RX::Item>
+where
+    RX: PollRecv,
+    TX: PollSend<Item = RX::Item>,
+    S: Strategy<RX::Item>,
+{
+    if N == 0 {
+        rx.close();
+        return None
+    }
+
+    let undelivered = loop {
+        let next = future::poll_fn(|cx| match rx.poll_recv(cx) {
+            Poll::Ready(result) => Poll::Ready(result.ok()),
+            Poll::Pending if any_closed(cx, &mut txs) => Poll::Ready(None),
+            Poll::Pending => Poll::Pending,
+        });
+        let value = match next.await {
+            None => break None,
+            Some(value) => value,
+        };
+
+        let tx = &mut txs[strategy.pick(&value, N)];
+        if let Err(rejected) = send(tx, value).await {
+            break Some(rejected)
+        }
+    };
+
+    rx.close();
+    txs.iter_mut().for_each(TX::close);
+
+    undelivered
+}
+
+/// Routes the values received from all of `rxs` to `tx`.
+///
+/// Resolves to `None` once all of `rxs` are closed, closing `tx`. If `tx` turns out to be closed,
+/// closes `rxs` and resolves to the value that could not be delivered, or to `None` if `tx` got
+/// closed while the router was waiting on `rxs`. The inputs are polled in rotating order, as with
+/// [`Merge`].
+pub async fn fan_in<RX, TX, const N: usize>(rxs: [RX; N], mut tx: TX) -> Option<RX::Item>
+where
+    RX: PollRecv,
+    TX: PollSend<Item = RX::Item>,
+{
+    let mut merge = Merge::new(rxs);
+
+    let undelivered = loop {
+        let next = future::poll_fn(|cx| loop {
+            match merge.poll_recv(cx) {
+                Poll::Ready(Some((_, Err(_)))) => (),
+                Poll::Ready(Some((_, Ok(value)))) => return Poll::Ready(Some(value)),
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending if any_closed(cx, core::slice::from_mut(&mut tx)) =>
+                    return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        });
+        let value = match next.await {
+            None => break None,
+            Some(value) => value,
+        };
+        if let Err(rejected) = send(&mut tx, value).await {
+            break Some(rejected)
+        }
+    };
+
+    merge.close();
+    tx.close();
+
+    undelivered
+}
+
+/// Whether one of the outputs is closed; registers the waker with the open ones otherwise, so that
+/// a router waiting on its inputs is woken when an output gets closed.
+fn any_closed<TX>(cx: &mut Context<'_>, txs: &mut [TX]) -> bool
+where
+    TX: PollSend,
+{
+    txs.iter_mut().any(|tx| tx.poll_closed(cx).is_ready())
+}
+
+/// Sends the value, fails with the value if the channel is closed.
+async fn send<TX>(tx: &mut TX, value: TX::Item) -> Result<(), TX::Item>
+where
+    TX: PollSend,
+{
+    let mut value = Some(value);
+    match future::poll_fn(|cx| tx.poll_send(cx, &mut value)).await {
+        Ok(()) | Err(SendError::Rejected(_) | SendError::Evicted(_)) => Ok(()),
+        Err(SendError::Closed(rejected) | SendError::ClosedWith(rejected, _)) => Err(rejected),
+    }
+}
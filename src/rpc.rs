@@ -0,0 +1,245 @@
+use core::future;
+use core::marker::PhantomData;
+use core::mem::ManuallyDrop;
+use core::sync::atomic::{AtomicBool, AtomicUsize};
+
+use crate::atomic_waker::AtomicWaker;
+use crate::error::{LimitReached, RecvError, RpcError, SendErrorNoWait};
+use crate::mpmc::wakers;
+use crate::slot::Slot;
+use crate::{mpmc, spsc};
+
+/// The link a response travels through; the [`Client`]s take them from [`Replies`].
+pub type ReplyLink<Resp> = spsc::direct::Link<Resp, RpcError>;
+
+/// A request as it travels from a [`Client`] to a [`Server`].
+pub type Request<'r, Req, Resp> = (Req, Responder<'r, Resp>);
+
+/// The link the requests travel through.
+pub type RequestLink<'r, Req, Resp, B, TW, RW> = mpmc::Link<Request<'r, Req, Resp>, B, TW, RW>;
+
+/// The [`ReplyLink`]s shared by the [`Client`]s.
+///
+/// Every call in progress holds one of the links in `P`. A call that finds them all in use waits
+/// for one to be let go, in one of the wakers of `W`.
+pub struct Replies<Resp, P, W>
+where
+    P: AsRef<[ReplyLink<Resp>]>,
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    _response: PhantomData<Resp>,
+
+    links: P,
+
+    refs: AtomicUsize,
+
+    wakers: W,
+}
+
+/// Makes calls to the [`Server`]s.
+///
+/// Every call in progress takes a [`ReplyLink`] from the [`Replies`]. The request link is closed
+/// when the last [`Client`] is dropped.
+pub struct Client<'a, 'r, Req, Resp, B, TW, RW, P, W>
+where
+    B: AsRef<[Slot<Request<'r, Req, Resp>>]>,
+    TW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    RW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    P: AsRef<[ReplyLink<Resp>]>,
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    #[allow(clippy::type_complexity)]
+    tx: mpmc::Tx<Request<'r, Req, Resp>, &'a RequestLink<'r, Req, Resp, B, TW, RW>, B, TW, RW>,
+    replies: &'r Replies<Resp, P, W>,
+}
+
+/// Takes the requests made by the [`Client`]s.
+///
+/// Dropping the last [`Server`] closes the request link: the requests still in it, and the calls
+/// made after that, fail with [`RpcError::Closed`].
+pub struct Server<'a, 'r, Req, Resp, B, TW, RW>
+where
+    B: AsRef<[Slot<Request<'r, Req, Resp>>]>,
+    TW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    RW: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    #[allow(clippy::type_complexity)]
+    rx: ManuallyDrop<
+        mpmc::Rx<Request<'r, Req, Resp>, &'a RequestLink<'r, Req, Resp, B, TW, RW>, B, TW, RW>,
+    >,
+    requests: &'a RequestLink<'r, Req, Resp, B, TW, RW>,
+}
+
+/// Responds to a request. Dropping it without responding fails the call with
+/// [`RpcError::NoReply`].
+pub struct Responder<'r, Resp> {
+    tx: spsc::direct::Tx<Resp, &'r ReplyLink<Resp>, RpcError>,
+    _freed: Freed<'r>,
+}
+
+impl<Resp, P, W> Replies<Resp, P, W>
+where
+    P: AsRef<[ReplyLink<Resp>]>,
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    /// Creates a new [`Replies`]
+    pub fn new(links: P, wakers: W) -> Self {
+        assert!(!links.as_ref().is_empty());
+
+        Self { _response: Default::default(), links, refs: Default::default(), wakers }
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn try_take(
+        &self,
+    ) -> Option<(
+        spsc::direct::Tx<Resp, &ReplyLink<Resp>, RpcError>,
+        spsc::direct::Rx<Resp, &ReplyLink<Resp>, RpcError>,
+    )> {
+        self.links.as_ref().iter().find_map(|link| link.try_reuse().ok())
+    }
+}
+
+impl<'a, 'r, Req, Resp, B, TW, RW, P, W> Client<'a, 'r, Req, Resp, B, TW, RW, P, W>
+where
+    B: AsRef<[Slot<Request<'r, Req, Resp>>]>,
+    TW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    RW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    P: AsRef<[ReplyLink<Resp>]>,
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    /// Creates a new [`Client`]
+    pub fn new(
+        requests: &'a RequestLink<'r, Req, Resp, B, TW, RW>,
+        replies: &'r Replies<Resp, P, W>,
+    ) -> Self {
+        Self { tx: mpmc::Tx::new(requests), replies }
+    }
+
+    /// Try cloning this [`Client`].
+    ///
+    /// Fails when all the tx-wakers of the request link are taken.
+    pub fn try_clone(&self) -> Result<Self, LimitReached> {
+        Ok(Self { tx: self.tx.try_clone()?, replies: self.replies })
+    }
+
+    /// Sends a request and waits for the response.
+    ///
+    /// Waits for a free [`ReplyLink`] first, failing with [`RpcError::Busy`] if all the wakers of
+    /// the [`Replies`] are taken.
+    pub async fn call(&mut self, request: Req) -> Result<Resp, RpcError> {
+        let replies = self.replies;
+
+        // Declared before the reply link is taken, so that it runs after the link is let go.
+        let _freed = Freed(replies.wakers.as_ref());
+
+        let mut waiter = wakers::Waiter::new(&replies.refs, replies.wakers.as_ref());
+        let (reply_tx, mut reply_rx) =
+            future::poll_fn(|cx| waiter.poll(cx.waker(), || replies.try_take()))
+                .await
+                .map_err(|LimitReached| RpcError::Busy)?;
+        drop(waiter);
+
+        let responder = Responder { tx: reply_tx, _freed: Freed(replies.wakers.as_ref()) };
+        self.tx.send((request, responder)).await.map_err(|_| RpcError::Closed)?;
+
+        reply_rx.recv().await.map_err(|e| match e {
+            RecvError::ClosedWith(reason) => reason,
+            RecvError::Closed => RpcError::NoReply,
+        })
+    }
+}
+
+impl<'a, 'r, Req, Resp, B, TW, RW> Server<'a, 'r, Req, Resp, B, TW, RW>
+where
+    B: AsRef<[Slot<Request<'r, Req, Resp>>]>,
+    TW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    RW: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    /// Creates a new [`Server`]
+    pub fn new(requests: &'a RequestLink<'r, Req, Resp, B, TW, RW>) -> Self {
+        Self { rx: ManuallyDrop::new(mpmc::Rx::new(requests)), requests }
+    }
+
+    /// Try cloning this [`Server`].
+    ///
+    /// Fails when all the rx-wakers of the request link are taken.
+    pub fn try_clone(&self) -> Result<Self, LimitReached> {
+        Ok(Self { rx: ManuallyDrop::new(self.rx.try_clone()?), requests: self.requests })
+    }
+
+    /// Takes the next request, waits if necessary.
+    ///
+    /// Returns `None` once all the [`Client`]s are gone and no request is left.
+    pub async fn next(&mut self) -> Option<Request<'r, Req, Resp>> {
+        self.rx.recv().await.ok()
+    }
+
+    /// Closes the request link: the calls in progress still get their responses, the new ones
+    /// fail with [`RpcError::Closed`].
+    pub fn close(&mut self) {
+        self.rx.close()
+    }
+}
+
+impl<Resp> Responder<'_, Resp> {
+    /// Sends the response.
+    ///
+    /// Fails, handing the response back, if the caller is gone.
+    pub fn respond(mut self, response: Resp) -> Result<(), Resp> {
+        self.tx.send_nowait(response).map_err(|e| match e {
+            SendErrorNoWait::Closed(response) |
+            SendErrorNoWait::ClosedWith(response, _) |
+            SendErrorNoWait::Full(response) |
+            SendErrorNoWait::Rejected(response) |
+            SendErrorNoWait::Evicted(response) => response,
+        })
+    }
+
+    /// Fails the call with [`RpcError::Closed`].
+    fn refuse(mut self) {
+        self.tx.close_with(RpcError::Closed)
+    }
+}
+
+/// Wakes the calls waiting for a [`ReplyLink`] when dropped.
+///
+/// Kept next to each end of a reply link, and dropped after it.
+struct Freed<'r>(&'r [(AtomicBool, AtomicWaker)]);
+
+impl Drop for Freed<'_> {
+    fn drop(&mut self) {
+        wakers::notify(self.0)
+    }
+}
+
+impl<'a, 'r, Req, Resp, B, TW, RW> Drop for Server<'a, 'r, Req, Resp, B, TW, RW>
+where
+    B: AsRef<[Slot<Request<'r, Req, Resp>>]>,
+    TW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    RW: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn drop(&mut self) {
+        unsafe { ManuallyDrop::drop(&mut self.rx) };
+
+        // The last servers to go may all get here, and take turns refusing the requests.
+        if !self.requests.has_rxs() {
+            self.requests.close();
+            while let Some((_request, responder)) = self.requests.reclaim() {
+                responder.refuse();
+            }
+        }
+    }
+}
+
+impl<Resp, P, W> Drop for Replies<Resp, P, W>
+where
+    P: AsRef<[ReplyLink<Resp>]>,
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn drop(&mut self) {
+        if *self.refs.get_mut() != 0 {
+            crate::leak::report::<Self>("Dropping Replies that is still referenced?")
+        }
+    }
+}
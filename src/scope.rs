@@ -0,0 +1,196 @@
+use core::cell::Cell;
+use core::marker::PhantomData;
+use core::ptr::NonNull;
+use core::sync::atomic::AtomicBool;
+
+use crate::atomic_waker::AtomicWaker;
+use crate::slot::Slot;
+use crate::{mpmc, spsc};
+
+/// The link of a [`spsc::direct`] channel made by [`Scope::spsc_direct`].
+pub type SpscDirectLink<T> = spsc::direct::Link<T>;
+
+/// The link of a [`spsc::buffered`] channel made by [`Scope::spsc_buffered`].
+pub type SpscBufferedLink<T, const N: usize> = spsc::buffered::Link<T, [Slot<T>; N]>;
+
+/// The link of a [`mpmc`] channel made by [`Scope::mpmc`].
+pub type MpmcLink<T, const N: usize, const W: usize> =
+    mpmc::Link<T, [Slot<T>; N], [(AtomicBool, AtomicWaker); W], [(AtomicBool, AtomicWaker); W]>;
+
+/// Calls `f` with a [`Scope`] in which links can be made.
+///
+/// The links are kept in [`Storage`]s declared by the caller outside of `f`, and the endpoints
+/// borrow them for no longer than `f`. When `f` returns (or unwinds), each link made in the scope
+/// is closed, the endpoints still attached to it (leaked ones) are detached, and the link is
+/// dropped, so no leak is reported to [`leak`](crate::leak).
+///
+/// The links are not kept in the frame of `scope` itself: their types and sizes are only known
+/// inside `f`, and the crate does not allocate, so `scope` has no room of its own to put them in.
+/// A [`Storage`] gives a link that room while leaving its lifetime to the scope: the caller only
+/// declares it.
+pub fn scope<'env, F, O>(f: F) -> O
+where
+    F: for<'scope> FnOnce(&'scope Scope<'scope, 'env>) -> O,
+{
+    let scope = Scope { stored: Chain(Cell::new(None)), _scope: PhantomData, _env: PhantomData };
+    f(&scope)
+}
+
+/// Makes links in the [`Storage`]s it is given, releases them at the end of [`scope`].
+pub struct Scope<'scope, 'env: 'scope> {
+    stored: Chain,
+    _scope: PhantomData<&'scope mut &'scope ()>,
+    _env: PhantomData<&'env mut &'env ()>,
+}
+
+/// Room for a link made in a [`scope`].
+///
+/// Declared by the caller next to the [`scope`] call, so that the link lives in the caller's frame.
+/// A storage can be used again after the scope ends.
+pub struct Storage<L> {
+    link: Option<L>,
+    next: Option<Stored>,
+}
+
+impl<'scope, 'env> Scope<'scope, 'env> {
+    /// Makes a [`spsc::direct`] channel in `storage`.
+    #[allow(clippy::type_complexity)]
+    pub fn spsc_direct<T: 'env>(
+        &'scope self,
+        storage: &'env mut Storage<SpscDirectLink<T>>,
+    ) -> (
+        spsc::direct::Tx<T, &'scope SpscDirectLink<T>>,
+        spsc::direct::Rx<T, &'scope SpscDirectLink<T>>,
+    ) {
+        let link = self.store(storage, SpscDirectLink::new());
+        (spsc::direct::Tx::new(link), spsc::direct::Rx::new(link))
+    }
+
+    /// Makes a [`spsc::buffered`] channel with a buffer of `N` slots in `storage`.
+    #[allow(clippy::type_complexity)]
+    pub fn spsc_buffered<T: 'env, const N: usize>(
+        &'scope self,
+        storage: &'env mut Storage<SpscBufferedLink<T, N>>,
+    ) -> (
+        spsc::buffered::Tx<T, &'scope SpscBufferedLink<T, N>, [Slot<T>; N]>,
+        spsc::buffered::Rx<T, &'scope SpscBufferedLink<T, N>, [Slot<T>; N]>,
+    ) {
+        let link = self
+            .store(storage, SpscBufferedLink::new(core::array::from_fn(|_| Default::default())));
+        (spsc::buffered::Tx::new(link), spsc::buffered::Rx::new(link))
+    }
+
+    /// Makes a [`mpmc`] channel with a buffer of `N` slots and room for `W` senders and `W`
+    /// receivers in `storage`.
+    #[allow(clippy::type_complexity)]
+    pub fn mpmc<T: 'env, const N: usize, const W: usize>(
+        &'scope self,
+        storage: &'env mut Storage<MpmcLink<T, N, W>>,
+    ) -> (
+        mpmc::Tx<
+            T,
+            &'scope MpmcLink<T, N, W>,
+            [Slot<T>; N],
+            [(AtomicBool, AtomicWaker); W],
+            [(AtomicBool, AtomicWaker); W],
+        >,
+        mpmc::Rx<
+            T,
+            &'scope MpmcLink<T, N, W>,
+            [Slot<T>; N],
+            [(AtomicBool, AtomicWaker); W],
+            [(AtomicBool, AtomicWaker); W],
+        >,
+    ) {
+        let link = self.store(
+            storage,
+            MpmcLink::new(
+                core::array::from_fn(|_| Default::default()),
+                core::array::from_fn(|_| Default::default()),
+                core::array::from_fn(|_| Default::default()),
+            ),
+        );
+        (mpmc::Tx::new(link), mpmc::Rx::new(link))
+    }
+
+    fn store<L: Release + 'env>(&'scope self, storage: &'env mut Storage<L>, link: L) -> &'scope L {
+        storage.next = self.stored.0.take();
+        let link: *const L = storage.link.insert(link);
+        self.stored
+            .0
+            .set(Some(Stored { storage: NonNull::from(storage).cast(), release: release::<L> }));
+
+        // The storage outlives the scope, and the scope is the only one to touch it again.
+        unsafe { &*link }
+    }
+}
+
+impl<L> Storage<L> {
+    /// Creates an empty [`Storage`]
+    pub const fn new() -> Self {
+        Self { link: None, next: None }
+    }
+}
+
+impl<L> Default for Storage<L> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for Chain {
+    fn drop(&mut self) {
+        let mut next = self.0.take();
+        while let Some(stored) = next {
+            // Nothing borrows the links anymore: the endpoints cannot outlive `'scope`.
+            next = unsafe { (stored.release)(stored.storage) };
+        }
+    }
+}
+
+/// The storages used in a [`Scope`], the last one first.
+///
+/// Kept apart from the [`Scope`] that is borrowed for `'scope`, so that it can release the links as
+/// the scope is dropped.
+struct Chain(Cell<Option<Stored>>);
+
+/// A [`Storage`] a link has been made in, chained to the ones made before it.
+#[derive(Clone, Copy)]
+struct Stored {
+    storage: NonNull<()>,
+    release: unsafe fn(NonNull<()>) -> Option<Stored>,
+}
+
+/// Releases and drops the link in the storage, returns the storage used before it.
+///
+/// # Safety
+/// `storage` points to a live `Storage<L>` that nothing else accesses.
+unsafe fn release<L: Release>(storage: NonNull<()>) -> Option<Stored> {
+    let storage = unsafe { storage.cast::<Storage<L>>().as_mut() };
+    if let Some(mut link) = storage.link.take() {
+        link.release();
+    }
+    storage.next.take()
+}
+
+trait Release {
+    fn release(&mut self);
+}
+
+impl<T> Release for SpscDirectLink<T> {
+    fn release(&mut self) {
+        self.reset()
+    }
+}
+
+impl<T, const N: usize> Release for SpscBufferedLink<T, N> {
+    fn release(&mut self) {
+        self.reset()
+    }
+}
+
+impl<T, const N: usize, const W: usize> Release for MpmcLink<T, N, W> {
+    fn release(&mut self) {
+        self.detach_all()
+    }
+}
@@ -0,0 +1,196 @@
+use core::future;
+use core::sync::atomic::{AtomicUsize, Ordering};
+use core::task::{Context, Poll};
+
+use crate::error::RecvError;
+
+/// A receiving side that can be polled for its next value.
+///
+/// Polling registers the caller's waker with the link, so that one task may wait on several
+/// receivers at once.
+pub trait PollRecv {
+    /// The values received.
+    type Item;
+    /// The reason the link may be closed with.
+    type Reason;
+
+    /// Receives a value if it is ready, registers the waker otherwise.
+    fn poll_recv(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Self::Item, RecvError<Self::Reason>>>;
+
+    /// Closes the channel.
+    fn close(&mut self);
+}
+
+impl<X> PollRecv for &mut X
+where
+    X: PollRecv + ?Sized,
+{
+    type Item = X::Item;
+    type Reason = X::Reason;
+
+    fn poll_recv(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Self::Item, RecvError<Self::Reason>>> {
+        X::poll_recv(self, cx)
+    }
+
+    fn close(&mut self) {
+        X::close(self)
+    }
+}
+
+/// Receives from an array of receivers of the same type.
+///
+/// The receivers are polled in rotating order: the one after the receiver that yielded last goes
+/// first, so that a busy receiver cannot starve the others.
+pub struct Merge<X, const N: usize> {
+    rxs: [X; N],
+    closed: [bool; N],
+    next: usize,
+}
+
+impl<X, const N: usize> Merge<X, N>
+where
+    X: PollRecv,
+{
+    /// Creates a new [`Merge`]
+    pub fn new(rxs: [X; N]) -> Self {
+        Self { rxs, closed: [false; N], next: 0 }
+    }
+
+    /// Receives a value along with the index of its receiver, waits if necessary.
+    ///
+    /// A receiver that got closed yields its error once and is skipped afterwards. Returns `None`
+    /// once all the receivers are closed.
+    #[allow(clippy::type_complexity)]
+    pub async fn recv(&mut self) -> Option<(usize, Result<X::Item, RecvError<X::Reason>>)> {
+        future::poll_fn(|cx| self.poll_recv(cx)).await
+    }
+
+    /// Same as [`Merge::recv`], for use in hand-written futures.
+    #[allow(clippy::type_complexity)]
+    pub fn poll_recv(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<(usize, Result<X::Item, RecvError<X::Reason>>)>> {
+        let mut all_closed = true;
+
+        for step in 0..N {
+            let idx = (self.next + step) % N;
+            if self.closed[idx] {
+                continue
+            }
+            all_closed = false;
+
+            if let Poll::Ready(result) = self.rxs[idx].poll_recv(cx) {
+                self.closed[idx] = result.is_err();
+                self.next = (idx + 1) % N;
+                return Poll::Ready(Some((idx, result)))
+            }
+        }
+
+        if all_closed {
+            Poll::Ready(None)
+        } else {
+            Poll::Pending
+        }
+    }
+
+    /// Whether the receiver at `idx` got closed.
+    pub fn is_closed(&self, idx: usize) -> bool {
+        self.closed[idx]
+    }
+
+    /// Closes all the receivers.
+    pub fn close(&mut self) {
+        self.rxs.iter_mut().for_each(X::close)
+    }
+
+    /// Takes the receivers back.
+    pub fn into_inner(self) -> [X; N] {
+        self.rxs
+    }
+}
+
+/// Picks the branch a [`select!`](crate::select!) polls first, advancing the rotation of its call
+/// site kept in `next`.
+#[doc(hidden)]
+pub fn first_branch(next: &AtomicUsize, branches: usize) -> usize {
+    next.fetch_add(1, Ordering::Relaxed) % branches
+}
+
+/// Waits on several receivers at once, runs the branch of the first one that yields.
+///
+/// Each branch reads `pattern = receiver => body`, where the receiver implements
+/// [`PollRecv`](crate::select::PollRecv) and the pattern binds the whole
+/// `Result<Item, RecvError<Reason>>`; it must be irrefutable. The receivers may be of different
+/// types. Successive runs of the same `select!` start polling from a different branch, so that a
+/// busy receiver cannot starve the others. Up to 32 branches are supported.
+#[macro_export]
+macro_rules! select {
+    (@munch { $($done:tt)* } [ $name:ident $($names:ident)* ]
+        $pat:pat = $rx:expr => $body:block , $($rest:tt)*) => {
+        $crate::select!(@munch { $($done)* ($name, $pat, $rx, $body) } [ $($names)* ] $($rest)*)
+    };
+    (@munch { $($done:tt)* } [ $name:ident $($names:ident)* ]
+        $pat:pat = $rx:expr => $body:block $($rest:tt)*) => {
+        $crate::select!(@munch { $($done)* ($name, $pat, $rx, $body) } [ $($names)* ] $($rest)*)
+    };
+    (@munch { $($done:tt)* } [ $name:ident $($names:ident)* ]
+        $pat:pat = $rx:expr => $body:expr $(, $($rest:tt)*)?) => {
+        $crate::select!(@munch { $($done)* ($name, $pat, $rx, $body) } [ $($names)* ] $($($rest)*)?)
+    };
+    (@munch { $($done:tt)* } [ ] $($rest:tt)+) => {
+        ::core::compile_error!("select! supports up to 32 branches")
+    };
+    (@munch { $( ($name:ident, $pat:pat, $rx:expr, $body:expr) )+ } [ $($names:ident)* ]) => {{
+        use $crate::select::PollRecv as _;
+
+        let branches = [$(::core::stringify!($name)),+].len();
+        let first = {
+            static NEXT: ::core::sync::atomic::AtomicUsize =
+                ::core::sync::atomic::AtomicUsize::new(0);
+            $crate::select::first_branch(&NEXT, branches)
+        };
+        $( let mut $name = (&mut $rx, ::core::option::Option::None); )+
+
+        ::core::future::poll_fn(|cx| {
+            for step in 0..branches {
+                let target = (first + step) % branches;
+                let mut branch = 0;
+                $(
+                    if branch == target {
+                        if let ::core::task::Poll::Ready(result) = $name.0.poll_recv(cx) {
+                            $name.1 = ::core::option::Option::Some(result);
+                            return ::core::task::Poll::Ready(())
+                        }
+                    }
+                    branch += 1;
+                )+
+                let _ = branch;
+            }
+            ::core::task::Poll::Pending
+        })
+        .await;
+
+        $(
+            if let ::core::option::Option::Some(result) = $name.1.take() {
+                let $pat = result;
+                $body
+            } else
+        )+
+        {
+            ::core::unreachable!("select! woke up without a value")
+        }
+    }};
+    ($($branches:tt)+) => {
+        $crate::select!(@munch { } [
+            b00 b01 b02 b03 b04 b05 b06 b07 b08 b09 b10 b11 b12 b13 b14 b15
+            b16 b17 b18 b19 b20 b21 b22 b23 b24 b25 b26 b27 b28 b29 b30 b31
+        ] $($branches)+)
+    };
+}
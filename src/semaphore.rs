@@ -0,0 +1,124 @@
+use core::future;
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use crate::atomic_waker::AtomicWaker;
+use crate::error::{AcquireError, LimitReached, NoPermits};
+use crate::mpmc::wakers;
+use crate::utils::{self, AtomicUpdate};
+
+/// A counting semaphore.
+///
+/// A task that has to wait for permits holds one of the wakers in `W` until it gets them, so `W`
+/// bounds the number of tasks waiting at once. Permits given back wake all the waiting tasks; the
+/// ones that find too few permits wait again.
+pub struct Semaphore<W>
+where
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    permits: AtomicUsize,
+    total: usize,
+
+    refs: AtomicUsize,
+
+    wakers: W,
+}
+
+/// Permits taken from a [`Semaphore`], given back when dropped.
+pub struct Permit<'a, W>
+where
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    semaphore: &'a Semaphore<W>,
+    count: usize,
+}
+
+impl<W> Semaphore<W>
+where
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    /// Creates a new [`Semaphore`] with `permits` permits.
+    pub fn new(permits: usize, wakers: W) -> Self {
+        Self {
+            permits: AtomicUsize::new(permits),
+            total: permits,
+            refs: Default::default(),
+            wakers,
+        }
+    }
+
+    /// The number of permits currently available.
+    pub fn available_permits(&self) -> usize {
+        self.permits.load(Ordering::SeqCst)
+    }
+
+    /// Takes `n` permits if they are available.
+    pub fn try_acquire(&self, n: usize) -> Result<Permit<'_, W>, NoPermits> {
+        match utils::compare_exchange_loop(
+            &self.permits,
+            utils::ATOMIC_UPDATE_MAX_ITERATIONS,
+            None,
+            |permits| {
+                if permits < n {
+                    Err(NoPermits)
+                } else {
+                    Ok(AtomicUpdate::Set(permits - n))
+                }
+            },
+        ) {
+            Ok(_) => Ok(Permit { semaphore: self, count: n }),
+            Err(None) => panic!("Failed to perform atomic update"),
+            Err(Some(e)) => Err(e),
+        }
+    }
+
+    /// Takes `n` permits, waits if necessary.
+    ///
+    /// Fails with [`AcquireError::TooMany`] if `n` exceeds the number of permits the semaphore was
+    /// created with, and with [`AcquireError::LimitReached`] if it has to wait while all the wakers
+    /// are taken.
+    pub async fn acquire(&self, n: usize) -> Result<Permit<'_, W>, AcquireError> {
+        if n > self.total {
+            return Err(AcquireError::TooMany)
+        }
+
+        let mut waiter = wakers::Waiter::new(&self.refs, self.wakers.as_ref());
+        future::poll_fn(|cx| waiter.poll(cx.waker(), || self.try_acquire(n).ok()))
+            .await
+            .map_err(|LimitReached| AcquireError::LimitReached)
+    }
+
+    fn release(&self, n: usize) {
+        self.permits.fetch_add(n, Ordering::SeqCst);
+        wakers::notify(self.wakers.as_ref());
+    }
+}
+
+impl<W> Permit<'_, W>
+where
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    /// The number of permits held.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+}
+
+impl<W> Drop for Permit<'_, W>
+where
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn drop(&mut self) {
+        self.semaphore.release(self.count);
+    }
+}
+
+impl<W> Drop for Semaphore<W>
+where
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn drop(&mut self) {
+        if *self.refs.get_mut() != 0 {
+            crate::leak::report::<Self>("Dropping Semaphore that is still referenced?")
+        }
+    }
+}
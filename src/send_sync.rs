@@ -9,14 +9,14 @@ mod spsc_direct {
     use crate::spsc::direct::*;
     use core::borrow::Borrow;
 
-    unsafe impl<T: Send> Send for Link<T> {}
-    unsafe impl<T: Send> Sync for Link<T> {}
+    unsafe impl<T: Send, R: Send> Send for Link<T, R> {}
+    unsafe impl<T: Send, R: Send + Sync> Sync for Link<T, R> {}
 
-    unsafe impl<T: Send, L: Send> Send for Tx<T, L> where L: Borrow<Link<T>> {}
-    unsafe impl<T: Send, L: Sync> Sync for Tx<T, L> where L: Borrow<Link<T>> {}
+    unsafe impl<T: Send, L: Send, R> Send for Tx<T, L, R> where L: Borrow<Link<T, R>> {}
+    unsafe impl<T: Send, L: Sync, R> Sync for Tx<T, L, R> where L: Borrow<Link<T, R>> {}
 
-    unsafe impl<T: Send, L: Send> Send for Rx<T, L> where L: Borrow<Link<T>> {}
-    unsafe impl<T: Send, L: Sync> Sync for Rx<T, L> where L: Borrow<Link<T>> {}
+    unsafe impl<T: Send, L: Send, R> Send for Rx<T, L, R> where L: Borrow<Link<T, R>> {}
+    unsafe impl<T: Send, L: Sync, R> Sync for Rx<T, L, R> where L: Borrow<Link<T, R>> {}
 }
 
 mod spsc_buffered {
@@ -24,31 +24,80 @@ mod spsc_buffered {
     use crate::spsc::buffered::*;
     use core::borrow::Borrow;
 
-    unsafe impl<T: Send, B: Send> Send for Link<T, B> where B: AsRef<[Slot<T>]> {}
-    unsafe impl<T: Send, B: Sync> Sync for Link<T, B> where B: AsRef<[Slot<T>]> {}
+    unsafe impl<T: Send, B: Send, R: Send> Send for Link<T, B, R> where B: AsRef<[Slot<T>]> {}
+    unsafe impl<T: Send, B: Sync, R: Send + Sync> Sync for Link<T, B, R> where B: AsRef<[Slot<T>]> {}
 
-    unsafe impl<T: Send, L: Send, B> Send for Tx<T, L, B>
+    unsafe impl<T: Send, L: Send, B, R> Send for Tx<T, L, B, R>
     where
-        L: Borrow<Link<T, B>>,
+        L: Borrow<Link<T, B, R>>,
         B: AsRef<[Slot<T>]>,
     {
     }
-    unsafe impl<T: Send, L: Sync, B> Sync for Tx<T, L, B>
+    unsafe impl<T: Send, L: Sync, B, R> Sync for Tx<T, L, B, R>
     where
-        L: Borrow<Link<T, B>>,
+        L: Borrow<Link<T, B, R>>,
         B: AsRef<[Slot<T>]>,
     {
     }
 
-    unsafe impl<T: Send, L: Send, B> Send for Rx<T, L, B>
+    unsafe impl<T: Send, L: Send, B, R> Send for Rx<T, L, B, R>
     where
-        L: Borrow<Link<T, B>>,
+        L: Borrow<Link<T, B, R>>,
         B: AsRef<[Slot<T>]>,
     {
     }
-    unsafe impl<T: Send, L: Sync, B> Sync for Rx<T, L, B>
+    unsafe impl<T: Send, L: Sync, B, R> Sync for Rx<T, L, B, R>
     where
-        L: Borrow<Link<T, B>>,
+        L: Borrow<Link<T, B, R>>,
+        B: AsRef<[Slot<T>]>,
+    {
+    }
+}
+
+mod spsc_buffered_growable {
+    use crate::slot::Slot;
+    use crate::spsc::buffered::growable::*;
+    use core::borrow::Borrow;
+
+    unsafe impl<T: Send, B: Send, R: Send> Send for Link<T, B, R> where B: AsRef<[Slot<T>]> {}
+    unsafe impl<T: Send, B: Send + Sync, R: Send + Sync> Sync for Link<T, B, R> where B: AsRef<[Slot<T>]>
+    {}
+
+    unsafe impl<T: Send, L: Send, B, R> Send for Tx<T, L, B, R>
+    where
+        L: Borrow<Link<T, B, R>>,
+        B: AsRef<[Slot<T>]>,
+    {
+    }
+    unsafe impl<T: Send, L: Sync, B, R> Sync for Tx<T, L, B, R>
+    where
+        L: Borrow<Link<T, B, R>>,
+        B: AsRef<[Slot<T>]>,
+    {
+    }
+
+    unsafe impl<T: Send, L: Send, B, R> Send for Rx<T, L, B, R>
+    where
+        L: Borrow<Link<T, B, R>>,
+        B: AsRef<[Slot<T>]>,
+    {
+    }
+    unsafe impl<T: Send, L: Sync, B, R> Sync for Rx<T, L, B, R>
+    where
+        L: Borrow<Link<T, B, R>>,
+        B: AsRef<[Slot<T>]>,
+    {
+    }
+
+    unsafe impl<T: Send, L: Send, B, R> Send for Resizer<T, L, B, R>
+    where
+        L: Borrow<Link<T, B, R>>,
+        B: AsRef<[Slot<T>]>,
+    {
+    }
+    unsafe impl<T: Send, L: Sync, B, R> Sync for Resizer<T, L, B, R>
+    where
+        L: Borrow<Link<T, B, R>>,
         B: AsRef<[Slot<T>]>,
     {
     }
@@ -62,52 +111,342 @@ mod mpmc {
     use crate::mpmc::*;
     use crate::slot::Slot;
 
-    unsafe impl<T: Send, B: Send, TW: Send, RW: Send> Send for Link<T, B, TW, RW>
+    unsafe impl<T: Send, B: Send, TW: Send, RW: Send, R: Send> Send for Link<T, B, TW, RW, R>
+    where
+        B: AsRef<[Slot<T>]>,
+        TW: AsRef<[(AtomicBool, AtomicWaker)]>,
+        RW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    {
+    }
+    unsafe impl<T: Send, B: Sync, TW: Sync, RW: Sync, R: Send + Sync> Sync for Link<T, B, TW, RW, R>
+    where
+        B: AsRef<[Slot<T>]>,
+        TW: AsRef<[(AtomicBool, AtomicWaker)]>,
+        RW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    {
+    }
+
+    unsafe impl<T: Send, L: Send, B, TW, RW, R> Send for Tx<T, L, B, TW, RW, R>
     where
+        L: Borrow<Link<T, B, TW, RW, R>>,
         B: AsRef<[Slot<T>]>,
         TW: AsRef<[(AtomicBool, AtomicWaker)]>,
         RW: AsRef<[(AtomicBool, AtomicWaker)]>,
     {
     }
-    unsafe impl<T: Send, B: Sync, TW: Sync, RW: Sync> Sync for Link<T, B, TW, RW>
+    unsafe impl<T: Send, L: Sync, B, TW, RW, R> Sync for Tx<T, L, B, TW, RW, R>
     where
+        L: Borrow<Link<T, B, TW, RW, R>>,
         B: AsRef<[Slot<T>]>,
         TW: AsRef<[(AtomicBool, AtomicWaker)]>,
         RW: AsRef<[(AtomicBool, AtomicWaker)]>,
     {
     }
 
-    unsafe impl<T: Send, L: Send, B, TW, RW> Send for Tx<T, L, B, TW, RW>
+    unsafe impl<T: Send, L: Send, B, TW, RW, R> Send for WeakTx<T, L, B, TW, RW, R>
     where
-        L: Borrow<Link<T, B, TW, RW>>,
+        L: Borrow<Link<T, B, TW, RW, R>>,
         B: AsRef<[Slot<T>]>,
         TW: AsRef<[(AtomicBool, AtomicWaker)]>,
         RW: AsRef<[(AtomicBool, AtomicWaker)]>,
     {
     }
-    unsafe impl<T: Send, L: Sync, B, TW, RW> Sync for Tx<T, L, B, TW, RW>
+    unsafe impl<T: Send, L: Sync, B, TW, RW, R> Sync for WeakTx<T, L, B, TW, RW, R>
     where
-        L: Borrow<Link<T, B, TW, RW>>,
+        L: Borrow<Link<T, B, TW, RW, R>>,
         B: AsRef<[Slot<T>]>,
         TW: AsRef<[(AtomicBool, AtomicWaker)]>,
         RW: AsRef<[(AtomicBool, AtomicWaker)]>,
     {
     }
 
-    unsafe impl<T: Send, L: Send, B, TW, RW> Send for Rx<T, L, B, TW, RW>
+    unsafe impl<T: Send, L: Send, B, TW, RW, R> Send for Rx<T, L, B, TW, RW, R>
     where
-        L: Borrow<Link<T, B, TW, RW>>,
+        L: Borrow<Link<T, B, TW, RW, R>>,
         B: AsRef<[Slot<T>]>,
         TW: AsRef<[(AtomicBool, AtomicWaker)]>,
         RW: AsRef<[(AtomicBool, AtomicWaker)]>,
     {
     }
-    unsafe impl<T: Send, L: Sync, B, TW, RW> Sync for Rx<T, L, B, TW, RW>
+    unsafe impl<T: Send, L: Sync, B, TW, RW, R> Sync for Rx<T, L, B, TW, RW, R>
     where
-        L: Borrow<Link<T, B, TW, RW>>,
+        L: Borrow<Link<T, B, TW, RW, R>>,
         B: AsRef<[Slot<T>]>,
         TW: AsRef<[(AtomicBool, AtomicWaker)]>,
         RW: AsRef<[(AtomicBool, AtomicWaker)]>,
     {
     }
 }
+
+mod mpsc {
+    use core::borrow::Borrow;
+    use core::sync::atomic::AtomicBool;
+
+    use crate::atomic_waker::AtomicWaker;
+    use crate::mpsc::*;
+    use crate::slot::Slot;
+
+    unsafe impl<T: Send, B: Send, TW: Send, R: Send> Send for Link<T, B, TW, R>
+    where
+        B: AsRef<[Slot<T>]>,
+        TW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    {
+    }
+    unsafe impl<T: Send, B: Sync, TW: Sync, R: Send + Sync> Sync for Link<T, B, TW, R>
+    where
+        B: AsRef<[Slot<T>]>,
+        TW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    {
+    }
+
+    unsafe impl<T: Send, L: Send, B, TW, R> Send for Tx<T, L, B, TW, R>
+    where
+        L: Borrow<Link<T, B, TW, R>>,
+        B: AsRef<[Slot<T>]>,
+        TW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    {
+    }
+    unsafe impl<T: Send, L: Sync, B, TW, R> Sync for Tx<T, L, B, TW, R>
+    where
+        L: Borrow<Link<T, B, TW, R>>,
+        B: AsRef<[Slot<T>]>,
+        TW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    {
+    }
+
+    unsafe impl<T: Send, L: Send, B, TW, R> Send for Rx<T, L, B, TW, R>
+    where
+        L: Borrow<Link<T, B, TW, R>>,
+        B: AsRef<[Slot<T>]>,
+        TW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    {
+    }
+    unsafe impl<T: Send, L: Sync, B, TW, R> Sync for Rx<T, L, B, TW, R>
+    where
+        L: Borrow<Link<T, B, TW, R>>,
+        B: AsRef<[Slot<T>]>,
+        TW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    {
+    }
+}
+
+mod spmc {
+    use core::borrow::Borrow;
+    use core::sync::atomic::AtomicBool;
+
+    use crate::atomic_waker::AtomicWaker;
+    use crate::slot::Slot;
+    use crate::spmc::*;
+
+    unsafe impl<T: Send, B: Send, RW: Send, R: Send> Send for Link<T, B, RW, R>
+    where
+        B: AsRef<[Slot<T>]>,
+        RW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    {
+    }
+    unsafe impl<T: Send, B: Sync, RW: Sync, R: Send + Sync> Sync for Link<T, B, RW, R>
+    where
+        B: AsRef<[Slot<T>]>,
+        RW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    {
+    }
+
+    unsafe impl<T: Send, L: Send, B, RW, R> Send for Tx<T, L, B, RW, R>
+    where
+        L: Borrow<Link<T, B, RW, R>>,
+        B: AsRef<[Slot<T>]>,
+        RW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    {
+    }
+    unsafe impl<T: Send, L: Sync, B, RW, R> Sync for Tx<T, L, B, RW, R>
+    where
+        L: Borrow<Link<T, B, RW, R>>,
+        B: AsRef<[Slot<T>]>,
+        RW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    {
+    }
+
+    unsafe impl<T: Send, L: Send, B, RW, R> Send for Rx<T, L, B, RW, R>
+    where
+        L: Borrow<Link<T, B, RW, R>>,
+        B: AsRef<[Slot<T>]>,
+        RW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    {
+    }
+    unsafe impl<T: Send, L: Sync, B, RW, R> Sync for Rx<T, L, B, RW, R>
+    where
+        L: Borrow<Link<T, B, RW, R>>,
+        B: AsRef<[Slot<T>]>,
+        RW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    {
+    }
+}
+
+mod mpmc_prio {
+    use core::borrow::Borrow;
+    use core::sync::atomic::AtomicBool;
+
+    use crate::atomic_waker::AtomicWaker;
+    use crate::mpmc::prio::*;
+    use crate::slot::Slot;
+
+    unsafe impl<T: Send, B: Send, TW: Send, RW: Send, const K: usize, R: Send> Send
+        for Link<T, B, TW, RW, K, R>
+    where
+        B: AsRef<[Slot<T>]>,
+        TW: AsRef<[(AtomicBool, AtomicWaker)]>,
+        RW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    {
+    }
+    unsafe impl<T: Send, B: Sync, TW: Sync, RW: Sync, const K: usize, R: Send + Sync> Sync
+        for Link<T, B, TW, RW, K, R>
+    where
+        B: AsRef<[Slot<T>]>,
+        TW: AsRef<[(AtomicBool, AtomicWaker)]>,
+        RW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    {
+    }
+
+    unsafe impl<T: Send, L: Send, B, TW, RW, const K: usize, R> Send for Tx<T, L, B, TW, RW, K, R>
+    where
+        L: Borrow<Link<T, B, TW, RW, K, R>>,
+        B: AsRef<[Slot<T>]>,
+        TW: AsRef<[(AtomicBool, AtomicWaker)]>,
+        RW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    {
+    }
+    unsafe impl<T: Send, L: Sync, B, TW, RW, const K: usize, R> Sync for Tx<T, L, B, TW, RW, K, R>
+    where
+        L: Borrow<Link<T, B, TW, RW, K, R>>,
+        B: AsRef<[Slot<T>]>,
+        TW: AsRef<[(AtomicBool, AtomicWaker)]>,
+        RW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    {
+    }
+
+    unsafe impl<T: Send, L: Send, B, TW, RW, const K: usize, R> Send for Rx<T, L, B, TW, RW, K, R>
+    where
+        L: Borrow<Link<T, B, TW, RW, K, R>>,
+        B: AsRef<[Slot<T>]>,
+        TW: AsRef<[(AtomicBool, AtomicWaker)]>,
+        RW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    {
+    }
+    unsafe impl<T: Send, L: Sync, B, TW, RW, const K: usize, R> Sync for Rx<T, L, B, TW, RW, K, R>
+    where
+        L: Borrow<Link<T, B, TW, RW, K, R>>,
+        B: AsRef<[Slot<T>]>,
+        TW: AsRef<[(AtomicBool, AtomicWaker)]>,
+        RW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    {
+    }
+}
+
+mod heap {
+    use crate::heap::*;
+    use crate::slot::Slot;
+    use core::borrow::Borrow;
+
+    unsafe impl<T: Send, B: Send, R: Send> Send for Link<T, B, R> where B: AsRef<[Slot<T>]> {}
+    unsafe impl<T: Send, B: Sync, R: Send + Sync> Sync for Link<T, B, R> where B: AsRef<[Slot<T>]> {}
+
+    unsafe impl<T: Send, L: Send, B, R> Send for Tx<T, L, B, R>
+    where
+        L: Borrow<Link<T, B, R>>,
+        B: AsRef<[Slot<T>]>,
+    {
+    }
+    unsafe impl<T: Send, L: Sync, B, R> Sync for Tx<T, L, B, R>
+    where
+        L: Borrow<Link<T, B, R>>,
+        B: AsRef<[Slot<T>]>,
+    {
+    }
+
+    unsafe impl<T: Send, L: Send, B, R> Send for Rx<T, L, B, R>
+    where
+        L: Borrow<Link<T, B, R>>,
+        B: AsRef<[Slot<T>]>,
+    {
+    }
+    unsafe impl<T: Send, L: Sync, B, R> Sync for Rx<T, L, B, R>
+    where
+        L: Borrow<Link<T, B, R>>,
+        B: AsRef<[Slot<T>]>,
+    {
+    }
+}
+
+#[cfg(feature = "alloc")]
+mod unbounded {
+    use core::borrow::Borrow;
+    use core::sync::atomic::AtomicBool;
+
+    use crate::atomic_waker::AtomicWaker;
+    use crate::unbounded::*;
+
+    unsafe impl<T: Send, W: Send, R: Send> Send for Link<T, W, R> where
+        W: AsRef<[(AtomicBool, AtomicWaker)]>
+    {
+    }
+    unsafe impl<T: Send, W: Sync, R: Send + Sync> Sync for Link<T, W, R> where
+        W: AsRef<[(AtomicBool, AtomicWaker)]>
+    {
+    }
+
+    unsafe impl<T: Send, L: Send, W, R> Send for Tx<T, L, W, R>
+    where
+        L: Borrow<Link<T, W, R>>,
+        W: AsRef<[(AtomicBool, AtomicWaker)]>,
+    {
+    }
+    unsafe impl<T: Send, L: Sync, W, R> Sync for Tx<T, L, W, R>
+    where
+        L: Borrow<Link<T, W, R>>,
+        W: AsRef<[(AtomicBool, AtomicWaker)]>,
+    {
+    }
+
+    unsafe impl<T: Send, L: Send, W, R> Send for Rx<T, L, W, R>
+    where
+        L: Borrow<Link<T, W, R>>,
+        W: AsRef<[(AtomicBool, AtomicWaker)]>,
+    {
+    }
+    unsafe impl<T: Send, L: Sync, W, R> Sync for Rx<T, L, W, R>
+    where
+        L: Borrow<Link<T, W, R>>,
+        W: AsRef<[(AtomicBool, AtomicWaker)]>,
+    {
+    }
+}
+
+mod duplex {
+    use crate::duplex::*;
+    use crate::slot::Slot;
+    use core::borrow::Borrow;
+
+    unsafe impl<A: Send, B: Send, L: Send, BA, BB, S, R> Send for End<A, B, L, BA, BB, S, R>
+    where
+        L: Borrow<Link<A, B, BA, BB, R>>,
+        BA: AsRef<[Slot<A>]>,
+        BB: AsRef<[Slot<B>]>,
+        S: Side<A, B, BA, BB, R>,
+    {
+    }
+    unsafe impl<A: Send, B: Send, L: Sync, BA, BB, S, R> Sync for End<A, B, L, BA, BB, S, R>
+    where
+        L: Borrow<Link<A, B, BA, BB, R>>,
+        BA: AsRef<[Slot<A>]>,
+        BB: AsRef<[Slot<B>]>,
+        S: Side<A, B, BA, BB, R>,
+    {
+    }
+}
+
+mod scope {
+    use crate::scope::*;
+
+    unsafe impl<L: Send> Send for Storage<L> {}
+    unsafe impl<L: Sync> Sync for Storage<L> {}
+}
@@ -0,0 +1,208 @@
+use core::marker::PhantomData;
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use crate::atomic_waker::AtomicWaker;
+use crate::error::LimitReached;
+use crate::mpmc::wakers;
+use crate::slot::Slot;
+use crate::utils::{self, AtomicUpdate};
+
+type Usize = <AtomicUsize as crate::utils::AtomicValue>::Value;
+const USIZE_BITS: u8 = Usize::BITS as u8;
+
+const POS_IS_CANCELLED: u8 = 0;
+const FLAGS_COUNT: u8 = 1;
+const INDEX_BIT_COUNT: u8 = (USIZE_BITS - FLAGS_COUNT) / 2;
+const START_RESERVED: u8 = FLAGS_COUNT;
+const START_PUBLISHED: u8 = FLAGS_COUNT + INDEX_BIT_COUNT;
+
+/// A link that can be closed by a [`Token`].
+pub trait Close {
+    /// Closes the link, waking all its endpoints.
+    fn close(&self);
+}
+
+/// A slot of the registry a [`Token`] keeps the registered links in.
+pub type Registration<'a> = Slot<&'a (dyn Close + Sync)>;
+
+/// Closes a group of links at once.
+///
+/// The links are kept in `S`, registering more links than it has slots fails. In the same way,
+/// `W` bounds the number of tasks that can wait in [`Token::cancelled`] at once.
+pub struct Token<'a, S, W>
+where
+    S: AsRef<[Registration<'a>]>,
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    _links: PhantomData<&'a ()>,
+
+    registry: S,
+
+    /// 1bit cancelled flag [0]
+    /// two counters (31/15bit):
+    /// - reserved  [ 1..=31 / 1..=15 ]
+    /// - published [32..=62 / 16..=30]
+    bits: AtomicUsize,
+
+    refs: AtomicUsize,
+
+    wakers: W,
+}
+
+impl<'a, S, W> Token<'a, S, W>
+where
+    S: AsRef<[Registration<'a>]>,
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    /// Creates a new [`Token`]
+    pub fn new(registry: S, wakers: W) -> Self {
+        assert!(registry.as_ref().len() <= max_len());
+
+        Self {
+            _links: Default::default(),
+            registry,
+            bits: Default::default(),
+            refs: Default::default(),
+            wakers,
+        }
+    }
+
+    /// Registers a link to be closed on cancellation.
+    ///
+    /// If the token is cancelled already, the link is closed right away.
+    pub fn register(&self, link: &'a (dyn Close + Sync)) -> Result<(), LimitReached> {
+        let registry = self.registry.as_ref();
+
+        let idx = match utils::compare_exchange_loop(
+            &self.bits,
+            utils::ATOMIC_UPDATE_MAX_ITERATIONS,
+            None,
+            |bits| {
+                let reserved = reserved(bits);
+                match (is_cancelled(bits), reserved == registry.len()) {
+                    (true, _) => Err(Refused::Cancelled),
+                    (false, true) => Err(Refused::Full),
+                    (false, false) => Ok(AtomicUpdate::Set(set_reserved(bits, reserved + 1))),
+                }
+            },
+        ) {
+            Ok(bits) => reserved(bits) - 1,
+            Err(None) => panic!("Failed to perform atomic update"),
+            Err(Some(Refused::Full)) => return Err(LimitReached),
+            Err(Some(Refused::Cancelled)) => {
+                link.close();
+                return Ok(())
+            },
+        };
+
+        unsafe { registry[idx].as_maybe_uninit_mut() }.write(link);
+
+        // The registrations are published in the order they were reserved.
+        let bits = utils::compare_exchange_loop(
+            &self.bits,
+            utils::ATOMIC_UPDATE_MAX_ITERATIONS,
+            None,
+            |bits| {
+                if published(bits) == idx {
+                    Ok::<_, core::convert::Infallible>(AtomicUpdate::Set(set_published(
+                        bits,
+                        idx + 1,
+                    )))
+                } else {
+                    Ok(AtomicUpdate::Retry)
+                }
+            },
+        )
+        .expect("failed to perform atomic update");
+
+        // Cancelled before this link was published: the canceller may have not seen it.
+        if is_cancelled(bits) {
+            link.close();
+        }
+
+        Ok(())
+    }
+
+    /// Cancels the token: closes all the registered links and wakes the tasks waiting for the
+    /// cancellation.
+    pub fn cancel(&self) {
+        let bits = match utils::compare_exchange_loop(
+            &self.bits,
+            utils::ATOMIC_UPDATE_MAX_ITERATIONS,
+            None,
+            |bits| {
+                if is_cancelled(bits) {
+                    Err(())
+                } else {
+                    Ok(AtomicUpdate::Set(set_cancelled(bits)))
+                }
+            },
+        ) {
+            Ok(bits) => bits,
+            Err(None) => panic!("Failed to perform atomic update"),
+            Err(Some(())) => return,
+        };
+
+        for registration in &self.registry.as_ref()[..published(bits)] {
+            unsafe { registration.as_maybe_uninit_mut().assume_init_read() }.close();
+        }
+
+        wakers::notify(self.wakers.as_ref());
+    }
+
+    /// Whether the token is cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        is_cancelled(self.bits.load(Ordering::SeqCst))
+    }
+
+    /// Waits until the token is cancelled.
+    ///
+    /// Fails with [`LimitReached`] if it has to wait while all the wakers are taken.
+    pub async fn cancelled(&self) -> Result<(), LimitReached> {
+        let mut waiter = wakers::Waiter::new(&self.refs, self.wakers.as_ref());
+        core::future::poll_fn(|cx| waiter.poll(cx.waker(), || self.is_cancelled().then_some(())))
+            .await
+    }
+}
+
+impl<'a, S, W> Drop for Token<'a, S, W>
+where
+    S: AsRef<[Registration<'a>]>,
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn drop(&mut self) {
+        if *self.refs.get_mut() != 0 {
+            crate::leak::report::<Self>("Dropping Token that is still referenced?")
+        }
+    }
+}
+
+enum Refused {
+    Cancelled,
+    Full,
+}
+
+fn max_len() -> Usize {
+    !(Usize::MAX << INDEX_BIT_COUNT)
+}
+
+fn is_cancelled(bits: Usize) -> bool {
+    utils::bits::flag::<Usize, POS_IS_CANCELLED>(bits) != 0
+}
+fn set_cancelled(bits: Usize) -> Usize {
+    bits | utils::bits::flag::<Usize, POS_IS_CANCELLED>(utils::bits::ones())
+}
+
+fn reserved(bits: Usize) -> Usize {
+    utils::bits::unpack::<Usize, START_RESERVED, INDEX_BIT_COUNT>(bits)
+}
+fn set_reserved(bits: Usize, value: Usize) -> Usize {
+    utils::bits::pack::<Usize, START_RESERVED, INDEX_BIT_COUNT>(bits, value)
+}
+
+fn published(bits: Usize) -> Usize {
+    utils::bits::unpack::<Usize, START_PUBLISHED, INDEX_BIT_COUNT>(bits)
+}
+fn set_published(bits: Usize, value: Usize) -> Usize {
+    utils::bits::pack::<Usize, START_PUBLISHED, INDEX_BIT_COUNT>(bits, value)
+}
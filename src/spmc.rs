@@ -0,0 +1,451 @@
+use core::borrow::Borrow;
+use core::convert::Infallible;
+use core::future;
+use core::marker::PhantomData;
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use core::task::{Context, Poll};
+
+use crate::atomic_waker::AtomicWaker;
+use crate::error::{LimitReached, RecvError, RecvErrorNoWait, SendError, SendErrorNoWait};
+use crate::mpmc::wakers;
+use crate::reason::Reason;
+use crate::slot::Slot;
+use crate::utils;
+use crate::utils::AtomicUpdate;
+
+mod bits;
+mod ring;
+
+/// A medium through which [`Tx`] and [`Rx`]s communicate.
+///
+/// Same as [`mpmc::Link`](crate::mpmc::Link) on the receiving side, but there is only one
+/// [`Tx`]: it has a single waker, and puts values at the tail without coordinating with other
+/// senders. The tail is kept apart from the rest of the state, so sending needs no CAS.
+///
+/// Any endpoint may close the link with a reason of type `R`. The link is closed when the [`Tx`]
+/// or the last [`Rx`] is dropped.
+pub struct Link<T, B, RW, R = ()>
+where
+    B: AsRef<[Slot<T>]>,
+    RW: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    _value: PhantomData<T>,
+
+    buffer: B,
+
+    refs: AtomicUsize,
+    rxs: AtomicUsize,
+
+    reason: Reason<R>,
+
+    /// 1bit closed flag [0]
+    /// 1bit tx is set flag [1]
+    /// two indexes (31/15bit):
+    /// - head-taken [ 2..=32 / 2..=16 ]
+    /// - head-avail [33..=63 / 17..=31]
+    ///
+    /// for 64bit usize max capacity — 2_147_483_648-1
+    /// for 32bit usize max capacity — 32_768-1
+    bits: AtomicUsize,
+
+    /// Only moved by the [`Tx`].
+    tail: AtomicUsize,
+
+    tx_waker: AtomicWaker,
+    rx_wakers: RW,
+}
+
+/// The sending side of the channel
+pub struct Tx<T, L, B, RW, R = ()>
+where
+    L: Borrow<Link<T, B, RW, R>>,
+    B: AsRef<[Slot<T>]>,
+    RW: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    _value: PhantomData<T>,
+    _buffer: PhantomData<B>,
+    _rx_wakers: PhantomData<RW>,
+    _reason: PhantomData<R>,
+
+    link: L,
+}
+
+/// The receiving side of the channel
+pub struct Rx<T, L, B, RW, R = ()>
+where
+    L: Borrow<Link<T, B, RW, R>>,
+    B: AsRef<[Slot<T>]>,
+    RW: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    _value: PhantomData<T>,
+    _buffer: PhantomData<B>,
+    _rx_wakers: PhantomData<RW>,
+    _reason: PhantomData<R>,
+
+    link: L,
+    idx: usize,
+}
+
+impl<T, L, B, RW, R> Tx<T, L, B, RW, R>
+where
+    L: Borrow<Link<T, B, RW, R>>,
+    B: AsRef<[Slot<T>]>,
+    RW: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    /// Creates a new [`Tx`]
+    pub fn new(link: L) -> Self {
+        link.borrow().set_tx();
+
+        Self {
+            _value: Default::default(),
+            _buffer: Default::default(),
+            _rx_wakers: Default::default(),
+            _reason: Default::default(),
+            link,
+        }
+    }
+
+    /// Sends a value if the channel is not full.
+    pub fn send_nowait(&mut self, value: T) -> Result<(), SendErrorNoWait<T, R>>
+    where
+        R: Clone,
+    {
+        let link = self.link.borrow();
+        link.send_nowait(value).map_err(|e| e.with_reason(|| link.reason.get()))
+    }
+
+    /// Sends a value, waits if necessary.
+    pub async fn send(&mut self, value: T) -> Result<(), SendError<T, R>>
+    where
+        R: Clone,
+    {
+        let mut value = Some(value);
+        let link = self.link.borrow();
+        future::poll_fn(|cx| link.poll_send(cx, &mut value)).await
+    }
+
+    /// Closes the channel.
+    pub fn close(&mut self) {
+        self.link.borrow().close()
+    }
+
+    /// Closes the channel with a reason.
+    ///
+    /// The reason is dropped if the channel is closed already.
+    pub fn close_with(&mut self, reason: R) {
+        self.link.borrow().close_with(reason)
+    }
+}
+
+impl<T, L, B, RW, R> Rx<T, L, B, RW, R>
+where
+    L: Borrow<Link<T, B, RW, R>>,
+    B: AsRef<[Slot<T>]>,
+    RW: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    /// Creates a new [`Rx`]
+    pub fn new(link: L) -> Self {
+        let idx = link.borrow().try_attach_rx().expect("all rx-wakers are taken");
+        Self::attached(link, idx)
+    }
+
+    /// Try cloning this [`Rx`].
+    ///
+    /// Fails when all wakers are taken.
+    pub fn try_clone(&self) -> Result<Self, LimitReached>
+    where
+        L: Clone,
+    {
+        let idx = self.link.borrow().try_attach_rx().map_err(|()| LimitReached)?;
+        Ok(Self::attached(self.link.clone(), idx))
+    }
+
+    /// Receives a value if it is ready.
+    pub fn recv_nowait(&mut self) -> Result<T, RecvErrorNoWait<R>>
+    where
+        R: Clone,
+    {
+        let link = self.link.borrow();
+        link.recv_nowait().map_err(|e| e.with_reason(|| link.reason.get()))
+    }
+
+    /// Receives a value, waits if necessary.
+    pub async fn recv(&mut self) -> Result<T, RecvError<R>>
+    where
+        R: Clone,
+    {
+        let link = self.link.borrow();
+        future::poll_fn(|cx| link.poll_recv(cx, self.idx)).await
+    }
+
+    /// Closes the channel.
+    pub fn close(&mut self) {
+        self.link.borrow().close()
+    }
+
+    /// Closes the channel with a reason.
+    ///
+    /// The reason is dropped if the channel is closed already.
+    pub fn close_with(&mut self, reason: R) {
+        self.link.borrow().close_with(reason)
+    }
+
+    fn attached(link: L, idx: usize) -> Self {
+        Self {
+            _value: Default::default(),
+            _buffer: Default::default(),
+            _rx_wakers: Default::default(),
+            _reason: Default::default(),
+            link,
+            idx,
+        }
+    }
+}
+
+impl<T, B, RW, R> Link<T, B, RW, R>
+where
+    B: AsRef<[Slot<T>]>,
+    RW: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    /// Creates a new [`Link`]
+    pub fn new(buffer: B, rx_wakers: RW) -> Self {
+        assert!(buffer.as_ref().len() < bits::max_len());
+
+        Self {
+            _value: Default::default(),
+            buffer,
+            refs: Default::default(),
+            rxs: Default::default(),
+            reason: Default::default(),
+            bits: Default::default(),
+            tail: Default::default(),
+            tx_waker: Default::default(),
+            rx_wakers,
+        }
+    }
+}
+
+impl<T, B, RW, R> Link<T, B, RW, R>
+where
+    B: AsRef<[Slot<T>]>,
+    RW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    R: Clone,
+{
+    fn poll_recv(&self, cx: &mut Context, idx: usize) -> Poll<Result<T, RecvError<R>>> {
+        self.rx_wakers.as_ref()[idx].1.register(cx.waker());
+        match self.recv_nowait().map_err(|e| e.with_reason(|| self.reason.get())) {
+            Ok(value) => Poll::Ready(Ok(value)),
+            Err(RecvErrorNoWait::Closed) => Poll::Ready(Err(RecvError::closed())),
+            Err(RecvErrorNoWait::ClosedWith(reason)) =>
+                Poll::Ready(Err(RecvError::closed_with(reason))),
+            Err(RecvErrorNoWait::Empty) => Poll::Pending,
+        }
+    }
+
+    fn poll_send(
+        &self,
+        cx: &mut Context,
+        value: &mut Option<T>,
+    ) -> Poll<Result<(), SendError<T, R>>> {
+        self.tx_waker.register(cx.waker());
+        match self
+            .send_nowait(value.take().expect("stolen value"))
+            .map_err(|e| e.with_reason(|| self.reason.get()))
+        {
+            Ok(()) => Poll::Ready(Ok(())),
+            Err(SendErrorNoWait::Closed(rejected)) => Poll::Ready(Err(SendError::closed(rejected))),
+            Err(SendErrorNoWait::ClosedWith(rejected, reason)) =>
+                Poll::Ready(Err(SendError::closed_with(rejected, reason))),
+            Err(SendErrorNoWait::Rejected(rejected)) =>
+                Poll::Ready(Err(SendError::rejected(rejected))),
+            Err(SendErrorNoWait::Evicted(evicted)) => Poll::Ready(Err(SendError::evicted(evicted))),
+            Err(SendErrorNoWait::Full(rejected)) => {
+                *value = Some(rejected);
+                Poll::Pending
+            },
+        }
+    }
+
+    fn poll_closed(&self, cx: &mut Context) -> Poll<()> {
+        self.tx_waker.register(cx.waker());
+        if bits::is_closed(self.bits.load(Ordering::SeqCst)) {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+impl<T, B, RW, R> Link<T, B, RW, R>
+where
+    B: AsRef<[Slot<T>]>,
+    RW: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn send_nowait(&self, value: T) -> Result<(), SendErrorNoWait<T>> {
+        ring::send_nowait(&self.bits, &self.tail, self.buffer.as_ref(), value)?;
+        self.notify_rxs();
+        Ok(())
+    }
+
+    fn recv_nowait(&self) -> Result<T, RecvErrorNoWait> {
+        let value = ring::recv_nowait(&self.bits, &self.tail, self.buffer.as_ref())?;
+        self.tx_waker.wake();
+        Ok(value)
+    }
+
+    fn set_tx(&self) {
+        if let Err(err) = utils::compare_exchange_loop(
+            &self.bits,
+            utils::ATOMIC_UPDATE_MAX_ITERATIONS,
+            None,
+            |old_bits| {
+                if bits::tx_is_set(old_bits) {
+                    Err("this link already has a Tx")
+                } else {
+                    Ok(AtomicUpdate::Set(bits::set_tx(old_bits)))
+                }
+            },
+        ) {
+            panic!("{}", err.unwrap_or("failed to perform atomic update"))
+        }
+        self.refs.fetch_add(1, Ordering::SeqCst);
+    }
+    fn detach_tx(&self) {
+        utils::compare_exchange_loop(
+            &self.bits,
+            utils::ATOMIC_UPDATE_MAX_ITERATIONS,
+            None,
+            |old_bits| Ok::<_, Infallible>(AtomicUpdate::Set(bits::unset_tx(old_bits))),
+        )
+        .expect("failed to perform atomic update");
+        self.refs.fetch_sub(1, Ordering::SeqCst);
+    }
+
+    fn try_attach_rx(&self) -> Result<usize, ()> {
+        let idx = wakers::try_attach(&self.refs, self.rx_wakers.as_ref())?;
+        self.rxs.fetch_add(1, Ordering::SeqCst);
+        Ok(idx)
+    }
+    fn detach_rx(&self, idx: usize) {
+        if self.rxs.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.close();
+        }
+        wakers::detach(&self.refs, self.rx_wakers.as_ref(), idx)
+    }
+
+    fn notify_rxs(&self) {
+        wakers::notify(self.rx_wakers.as_ref());
+    }
+
+    fn close_with(&self, reason: R) {
+        if !bits::is_closed(self.bits.load(Ordering::SeqCst)) {
+            let _ = self.reason.set(reason);
+        }
+        self.close()
+    }
+
+    fn close(&self) {
+        ring::close(&self.bits);
+
+        self.tx_waker.wake();
+        self.notify_rxs();
+    }
+}
+
+impl<T, L, B, RW, R> Drop for Tx<T, L, B, RW, R>
+where
+    L: Borrow<Link<T, B, RW, R>>,
+    B: AsRef<[Slot<T>]>,
+    RW: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn drop(&mut self) {
+        let link = self.link.borrow();
+        link.close();
+        link.detach_tx();
+    }
+}
+
+impl<T, L, B, RW, R> Drop for Rx<T, L, B, RW, R>
+where
+    L: Borrow<Link<T, B, RW, R>>,
+    B: AsRef<[Slot<T>]>,
+    RW: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn drop(&mut self) {
+        self.link.borrow().detach_rx(self.idx);
+    }
+}
+
+impl<T, B, RW, R> Drop for Link<T, B, RW, R>
+where
+    B: AsRef<[Slot<T>]>,
+    RW: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn drop(&mut self) {
+        let refs = self.refs.load(Ordering::SeqCst);
+        if refs != 0 {
+            crate::leak::report::<Self>("Dropping Link that is still referenced?")
+        }
+
+        ring::drop_values(&self.bits, &self.tail, self.buffer.as_ref());
+    }
+}
+
+impl<T, B, RW, R> crate::shutdown::Close for Link<T, B, RW, R>
+where
+    B: AsRef<[Slot<T>]>,
+    RW: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn close(&self) {
+        Link::close(self)
+    }
+}
+
+impl<T, L, B, RW, R> crate::select::PollRecv for Rx<T, L, B, RW, R>
+where
+    L: Borrow<Link<T, B, RW, R>>,
+    B: AsRef<[Slot<T>]>,
+    RW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    R: Clone,
+{
+    type Item = T;
+    type Reason = R;
+
+    fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Result<T, RecvError<R>>> {
+        let link = self.link.borrow();
+        link.poll_recv(cx, self.idx)
+    }
+
+    fn close(&mut self) {
+        Rx::close(self)
+    }
+}
+
+impl<T, L, B, RW, R> crate::route::PollSend for Tx<T, L, B, RW, R>
+where
+    L: Borrow<Link<T, B, RW, R>>,
+    B: AsRef<[Slot<T>]>,
+    RW: AsRef<[(AtomicBool, AtomicWaker)]>,
+    R: Clone,
+{
+    type Item = T;
+    type Reason = R;
+
+    fn poll_send(
+        &mut self,
+        cx: &mut Context<'_>,
+        value: &mut Option<T>,
+    ) -> Poll<Result<(), SendError<T, R>>> {
+        let link = self.link.borrow();
+        link.poll_send(cx, value)
+    }
+
+    fn poll_closed(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        let link = self.link.borrow();
+        link.poll_closed(cx)
+    }
+
+    fn close(&mut self) {
+        Tx::close(self)
+    }
+}
@@ -0,0 +1,79 @@
+use core::sync::atomic::AtomicUsize;
+
+use crate::utils;
+
+const POS_IS_CLOSED: u8 = 0;
+const POS_TX_IS_SET: u8 = 1;
+const FLAGS_COUNT: u8 = 2;
+
+type Usize = <AtomicUsize as crate::utils::AtomicValue>::Value;
+const USIZE_BITS: u8 = Usize::BITS as u8;
+
+const INDEX_BIT_COUNT: u8 = (USIZE_BITS - FLAGS_COUNT) / 2;
+const START_HEAD_TAKEN: u8 = FLAGS_COUNT;
+const START_HEAD_AVAIL: u8 = FLAGS_COUNT + INDEX_BIT_COUNT;
+
+pub(super) fn max_len() -> Usize {
+    !(Usize::MAX << INDEX_BIT_COUNT)
+}
+
+pub(super) fn is_closed(bits: Usize) -> bool {
+    utils::bits::flag::<Usize, POS_IS_CLOSED>(bits) != 0
+}
+pub(super) fn set_closed(bits: Usize) -> Usize {
+    bits | utils::bits::flag::<Usize, POS_IS_CLOSED>(utils::bits::ones())
+}
+
+pub(super) fn tx_is_set(bits: Usize) -> bool {
+    utils::bits::flag::<Usize, POS_TX_IS_SET>(bits) != 0
+}
+pub(super) fn set_tx(bits: Usize) -> Usize {
+    bits | utils::bits::flag::<Usize, POS_TX_IS_SET>(utils::bits::ones())
+}
+pub(super) fn unset_tx(bits: Usize) -> Usize {
+    bits & !utils::bits::flag::<Usize, POS_TX_IS_SET>(utils::bits::ones())
+}
+
+pub(super) fn head_taken(bits: Usize) -> Usize {
+    utils::bits::unpack::<Usize, START_HEAD_TAKEN, INDEX_BIT_COUNT>(bits)
+}
+pub(super) fn set_head_taken(bits: Usize, value: Usize) -> Usize {
+    utils::bits::pack::<Usize, START_HEAD_TAKEN, INDEX_BIT_COUNT>(bits, value)
+}
+
+pub(super) fn head_avail(bits: Usize) -> Usize {
+    utils::bits::unpack::<Usize, START_HEAD_AVAIL, INDEX_BIT_COUNT>(bits)
+}
+pub(super) fn set_head_avail(bits: Usize, value: Usize) -> Usize {
+    utils::bits::pack::<Usize, START_HEAD_AVAIL, INDEX_BIT_COUNT>(bits, value)
+}
+
+#[test]
+fn test() {
+    let max = max_len();
+
+    for head_taken in [0, 1, max / 2, max - 1, max] {
+        for head_avail in [0, 1, max / 2, max - 1, max] {
+            for closed in [true, false] {
+                for tx in [true, false] {
+                    let bits = 0;
+                    let bits = if closed { set_closed(bits) } else { bits };
+                    let bits = if tx { set_tx(bits) } else { bits };
+                    let bits = set_head_taken(bits, head_taken);
+                    let bits = set_head_avail(bits, head_avail);
+
+                    assert_eq!(closed, is_closed(bits));
+                    assert_eq!(tx, tx_is_set(bits));
+                    assert_eq!(head_taken, self::head_taken(bits));
+                    assert_eq!(head_avail, self::head_avail(bits));
+
+                    let bits = unset_tx(bits);
+                    assert!(!tx_is_set(bits));
+                    assert_eq!(closed, is_closed(bits));
+                    assert_eq!(head_taken, self::head_taken(bits));
+                    assert_eq!(head_avail, self::head_avail(bits));
+                }
+            }
+        }
+    }
+}
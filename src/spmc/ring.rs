@@ -0,0 +1,91 @@
+use core::convert::Infallible;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::error::{RecvErrorNoWait, SendErrorNoWait};
+use crate::mpmc::ring::{give_slot, take_slot, Cursors};
+use crate::slot::Slot;
+use crate::utils::{self, AtomicUpdate};
+
+use super::bits;
+
+/// Only the single producer may call this: nobody else moves the tail.
+pub(super) fn send_nowait<T>(
+    bits: &AtomicUsize,
+    tail: &AtomicUsize,
+    buffer: &[Slot<T>],
+    value: T,
+) -> Result<(), SendErrorNoWait<T>> {
+    let buffer_len = buffer.len();
+
+    let current = bits.load(Ordering::SeqCst);
+    let tail_this = tail.load(Ordering::SeqCst);
+    let tail_if_full = (bits::head_avail(current) + buffer_len - 1) % buffer_len;
+
+    match (bits::is_closed(current), tail_this == tail_if_full) {
+        (true, _) => return Err(SendErrorNoWait::Closed(value)),
+        (false, true) => return Err(SendErrorNoWait::Full(value)),
+        (false, false) => (),
+    }
+
+    unsafe { buffer[tail_this].as_maybe_uninit_mut() }.write(value);
+    tail.store((tail_this + 1) % buffer_len, Ordering::SeqCst);
+
+    Ok(())
+}
+
+const HEAD: Cursors = Cursors {
+    taken: bits::head_taken,
+    set_taken: bits::set_head_taken,
+    avail: bits::head_avail,
+    set_avail: bits::set_head_avail,
+};
+
+pub(super) fn recv_nowait<T>(
+    bits: &AtomicUsize,
+    tail: &AtomicUsize,
+    buffer: &[Slot<T>],
+) -> Result<T, RecvErrorNoWait> {
+    let buffer_len = buffer.len();
+
+    let head_this = take_slot(bits, buffer_len, &HEAD, |bits, head_taken| {
+        // Loaded after the bits: if the bits say closed, the tail is final.
+        let tail = tail.load(Ordering::SeqCst);
+
+        match (tail == head_taken, bits::is_closed(bits)) {
+            (true, true) => Err(RecvErrorNoWait::closed()),
+            (true, false) => Err(RecvErrorNoWait::empty()),
+            (false, _) => Ok(()),
+        }
+    })?;
+
+    let value = unsafe { buffer[head_this].as_maybe_uninit_mut().assume_init_read() };
+    give_slot(bits, buffer_len, &HEAD, head_this);
+
+    Ok(value)
+}
+
+pub(super) fn close(bits: &AtomicUsize) {
+    utils::compare_exchange_loop(bits, utils::ATOMIC_UPDATE_MAX_ITERATIONS, None, |bits| {
+        Ok::<_, Infallible>(AtomicUpdate::Set(bits::set_closed(bits)))
+    })
+    .expect("failed to perform atomic update");
+}
+
+/// Drops the values left in the ring. Requires exclusive access to the ring.
+pub(super) fn drop_values<T>(bits: &AtomicUsize, tail: &AtomicUsize, buffer: &[Slot<T>]) {
+    let bits = bits.load(Ordering::SeqCst);
+    let mut head = bits::head_avail(bits);
+    let tail = tail.load(Ordering::SeqCst);
+    assert_eq!(head, bits::head_taken(bits));
+
+    let buffer_len = buffer.len();
+
+    while head != tail {
+        unsafe {
+            buffer[head].as_maybe_uninit_mut().assume_init_drop();
+        }
+
+        head += 1;
+        head %= buffer_len;
+    }
+}
@@ -7,70 +7,141 @@ use core::task::{Context, Poll};
 
 use crate::atomic_waker::AtomicWaker;
 
-use crate::error::{RecvError, RecvErrorNoWait, SendError, SendErrorNoWait};
+use crate::error::{InUse, RecvError, RecvErrorNoWait, SendError, SendErrorNoWait};
+use crate::reason::Reason;
 use crate::slot::Slot;
 use crate::utils;
 use crate::utils::AtomicUpdate;
 
 /// A medium through which [`Rx`] and [`Tx`] communicate.
-pub struct Link<T, B>
+///
+/// Either side may close the link with a reason of type `R`.
+pub struct Link<T, B, R = ()>
 where
     B: AsRef<[Slot<T>]>,
 {
     /// 1bit — closed
     /// 1bit — tx is set
-    /// 1bit — rx is set
+    /// 1bit — rx is set
+    /// 1bit — rx is busy (reading the element it has just taken off the head)
+    /// 1bit — aborted
+    /// 1bit — rx is closed
     ///
-    /// 14bit / 30bit — head
-    /// 14bit / 30bit — tail
+    /// 13bit / 29bit — head
+    /// 13bit / 29bit — tail
     ///
-    /// 1bit — unused
-    ///
-    /// for 32bit usize max capacity — 16_384-1
-    /// for 64bit usize max capacity — 1_073_741_824-1
+    /// for 32bit usize max capacity — 8_192-1
+    /// for 64bit usize max capacity — 536_870_912-1
     bits: AtomicUsize,
 
+    /// whether the oldest element is evicted when sending into a full buffer
+    lossy: bool,
+    /// number of elements evicted in the lossy mode
+    dropped: AtomicUsize,
+
     tx_waker: AtomicWaker,
     rx_waker: AtomicWaker,
 
+    pub(crate) reason: Reason<R>,
+
     _value: PhantomData<T>,
 
     buffer: B,
 }
 
 /// The sending side of the channel
-pub struct Tx<T, L, B>
+pub struct Tx<T, L, B, R = ()>
 where
     B: AsRef<[Slot<T>]>,
-    L: Borrow<Link<T, B>>,
+    L: Borrow<Link<T, B, R>>,
 {
     link: L,
     _value: PhantomData<T>,
     _buffer: PhantomData<B>,
+    _reason: PhantomData<R>,
 }
 
 /// The receiving side of the channel
-pub struct Rx<T, L, B>
+pub struct Rx<T, L, B, R = ()>
 where
     B: AsRef<[Slot<T>]>,
-    L: Borrow<Link<T, B>>,
+    L: Borrow<Link<T, B, R>>,
 {
     link: L,
     _value: PhantomData<T>,
     _buffer: PhantomData<B>,
+    _reason: PhantomData<R>,
 }
 
-impl<T, B> Link<T, B>
+impl<T, B, R> Link<T, B, R>
 where
     B: AsRef<[Slot<T>]>,
 {
     /// Creates a new ['Link`]
     pub fn new(buffer: B) -> Self {
+        Self::with_lossy(buffer, false)
+    }
+
+    /// Creates a new lossy ['Link`]
+    ///
+    /// Sending into a full lossy link does not fail: the oldest element is dropped to make room
+    /// for the new one. The number of dropped elements is available via [`Rx::dropped`].
+    pub fn new_lossy(buffer: B) -> Self {
+        Self::with_lossy(buffer, true)
+    }
+
+    /// Calls `f` with a [`Tx`] and an [`Rx`] of a link that lives for the duration of the call.
+    ///
+    /// The endpoints cannot outlive the call. An endpoint leaked inside `f` is not reported
+    /// to [`leak`](crate::leak) as the link is reset before it is dropped.
+    pub fn scoped<F, O>(buffer: B, f: F) -> O
+    where
+        F: for<'a> FnOnce(Tx<T, &'a Self, B, R>, Rx<T, &'a Self, B, R>) -> O,
+    {
+        let scope = Scope(Self::with_lossy(buffer, false));
+        f(Tx::new(&scope.0), Rx::new(&scope.0))
+    }
+
+    /// Prepares the link for another session: drops the values left in the link along with the
+    /// close reason, and lets a new [`Tx`] and [`Rx`] attach.
+    pub fn reset(&mut self) {
+        unsafe { self.clear() }
+    }
+
+    /// Same as [`Link::reset`], but fails if a [`Tx`] or an [`Rx`] is still attached.
+    ///
+    /// Attaching a [`Tx`] or an [`Rx`] while the link is being reset panics.
+    pub fn try_reset(&self) -> Result<(), InUse> {
+        match utils::compare_exchange_loop(
+            &self.bits,
+            self.max_iterations_for_atomic_update(),
+            None,
+            |old_bits| {
+                if bits::tx_is_set::is_set(old_bits) || bits::rx_is_set::is_set(old_bits) {
+                    Err(InUse)
+                } else {
+                    Ok(AtomicUpdate::Set(bits::tx_is_set::set(bits::rx_is_set::set(old_bits))))
+                }
+            },
+        ) {
+            Ok(_) => (),
+            Err(None) => panic!("failed to perform atomic update"),
+            Err(Some(in_use)) => return Err(in_use),
+        }
+
+        unsafe { self.clear() };
+        Ok(())
+    }
+
+    fn with_lossy(buffer: B, lossy: bool) -> Self {
         assert!(buffer.as_ref().len() < bits::max_len());
 
         Self {
             buffer,
             bits: Default::default(),
+            lossy,
+            dropped: Default::default(),
+            reason: Default::default(),
             tx_waker: Default::default(),
             rx_waker: Default::default(),
             _value: Default::default(),
@@ -78,24 +149,38 @@ where
     }
 }
 
-impl<T, L, B> Tx<T, L, B>
+impl<T, L, B, R> Tx<T, L, B, R>
 where
     B: AsRef<[Slot<T>]>,
-    L: Borrow<Link<T, B>>,
+    L: Borrow<Link<T, B, R>>,
 {
     /// Creates a new [`Tx`]
     pub fn new(link: L) -> Self {
         link.borrow().set_tx();
-        Self { link, _value: Default::default(), _buffer: Default::default() }
+        Self {
+            link,
+            _value: Default::default(),
+            _buffer: Default::default(),
+            _reason: Default::default(),
+        }
     }
 
     /// Sends a value if the channel is not full.
-    pub fn send_nowait(&mut self, value: T) -> Result<(), SendErrorNoWait<T>> {
-        self.link.borrow().send_nowait(value)
+    ///
+    /// A lossy link is never full: the oldest element is dropped instead.
+    pub fn send_nowait(&mut self, value: T) -> Result<(), SendErrorNoWait<T, R>>
+    where
+        R: Clone,
+    {
+        let link = self.link.borrow();
+        link.send_nowait(value).map_err(|e| e.with_reason(|| link.reason.get()))
     }
 
     /// Sends a value, waits if necessary.
-    pub async fn send(&mut self, value: T) -> Result<(), SendError<T>> {
+    pub async fn send(&mut self, value: T) -> Result<(), SendError<T, R>>
+    where
+        R: Clone,
+    {
         let mut value = Some(value);
         let link = self.link.borrow();
         future::poll_fn(|cx| link.poll_send(cx, &mut value)).await
@@ -105,54 +190,137 @@ where
     pub fn close(&mut self) {
         self.link.borrow().close(false, true)
     }
+
+    /// Closes the channel with a reason.
+    ///
+    /// The reason is dropped if the channel is closed already.
+    pub fn close_with(&mut self, reason: R) {
+        let link = self.link.borrow();
+        link.set_reason(reason);
+        link.close(false, true)
+    }
+
+    /// Closes the channel, dropping the values that have not been received yet.
+    pub fn abort(&mut self) {
+        let link = self.link.borrow();
+        link.abort(false, true);
+        link.discard();
+    }
+
+    /// Takes back the values the [`Rx`] has not received.
+    ///
+    /// Yields nothing until the [`Rx`] is dropped or closed with [`Rx::close_for_reclaim`]: only
+    /// then the values are surely not going to be received.
+    pub fn reclaim(&mut self) -> impl Iterator<Item = T> + '_ {
+        let link = self.link.borrow();
+        core::iter::from_fn(move || link.reclaim())
+    }
 }
 
-impl<T, L, B> Rx<T, L, B>
+impl<T, L, B, R> Rx<T, L, B, R>
 where
     B: AsRef<[Slot<T>]>,
-    L: Borrow<Link<T, B>>,
+    L: Borrow<Link<T, B, R>>,
 {
     /// Creates a new [`Rx`]
     pub fn new(link: L) -> Self {
         link.borrow().set_rx();
-        Self { link, _value: Default::default(), _buffer: Default::default() }
+        Self {
+            link,
+            _value: Default::default(),
+            _buffer: Default::default(),
+            _reason: Default::default(),
+        }
     }
 
     /// Receives a value if it is ready.
-    pub fn recv_nowait(&mut self) -> Result<T, RecvErrorNoWait> {
-        self.link.borrow().recv_nowait()
+    pub fn recv_nowait(&mut self) -> Result<T, RecvErrorNoWait<R>>
+    where
+        R: Clone,
+    {
+        let link = self.link.borrow();
+        link.recv_nowait().map_err(|e| e.with_reason(|| link.reason.get()))
     }
 
     /// Receives a value, waits if necessary.
-    pub async fn recv(&mut self) -> Result<T, RecvError> {
+    pub async fn recv(&mut self) -> Result<T, RecvError<R>>
+    where
+        R: Clone,
+    {
         let link = self.link.borrow();
         future::poll_fn(|cx| link.poll_recv(cx)).await
     }
 
+    /// The number of elements dropped by a lossy link so far.
+    pub fn dropped(&self) -> usize {
+        self.link.borrow().dropped.load(Ordering::SeqCst)
+    }
+
     /// Closes the channel.
+    ///
+    /// The values sent before the channel got closed can still be received.
     pub fn close(&mut self) {
-        self.link.borrow().close(false, true)
+        self.link.borrow().close(true, false)
+    }
+
+    /// Closes the channel with a reason.
+    ///
+    /// The reason is dropped if the channel is closed already.
+    pub fn close_with(&mut self, reason: R) {
+        let link = self.link.borrow();
+        link.set_reason(reason);
+        link.close(true, false)
+    }
+
+    /// Closes the channel and stops receiving.
+    ///
+    /// The values that have not been received yet can be taken back with [`Tx::reclaim`].
+    pub fn close_for_reclaim(&mut self) {
+        self.link.borrow().close_rx()
+    }
+
+    /// Closes the channel, dropping the values that have not been received yet.
+    pub fn abort(&mut self) {
+        let link = self.link.borrow();
+        link.abort(true, false);
+        link.discard();
+        link.close_rx();
     }
 }
 
-impl<T, B> Link<T, B>
+impl<T, B, R> Link<T, B, R>
 where
     B: AsRef<[Slot<T>]>,
+    R: Clone,
 {
-    fn poll_recv(&self, cx: &mut Context) -> Poll<Result<T, RecvError>> {
+    pub(crate) fn poll_recv(&self, cx: &mut Context) -> Poll<Result<T, RecvError<R>>> {
         self.rx_waker.register(cx.waker());
-        match self.recv_nowait() {
+        match self.recv_nowait().map_err(|e| e.with_reason(|| self.reason.get())) {
             Ok(value) => Poll::Ready(Ok(value)),
             Err(RecvErrorNoWait::Closed) => Poll::Ready(Err(RecvError::closed())),
+            Err(RecvErrorNoWait::ClosedWith(reason)) =>
+                Poll::Ready(Err(RecvError::closed_with(reason))),
             Err(RecvErrorNoWait::Empty) => Poll::Pending,
         }
     }
 
-    fn poll_send(&self, cx: &mut Context, value: &mut Option<T>) -> Poll<Result<(), SendError<T>>> {
+    pub(crate) fn poll_send(
+        &self,
+        cx: &mut Context,
+        value: &mut Option<T>,
+    ) -> Poll<Result<(), SendError<T, R>>> {
         self.tx_waker.register(cx.waker());
-        match self.send_nowait(value.take().expect("stolen value")) {
+        match self
+            .send_nowait(value.take().expect("stolen value"))
+            .map_err(|e| e.with_reason(|| self.reason.get()))
+        {
             Ok(()) => Poll::Ready(Ok(())),
             Err(SendErrorNoWait::Closed(rejected)) => Poll::Ready(Err(SendError::closed(rejected))),
+            Err(SendErrorNoWait::ClosedWith(rejected, reason)) =>
+                Poll::Ready(Err(SendError::closed_with(rejected, reason))),
+            Err(SendErrorNoWait::Rejected(rejected)) =>
+                Poll::Ready(Err(SendError::rejected(rejected))),
+            Err(SendErrorNoWait::Evicted(evicted)) => Poll::Ready(Err(SendError::evicted(evicted))),
             Err(SendErrorNoWait::Full(rejected)) => {
                 *value = Some(rejected);
                 Poll::Pending
@@ -160,39 +328,56 @@ where
         }
     }
 
-    fn recv_nowait(&self) -> Result<T, RecvErrorNoWait> {
+    pub(crate) fn poll_closed(&self, cx: &mut Context) -> Poll<()> {
+        self.tx_waker.register(cx.waker());
+        if bits::is_closed::is_set(self.bits.load(Ordering::SeqCst)) {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+impl<T, B, R> Link<T, B, R>
+where
+    B: AsRef<[Slot<T>]>,
+{
+    pub(crate) fn recv_nowait(&self) -> Result<T, RecvErrorNoWait> {
         let bits = self.bits.load(Ordering::SeqCst);
 
-        let buffer = self.buffer.as_ref();
-        let buffer_len = buffer.len();
+        if bits::rx_is_closed::is_set(bits) {
+            return Err(RecvErrorNoWait::closed())
+        }
+        if bits::is_aborted::is_set(bits) {
+            self.discard();
+            return Err(RecvErrorNoWait::closed())
+        }
 
-        let head = bits::head::get(bits);
-        let tail = bits::tail::get(bits);
-        let is_empty = head == tail;
-        let is_closed = bits::is_closed::is_set(bits);
+        self.take()
+    }
 
-        match (is_empty, is_closed) {
-            (true, true) => Err(RecvErrorNoWait::Closed),
-            (true, false) => Err(RecvErrorNoWait::Empty),
-            (false, _) => {
-                let head_next = (head + 1) % buffer_len;
-                let value = unsafe { buffer[head].as_maybe_uninit_mut().assume_init_read() };
-                utils::compare_exchange_loop(
-                    &self.bits,
-                    self.max_iterations_for_atomic_update(),
-                    Some(bits),
-                    |old_bits| {
-                        Ok::<_, Infallible>(AtomicUpdate::Set(bits::head::set(old_bits, head_next)))
-                    },
-                )
-                .expect("failed to perform atomic update");
-                self.tx_waker.wake();
-                Ok(value)
-            },
+    /// Drops the values in the buffer. Either side may call this.
+    fn discard(&self) {
+        loop {
+            let bits = self.bits.load(Ordering::SeqCst);
+            let head = bits::head::get(bits);
+            if head == bits::tail::get(bits) {
+                break
+            }
+            self.drop_head(head);
         }
     }
 
-    fn send_nowait(&self, value: T) -> Result<(), SendErrorNoWait<T>> {
+    /// Takes a value off the buffer on behalf of the [`Tx`], once the [`Rx`] is closed.
+    fn reclaim(&self) -> Option<T> {
+        if bits::rx_is_closed::is_set(self.bits.load(Ordering::SeqCst)) {
+            self.take().ok()
+        } else {
+            None
+        }
+    }
+
+    pub(crate) fn send_nowait(&self, value: T) -> Result<(), SendErrorNoWait<T>> {
         let bits = self.bits.load(Ordering::SeqCst);
 
         let buffer = self.buffer.as_ref();
@@ -206,6 +391,10 @@ where
 
         match (is_closed, is_full) {
             (true, _) => Err(SendErrorNoWait::Closed(value)),
+            (false, true) if self.lossy => {
+                self.evict_oldest(head);
+                self.send_nowait(value)
+            },
             (false, true) => Err(SendErrorNoWait::Full(value)),
             (false, false) => {
                 let tail_next = (tail + 1) % buffer_len;
@@ -226,7 +415,124 @@ where
         }
     }
 
-    fn close(&self, notify_tx: bool, notify_rx: bool) {
+    /// The head is advanced by both sides: the [`Tx`] evicts and discards the elements, the [`Rx`]
+    /// takes the head element off and marks itself busy until it has read the value out of the
+    /// slot.
+    fn take(&self) -> Result<T, RecvErrorNoWait> {
+        let buffer = self.buffer.as_ref();
+        let buffer_len = buffer.len();
+
+        let head_this = {
+            let mut output = None;
+
+            match utils::compare_exchange_loop(
+                &self.bits,
+                self.max_iterations_for_atomic_update(),
+                None,
+                |bits| {
+                    let head = bits::head::get(bits);
+                    let tail = bits::tail::get(bits);
+                    let is_empty = head == tail;
+                    let is_closed = bits::is_closed::is_set(bits);
+
+                    match (is_empty, is_closed) {
+                        (true, true) => Err(RecvErrorNoWait::closed()),
+                        (true, false) => Err(RecvErrorNoWait::empty()),
+                        (false, _) => {
+                            output = Some(head);
+                            let new_bits = bits::head::set(bits, (head + 1) % buffer_len);
+                            Ok(AtomicUpdate::Set(bits::rx_is_busy::set(new_bits)))
+                        },
+                    }
+                },
+            ) {
+                Ok(_) => output.unwrap(),
+                Err(None) => panic!("failed to perform atomic update"),
+                Err(Some(e)) => return Err(e),
+            }
+        };
+
+        let value = unsafe { buffer[head_this].as_maybe_uninit_mut().assume_init_read() };
+
+        utils::compare_exchange_loop(
+            &self.bits,
+            self.max_iterations_for_atomic_update(),
+            None,
+            |old_bits| Ok::<_, Infallible>(AtomicUpdate::Set(bits::rx_is_busy::unset(old_bits))),
+        )
+        .expect("failed to perform atomic update");
+
+        self.tx_waker.wake();
+        Ok(value)
+    }
+
+    /// Drops the element at `head`, unless the [`Rx`] has taken it already.
+    ///
+    /// While the [`Rx`] is busy, the slot it reads from is the one the [`Tx`] is going to write
+    /// next, so the eviction has to wait until the read is over.
+    fn drop_head(&self, head: usize) -> bool {
+        let buffer = self.buffer.as_ref();
+        let head_next = (head + 1) % buffer.len();
+
+        loop {
+            match utils::compare_exchange_loop(
+                &self.bits,
+                self.max_iterations_for_atomic_update(),
+                None,
+                |old_bits| {
+                    if bits::head::get(old_bits) != head {
+                        Err(())
+                    } else if bits::rx_is_busy::is_set(old_bits) {
+                        Ok(AtomicUpdate::Retry)
+                    } else {
+                        Ok(AtomicUpdate::Set(bits::head::set(old_bits, head_next)))
+                    }
+                },
+            ) {
+                Ok(_) => break,
+                Err(Some(())) => return false,
+                Err(None) => core::hint::spin_loop(),
+            }
+        }
+
+        unsafe { buffer[head].as_maybe_uninit_mut().assume_init_drop() };
+        true
+    }
+
+    fn evict_oldest(&self, head: usize) {
+        if self.drop_head(head) {
+            self.dropped.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    pub(crate) fn set_reason(&self, reason: R) {
+        if !bits::is_closed::is_set(self.bits.load(Ordering::SeqCst)) {
+            let _ = self.reason.set(reason);
+        }
+    }
+
+    fn abort(&self, notify_tx: bool, notify_rx: bool) {
+        utils::compare_exchange_loop(
+            &self.bits,
+            self.max_iterations_for_atomic_update(),
+            None,
+            |old_bits| {
+                Ok::<_, Infallible>(AtomicUpdate::Set(bits::is_aborted::set(bits::is_closed::set(
+                    old_bits,
+                ))))
+            },
+        )
+        .expect("failed to perform atomic update");
+
+        if notify_tx {
+            self.tx_waker.wake();
+        }
+        if notify_rx {
+            self.rx_waker.wake();
+        }
+    }
+
+    pub(crate) fn close(&self, notify_tx: bool, notify_rx: bool) {
         utils::compare_exchange_loop(
             &self.bits,
             self.max_iterations_for_atomic_update(),
@@ -243,7 +549,56 @@ where
         }
     }
 
-    fn set_tx(&self) {
+    /// Closes the channel on behalf of the [`Rx`]. The [`Rx`] never takes anything off the buffer
+    /// afterwards, leaving the rest to [`Tx::reclaim`].
+    pub(crate) fn close_rx(&self) {
+        utils::compare_exchange_loop(
+            &self.bits,
+            self.max_iterations_for_atomic_update(),
+            None,
+            |old_bits| {
+                Ok::<_, Infallible>(AtomicUpdate::Set(bits::rx_is_closed::set(
+                    bits::is_closed::set(old_bits),
+                )))
+            },
+        )
+        .expect("failed to perform atomic update");
+
+        self.tx_waker.wake();
+    }
+
+    pub(crate) fn detach_tx(&self) {
+        utils::compare_exchange_loop(
+            &self.bits,
+            self.max_iterations_for_atomic_update(),
+            None,
+            |old_bits| Ok::<_, Infallible>(AtomicUpdate::Set(bits::tx_is_set::unset(old_bits))),
+        )
+        .expect("failed to perform atomic update");
+    }
+    pub(crate) fn detach_rx(&self) {
+        utils::compare_exchange_loop(
+            &self.bits,
+            self.max_iterations_for_atomic_update(),
+            None,
+            |old_bits| Ok::<_, Infallible>(AtomicUpdate::Set(bits::rx_is_set::unset(old_bits))),
+        )
+        .expect("failed to perform atomic update");
+    }
+
+    /// Drops the values and the reason, and brings the bits to the initial state.
+    ///
+    /// # Safety
+    /// Nothing else may access the link.
+    unsafe fn clear(&self) {
+        unsafe { drop_values(self.bits.load(Ordering::SeqCst), self.buffer.as_ref()) };
+        unsafe { self.reason.clear() };
+
+        self.dropped.store(0, Ordering::SeqCst);
+        self.bits.store(0, Ordering::SeqCst);
+    }
+
+    pub(crate) fn set_tx(&self) {
         if let Err(err) = utils::compare_exchange_loop(
             &self.bits,
             self.max_iterations_for_atomic_update(),
@@ -259,7 +614,7 @@ where
             panic!("{}", err.unwrap_or("failed to perform atomic update"))
         }
     }
-    fn set_rx(&self) {
+    pub(crate) fn set_rx(&self) {
         if let Err(err) = utils::compare_exchange_loop(
             &self.bits,
             self.max_iterations_for_atomic_update(),
@@ -281,7 +636,21 @@ where
     }
 }
 
-impl<T, B> Drop for Link<T, B>
+/// Resets the link before it is dropped, so that leaked endpoints go unreported.
+struct Scope<T, B, R>(Link<T, B, R>)
+where
+    B: AsRef<[Slot<T>]>;
+
+impl<T, B, R> Drop for Scope<T, B, R>
+where
+    B: AsRef<[Slot<T>]>,
+{
+    fn drop(&mut self) {
+        self.0.reset()
+    }
+}
+
+impl<T, B, R> Drop for Link<T, B, R>
 where
     B: AsRef<[Slot<T>]>,
 {
@@ -293,42 +662,111 @@ where
         let rx_is_set = bits::rx_is_set::is_set(bits);
 
         if !is_closed && (tx_is_set || rx_is_set) {
-            panic!("Dropping unclosed Link")
+            crate::leak::report::<Self>("Dropping unclosed Link")
         }
 
-        let mut head = bits::head::get(bits);
-        let tail = bits::tail::get(bits);
+        unsafe { drop_values(bits, self.buffer.as_ref()) };
+    }
+}
 
-        let slots = self.buffer.as_ref();
+impl<T, L, B, R> Drop for Tx<T, L, B, R>
+where
+    B: AsRef<[Slot<T>]>,
+    L: Borrow<Link<T, B, R>>,
+{
+    fn drop(&mut self) {
+        let link = self.link.borrow();
+        link.close(/* notify_tx: */ false, /* notify_rx: */ true);
+        link.detach_tx();
+    }
+}
 
-        while head != tail {
-            unsafe {
-                slots[head].as_maybe_uninit_mut().assume_init_drop();
-            }
+impl<T, L, B, R> Drop for Rx<T, L, B, R>
+where
+    B: AsRef<[Slot<T>]>,
+    L: Borrow<Link<T, B, R>>,
+{
+    fn drop(&mut self) {
+        let link = self.link.borrow();
+        link.close_rx();
+        link.detach_rx();
+    }
+}
 
-            head += 1;
+/// Drops the values between the head and the tail.
+///
+/// # Safety
+/// Nothing else may access the buffer.
+unsafe fn drop_values<T>(bits: usize, slots: &[Slot<T>]) {
+    let mut head = bits::head::get(bits);
+    let tail = bits::tail::get(bits);
+
+    while head != tail {
+        unsafe {
+            slots[head].as_maybe_uninit_mut().assume_init_drop();
         }
+
+        head = (head + 1) % slots.len();
     }
 }
 
-impl<T, L, B> Drop for Tx<T, L, B>
+/// Single producer single consumer buffered channel that can be resized at runtime.
+pub mod growable;
+
+mod bits;
+
+impl<T, B, R> crate::shutdown::Close for Link<T, B, R>
 where
     B: AsRef<[Slot<T>]>,
-    L: Borrow<Link<T, B>>,
 {
-    fn drop(&mut self) {
-        self.link.borrow().close(/* notify_tx: */ false, /* notify_rx: */ true)
+    fn close(&self) {
+        Link::close(self, true, true)
     }
 }
 
-impl<T, L, B> Drop for Rx<T, L, B>
+impl<T, L, B, R> crate::select::PollRecv for Rx<T, L, B, R>
 where
     B: AsRef<[Slot<T>]>,
-    L: Borrow<Link<T, B>>,
+    L: Borrow<Link<T, B, R>>,
+    R: Clone,
 {
-    fn drop(&mut self) {
-        self.link.borrow().close(/* notify_tx: */ true, /* notify_rx: */ false)
+    type Item = T;
+    type Reason = R;
+
+    fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Result<T, RecvError<R>>> {
+        let link = self.link.borrow();
+        link.poll_recv(cx)
+    }
+
+    fn close(&mut self) {
+        Rx::close(self)
     }
 }
 
-mod bits;
+impl<T, L, B, R> crate::route::PollSend for Tx<T, L, B, R>
+where
+    B: AsRef<[Slot<T>]>,
+    L: Borrow<Link<T, B, R>>,
+    R: Clone,
+{
+    type Item = T;
+    type Reason = R;
+
+    fn poll_send(
+        &mut self,
+        cx: &mut Context<'_>,
+        value: &mut Option<T>,
+    ) -> Poll<Result<(), SendError<T, R>>> {
+        let link = self.link.borrow();
+        link.poll_send(cx, value)
+    }
+
+    fn poll_closed(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        let link = self.link.borrow();
+        link.poll_closed(cx)
+    }
+
+    fn close(&mut self) {
+        Tx::close(self)
+    }
+}
@@ -9,8 +9,11 @@ const USIZE_BITS: u8 = Usize::BITS as u8;
 const POS_IS_CLOSED: u8 = 0;
 const POS_TX_IS_SET: u8 = 1;
 const POS_RX_IS_SET: u8 = 2;
+const POS_RX_IS_BUSY: u8 = 3;
+const POS_IS_ABORTED: u8 = 4;
+const POS_RX_IS_CLOSED: u8 = 5;
 
-const FLAGS_COUNT: u8 = 3;
+const FLAGS_COUNT: u8 = 6;
 
 const INDEX_BIT_COUNT: u8 = (USIZE_BITS - FLAGS_COUNT) / 2;
 
@@ -42,6 +45,10 @@ pub(super) mod tx_is_set {
     pub fn set(bits: Usize) -> Usize {
         bits | utils::bits::flag::<Usize, POS_TX_IS_SET>(utils::bits::ones::<Usize>())
     }
+
+    pub fn unset(bits: Usize) -> Usize {
+        bits & !utils::bits::flag::<Usize, POS_TX_IS_SET>(utils::bits::ones::<Usize>())
+    }
 }
 pub(super) mod rx_is_set {
     use super::*;
@@ -53,6 +60,47 @@ pub(super) mod rx_is_set {
     pub fn set(bits: Usize) -> Usize {
         bits | utils::bits::flag::<Usize, POS_RX_IS_SET>(utils::bits::ones::<Usize>())
     }
+
+    pub fn unset(bits: Usize) -> Usize {
+        bits & !utils::bits::flag::<Usize, POS_RX_IS_SET>(utils::bits::ones::<Usize>())
+    }
+}
+pub(super) mod rx_is_busy {
+    use super::*;
+
+    pub fn is_set(bits: Usize) -> bool {
+        utils::bits::flag::<Usize, POS_RX_IS_BUSY>(bits) != 0
+    }
+
+    pub fn set(bits: Usize) -> Usize {
+        bits | utils::bits::flag::<Usize, POS_RX_IS_BUSY>(utils::bits::ones::<Usize>())
+    }
+
+    pub fn unset(bits: Usize) -> Usize {
+        bits & !utils::bits::flag::<Usize, POS_RX_IS_BUSY>(utils::bits::ones::<Usize>())
+    }
+}
+pub(super) mod is_aborted {
+    use super::*;
+
+    pub fn is_set(bits: Usize) -> bool {
+        utils::bits::flag::<Usize, POS_IS_ABORTED>(bits) != 0
+    }
+
+    pub fn set(bits: Usize) -> Usize {
+        bits | utils::bits::flag::<Usize, POS_IS_ABORTED>(utils::bits::ones::<Usize>())
+    }
+}
+pub(super) mod rx_is_closed {
+    use super::*;
+
+    pub fn is_set(bits: Usize) -> bool {
+        utils::bits::flag::<Usize, POS_RX_IS_CLOSED>(bits) != 0
+    }
+
+    pub fn set(bits: Usize) -> Usize {
+        bits | utils::bits::flag::<Usize, POS_RX_IS_CLOSED>(utils::bits::ones::<Usize>())
+    }
 }
 
 pub(super) mod head {
@@ -91,22 +139,50 @@ fn test() {
             for closed in [true, false] {
                 for tx_is_set in [true, false] {
                     for rx_is_set in [true, false] {
-                        let bits = 0;
-
-                        let bits = if closed { is_closed::set(bits) } else { bits };
-
-                        let bits = if tx_is_set { tx_is_set::set(bits) } else { bits };
-
-                        let bits = if rx_is_set { rx_is_set::set(bits) } else { bits };
-
-                        let bits = head::set(bits, head);
-                        let bits = tail::set(bits, tail);
-
-                        assert_eq!(closed, is_closed::is_set(bits));
-                        assert_eq!(tx_is_set, tx_is_set::is_set(bits));
-                        assert_eq!(rx_is_set, rx_is_set::is_set(bits));
-                        assert_eq!(head, head::get(bits));
-                        assert_eq!(tail, tail::get(bits));
+                        for rx_is_busy in [true, false] {
+                            for (aborted, rx_is_closed) in
+                                [(true, false), (false, true), (true, true), (false, false)]
+                            {
+                                let bits = 0;
+
+                                let bits = if closed { is_closed::set(bits) } else { bits };
+
+                                let bits = if tx_is_set { tx_is_set::set(bits) } else { bits };
+
+                                let bits = if rx_is_set { rx_is_set::set(bits) } else { bits };
+
+                                let bits = if rx_is_busy { rx_is_busy::set(bits) } else { bits };
+
+                                let bits = if aborted { is_aborted::set(bits) } else { bits };
+
+                                let bits =
+                                    if rx_is_closed { rx_is_closed::set(bits) } else { bits };
+
+                                let bits = head::set(bits, head);
+                                let bits = tail::set(bits, tail);
+
+                                assert_eq!(closed, is_closed::is_set(bits));
+                                assert_eq!(tx_is_set, tx_is_set::is_set(bits));
+                                assert_eq!(rx_is_set, rx_is_set::is_set(bits));
+                                assert_eq!(rx_is_busy, rx_is_busy::is_set(bits));
+                                assert_eq!(aborted, is_aborted::is_set(bits));
+                                assert_eq!(rx_is_closed, rx_is_closed::is_set(bits));
+                                assert_eq!(head, head::get(bits));
+                                assert_eq!(tail, tail::get(bits));
+
+                                let bits = tx_is_set::unset(rx_is_set::unset(bits));
+                                assert!(!tx_is_set::is_set(bits));
+                                assert!(!rx_is_set::is_set(bits));
+                                assert_eq!(rx_is_busy, rx_is_busy::is_set(bits));
+
+                                let bits = rx_is_busy::unset(bits);
+                                assert!(!rx_is_busy::is_set(bits));
+                                assert_eq!(closed, is_closed::is_set(bits));
+                                assert_eq!(aborted, is_aborted::is_set(bits));
+                                assert_eq!(head, head::get(bits));
+                                assert_eq!(tail, tail::get(bits));
+                            }
+                        }
                     }
                 }
             }
@@ -0,0 +1,699 @@
+use core::borrow::Borrow;
+use core::cell::UnsafeCell;
+use core::convert::Infallible;
+use core::future;
+use core::marker::PhantomData;
+use core::sync::atomic::{AtomicUsize, Ordering};
+use core::task::{Context, Poll};
+
+use crate::atomic_waker::AtomicWaker;
+
+use crate::error::{RecvError, RecvErrorNoWait, ResizeError, SendError, SendErrorNoWait};
+use crate::reason::Reason;
+use crate::slot::Slot;
+use crate::utils;
+use crate::utils::AtomicUpdate;
+
+/// A medium through which [`Rx`] and [`Tx`] communicate.
+///
+/// Unlike [`buffered::Link`](super::Link), the buffer can be replaced at runtime by the [`Rx`]
+/// ([`Rx::resize`]) or by a [`Resizer`] held by the owner of the link: the values in flight are
+/// moved into the new buffer in order. A buffer may be shrunk only while the link is empty.
+///
+/// Either side may close the link with a reason of type `R`.
+pub struct Link<T, B, R = ()>
+where
+    B: AsRef<[Slot<T>]>,
+{
+    /// 1bit — closed
+    /// 1bit — tx is set
+    /// 1bit — rx is set
+    /// 1bit — tx is busy (writing into the tail of the buffer)
+    /// 1bit — rx is busy (reading from the head of the buffer)
+    /// 1bit — resizing (replacing the buffer)
+    ///
+    /// 13bit / 29bit — head
+    /// 13bit / 29bit — tail
+    ///
+    /// for 32bit usize max capacity — 8_192-1
+    /// for 64bit usize max capacity — 536_870_912-1
+    bits: AtomicUsize,
+
+    tx_waker: AtomicWaker,
+    rx_waker: AtomicWaker,
+
+    reason: Reason<R>,
+
+    _value: PhantomData<T>,
+
+    /// Read only while "tx is busy" or "rx is busy" is set; replaced only while "resizing" is set,
+    /// which excludes both.
+    buffer: UnsafeCell<B>,
+    /// the length of the current buffer
+    buffer_len: AtomicUsize,
+}
+
+/// The sending side of the channel
+pub struct Tx<T, L, B, R = ()>
+where
+    B: AsRef<[Slot<T>]>,
+    L: Borrow<Link<T, B, R>>,
+{
+    link: L,
+    _value: PhantomData<T>,
+    _buffer: PhantomData<B>,
+    _reason: PhantomData<R>,
+}
+
+/// The receiving side of the channel
+pub struct Rx<T, L, B, R = ()>
+where
+    B: AsRef<[Slot<T>]>,
+    L: Borrow<Link<T, B, R>>,
+{
+    link: L,
+    _value: PhantomData<T>,
+    _buffer: PhantomData<B>,
+    _reason: PhantomData<R>,
+}
+
+/// A handle that resizes the buffer of a live channel on behalf of the owner of the link.
+///
+/// Neither sends nor receives, and does not keep the channel open.
+pub struct Resizer<T, L, B, R = ()>
+where
+    B: AsRef<[Slot<T>]>,
+    L: Borrow<Link<T, B, R>>,
+{
+    link: L,
+    _value: PhantomData<T>,
+    _buffer: PhantomData<B>,
+    _reason: PhantomData<R>,
+}
+
+impl<T, B, R> Link<T, B, R>
+where
+    B: AsRef<[Slot<T>]>,
+{
+    /// Creates a new ['Link`]
+    pub fn new(buffer: B) -> Self {
+        assert!(!buffer.as_ref().is_empty());
+        assert!(buffer.as_ref().len() < bits::max_len());
+
+        Self {
+            buffer_len: AtomicUsize::new(buffer.as_ref().len()),
+            buffer: UnsafeCell::new(buffer),
+            bits: Default::default(),
+            reason: Default::default(),
+            tx_waker: Default::default(),
+            rx_waker: Default::default(),
+            _value: Default::default(),
+        }
+    }
+}
+
+impl<T, L, B, R> Tx<T, L, B, R>
+where
+    B: AsRef<[Slot<T>]>,
+    L: Borrow<Link<T, B, R>>,
+{
+    /// Creates a new [`Tx`]
+    pub fn new(link: L) -> Self {
+        link.borrow().set_tx();
+        Self {
+            link,
+            _value: Default::default(),
+            _buffer: Default::default(),
+            _reason: Default::default(),
+        }
+    }
+
+    /// Sends a value if the channel is not full.
+    ///
+    /// The channel is reported full while its buffer is being replaced.
+    pub fn send_nowait(&mut self, value: T) -> Result<(), SendErrorNoWait<T, R>>
+    where
+        R: Clone,
+    {
+        let link = self.link.borrow();
+        link.send_nowait(value).map_err(|e| e.with_reason(|| link.reason.get()))
+    }
+
+    /// Sends a value, waits if necessary.
+    pub async fn send(&mut self, value: T) -> Result<(), SendError<T, R>>
+    where
+        R: Clone,
+    {
+        let mut value = Some(value);
+        let link = self.link.borrow();
+        future::poll_fn(|cx| link.poll_send(cx, &mut value)).await
+    }
+
+    /// Closes the channel.
+    pub fn close(&mut self) {
+        self.link.borrow().close(false, true)
+    }
+
+    /// Closes the channel with a reason.
+    ///
+    /// The reason is dropped if the channel is closed already.
+    pub fn close_with(&mut self, reason: R) {
+        let link = self.link.borrow();
+        link.set_reason(reason);
+        link.close(false, true)
+    }
+}
+
+impl<T, L, B, R> Rx<T, L, B, R>
+where
+    B: AsRef<[Slot<T>]>,
+    L: Borrow<Link<T, B, R>>,
+{
+    /// Creates a new [`Rx`]
+    pub fn new(link: L) -> Self {
+        link.borrow().set_rx();
+        Self {
+            link,
+            _value: Default::default(),
+            _buffer: Default::default(),
+            _reason: Default::default(),
+        }
+    }
+
+    /// Receives a value if it is ready.
+    pub fn recv_nowait(&mut self) -> Result<T, RecvErrorNoWait<R>>
+    where
+        R: Clone,
+    {
+        let link = self.link.borrow();
+        link.recv_nowait().map_err(|e| e.with_reason(|| link.reason.get()))
+    }
+
+    /// Receives a value, waits if necessary.
+    pub async fn recv(&mut self) -> Result<T, RecvError<R>>
+    where
+        R: Clone,
+    {
+        let link = self.link.borrow();
+        future::poll_fn(|cx| link.poll_recv(cx)).await
+    }
+
+    /// The number of values the link can hold.
+    pub fn capacity(&self) -> usize {
+        self.link.borrow().capacity()
+    }
+
+    /// Replaces the buffer, moving the values in flight into the new one.
+    ///
+    /// Returns the old buffer. Waits for the [`Tx`] to finish writing a value, or a [`Resizer`] to
+    /// finish resizing, if either is in the middle of that.
+    pub fn resize(&mut self, buffer: B) -> Result<B, ResizeError<B>> {
+        self.link.borrow().replace_buffer(buffer)
+    }
+
+    /// Closes the channel.
+    pub fn close(&mut self) {
+        self.link.borrow().close(true, false)
+    }
+
+    /// Closes the channel with a reason.
+    ///
+    /// The reason is dropped if the channel is closed already.
+    pub fn close_with(&mut self, reason: R) {
+        let link = self.link.borrow();
+        link.set_reason(reason);
+        link.close(true, false)
+    }
+}
+
+impl<T, L, B, R> Resizer<T, L, B, R>
+where
+    B: AsRef<[Slot<T>]>,
+    L: Borrow<Link<T, B, R>>,
+{
+    /// Creates a new [`Resizer`]
+    pub fn new(link: L) -> Self {
+        Self {
+            link,
+            _value: Default::default(),
+            _buffer: Default::default(),
+            _reason: Default::default(),
+        }
+    }
+
+    /// The number of values the link can hold.
+    pub fn capacity(&self) -> usize {
+        self.link.borrow().capacity()
+    }
+
+    /// Replaces the buffer, moving the values in flight into the new one.
+    ///
+    /// Returns the old buffer. Waits for the [`Tx`] or the [`Rx`] to finish accessing the buffer,
+    /// if either is in the middle of that.
+    pub fn resize(&mut self, buffer: B) -> Result<B, ResizeError<B>> {
+        self.link.borrow().replace_buffer(buffer)
+    }
+}
+
+impl<T, B, R> Link<T, B, R>
+where
+    B: AsRef<[Slot<T>]>,
+    R: Clone,
+{
+    fn poll_recv(&self, cx: &mut Context) -> Poll<Result<T, RecvError<R>>> {
+        self.rx_waker.register(cx.waker());
+        match self.recv_nowait().map_err(|e| e.with_reason(|| self.reason.get())) {
+            Ok(value) => Poll::Ready(Ok(value)),
+            Err(RecvErrorNoWait::Closed) => Poll::Ready(Err(RecvError::closed())),
+            Err(RecvErrorNoWait::ClosedWith(reason)) =>
+                Poll::Ready(Err(RecvError::closed_with(reason))),
+            Err(RecvErrorNoWait::Empty) => Poll::Pending,
+        }
+    }
+
+    fn poll_send(
+        &self,
+        cx: &mut Context,
+        value: &mut Option<T>,
+    ) -> Poll<Result<(), SendError<T, R>>> {
+        self.tx_waker.register(cx.waker());
+        match self
+            .send_nowait(value.take().expect("stolen value"))
+            .map_err(|e| e.with_reason(|| self.reason.get()))
+        {
+            Ok(()) => Poll::Ready(Ok(())),
+            Err(SendErrorNoWait::Closed(rejected)) => Poll::Ready(Err(SendError::closed(rejected))),
+            Err(SendErrorNoWait::ClosedWith(rejected, reason)) =>
+                Poll::Ready(Err(SendError::closed_with(rejected, reason))),
+            Err(SendErrorNoWait::Rejected(rejected)) =>
+                Poll::Ready(Err(SendError::rejected(rejected))),
+            Err(SendErrorNoWait::Evicted(evicted)) => Poll::Ready(Err(SendError::evicted(evicted))),
+            Err(SendErrorNoWait::Full(rejected)) => {
+                *value = Some(rejected);
+                Poll::Pending
+            },
+        }
+    }
+
+    fn poll_closed(&self, cx: &mut Context) -> Poll<()> {
+        self.tx_waker.register(cx.waker());
+        if bits::is_closed::is_set(self.bits.load(Ordering::SeqCst)) {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+impl<T, B, R> Link<T, B, R>
+where
+    B: AsRef<[Slot<T>]>,
+{
+    /// Only the [`Rx`] side may call this.
+    ///
+    /// The channel is reported empty while its buffer is being replaced.
+    fn recv_nowait(&self) -> Result<T, RecvErrorNoWait> {
+        let bits = match utils::compare_exchange_loop(
+            &self.bits,
+            self.max_iterations_for_atomic_update(),
+            None,
+            |old_bits| {
+                let is_empty = bits::head::get(old_bits) == bits::tail::get(old_bits);
+                let is_closed = bits::is_closed::is_set(old_bits);
+
+                if bits::is_resizing::is_set(old_bits) {
+                    Err(RecvErrorNoWait::Empty)
+                } else if is_empty && is_closed {
+                    Err(RecvErrorNoWait::Closed)
+                } else if is_empty {
+                    Err(RecvErrorNoWait::Empty)
+                } else {
+                    Ok(AtomicUpdate::Set(bits::rx_is_busy::set(old_bits)))
+                }
+            },
+        ) {
+            Ok(bits) => bits,
+            Err(None) => panic!("failed to perform atomic update"),
+            Err(Some(e)) => return Err(e),
+        };
+
+        // The buffer cannot be replaced until "rx is busy" is unset.
+        let head = bits::head::get(bits);
+        let (value, head_next) = self.with_buffer(|buffer| {
+            let value = unsafe { buffer[head].as_maybe_uninit_mut().assume_init_read() };
+            (value, (head + 1) % buffer.len())
+        });
+
+        utils::compare_exchange_loop(
+            &self.bits,
+            self.max_iterations_for_atomic_update(),
+            None,
+            |old_bits| {
+                let new_bits = bits::rx_is_busy::unset(old_bits);
+                Ok::<_, Infallible>(AtomicUpdate::Set(bits::head::set(new_bits, head_next)))
+            },
+        )
+        .expect("failed to perform atomic update");
+
+        self.tx_waker.wake();
+        Ok(value)
+    }
+
+    fn send_nowait(&self, value: T) -> Result<(), SendErrorNoWait<T>> {
+        match utils::compare_exchange_loop(
+            &self.bits,
+            self.max_iterations_for_atomic_update(),
+            None,
+            |old_bits| {
+                if bits::is_closed::is_set(old_bits) {
+                    Err(SendErrorNoWait::closed(()))
+                } else if bits::is_resizing::is_set(old_bits) {
+                    Err(SendErrorNoWait::full(()))
+                } else {
+                    Ok(AtomicUpdate::Set(bits::tx_is_busy::set(old_bits)))
+                }
+            },
+        ) {
+            Ok(_) => (),
+            Err(None) => panic!("failed to perform atomic update"),
+            Err(Some(e)) => return Err(e.map_value(value)),
+        }
+
+        // The buffer cannot be replaced until "tx is busy" is unset.
+        let bits = self.bits.load(Ordering::SeqCst);
+        let head = bits::head::get(bits);
+        let tail = bits::tail::get(bits);
+
+        let result = self.with_buffer(|buffer| {
+            let buffer_len = buffer.len();
+            let tail_if_full = (head + buffer_len - 1) % buffer_len;
+
+            if tail == tail_if_full {
+                Err(SendErrorNoWait::Full(value))
+            } else {
+                unsafe { buffer[tail].as_maybe_uninit_mut() }.write(value);
+                Ok((tail + 1) % buffer_len)
+            }
+        });
+
+        utils::compare_exchange_loop(
+            &self.bits,
+            self.max_iterations_for_atomic_update(),
+            None,
+            |old_bits| {
+                let new_bits = bits::tx_is_busy::unset(old_bits);
+                let new_bits = match result {
+                    Ok(tail_next) => bits::tail::set(new_bits, tail_next),
+                    Err(_) => new_bits,
+                };
+                Ok::<_, Infallible>(AtomicUpdate::Set(new_bits))
+            },
+        )
+        .expect("failed to perform atomic update");
+
+        result?;
+        self.rx_waker.wake();
+        Ok(())
+    }
+
+    /// Only the [`Rx`] side or a [`Resizer`] may call this.
+    fn replace_buffer(&self, buffer: B) -> Result<B, ResizeError<B>> {
+        if buffer.as_ref().is_empty() {
+            return Err(ResizeError::Empty(buffer))
+        }
+        if buffer.as_ref().len() >= bits::max_len() {
+            return Err(ResizeError::TooLarge(buffer))
+        }
+
+        loop {
+            match utils::compare_exchange_loop(
+                &self.bits,
+                self.max_iterations_for_atomic_update(),
+                None,
+                |old_bits| {
+                    if bits::tx_is_busy::is_set(old_bits) ||
+                        bits::rx_is_busy::is_set(old_bits) ||
+                        bits::is_resizing::is_set(old_bits)
+                    {
+                        Ok(AtomicUpdate::Retry)
+                    } else {
+                        Ok::<_, Infallible>(AtomicUpdate::Set(bits::is_resizing::set(old_bits)))
+                    }
+                },
+            ) {
+                Ok(_) => break,
+                Err(_) => core::hint::spin_loop(),
+            }
+        }
+
+        let bits = self.bits.load(Ordering::SeqCst);
+        let head = bits::head::get(bits);
+        let tail = bits::tail::get(bits);
+
+        // Nothing else accesses the buffer while "resizing" is set.
+        let old_buffer = unsafe { &mut *self.buffer.get() };
+        let old_len = old_buffer.as_ref().len();
+        let new_len = buffer.as_ref().len();
+        let count = (tail + old_len - head) % old_len;
+
+        let result = if new_len < old_len && count != 0 {
+            Err(ResizeError::NotEmpty(buffer))
+        } else {
+            let (old_slots, new_slots) = (old_buffer.as_ref(), buffer.as_ref());
+            for (idx, new_slot) in new_slots.iter().take(count).enumerate() {
+                let value = unsafe {
+                    old_slots[(head + idx) % old_len].as_maybe_uninit_mut().assume_init_read()
+                };
+                unsafe { new_slot.as_maybe_uninit_mut() }.write(value);
+            }
+            self.buffer_len.store(new_len, Ordering::SeqCst);
+            Ok(core::mem::replace(old_buffer, buffer))
+        };
+
+        utils::compare_exchange_loop(
+            &self.bits,
+            self.max_iterations_for_atomic_update(),
+            None,
+            |old_bits| {
+                let new_bits = bits::is_resizing::unset(old_bits);
+                let new_bits = if result.is_ok() {
+                    bits::tail::set(bits::head::set(new_bits, 0), count)
+                } else {
+                    new_bits
+                };
+                Ok::<_, Infallible>(AtomicUpdate::Set(new_bits))
+            },
+        )
+        .expect("failed to perform atomic update");
+
+        self.tx_waker.wake();
+        self.rx_waker.wake();
+        result
+    }
+
+    fn capacity(&self) -> usize {
+        self.buffer_len.load(Ordering::SeqCst) - 1
+    }
+
+    /// Reads the buffer. The caller must make sure that it is not being replaced.
+    fn with_buffer<O>(&self, f: impl FnOnce(&[Slot<T>]) -> O) -> O {
+        f(unsafe { &*self.buffer.get() }.as_ref())
+    }
+
+    fn set_reason(&self, reason: R) {
+        if !bits::is_closed::is_set(self.bits.load(Ordering::SeqCst)) {
+            let _ = self.reason.set(reason);
+        }
+    }
+
+    fn close(&self, notify_tx: bool, notify_rx: bool) {
+        utils::compare_exchange_loop(
+            &self.bits,
+            self.max_iterations_for_atomic_update(),
+            None,
+            |old_flags| Ok::<_, Infallible>(AtomicUpdate::Set(bits::is_closed::set(old_flags))),
+        )
+        .expect("failed to perform atomic update");
+
+        if notify_tx {
+            self.tx_waker.wake();
+        }
+        if notify_rx {
+            self.rx_waker.wake();
+        }
+    }
+
+    fn detach_tx(&self) {
+        utils::compare_exchange_loop(
+            &self.bits,
+            self.max_iterations_for_atomic_update(),
+            None,
+            |old_bits| Ok::<_, Infallible>(AtomicUpdate::Set(bits::tx_is_set::unset(old_bits))),
+        )
+        .expect("failed to perform atomic update");
+    }
+    fn detach_rx(&self) {
+        utils::compare_exchange_loop(
+            &self.bits,
+            self.max_iterations_for_atomic_update(),
+            None,
+            |old_bits| Ok::<_, Infallible>(AtomicUpdate::Set(bits::rx_is_set::unset(old_bits))),
+        )
+        .expect("failed to perform atomic update");
+    }
+
+    fn set_tx(&self) {
+        if let Err(err) = utils::compare_exchange_loop(
+            &self.bits,
+            self.max_iterations_for_atomic_update(),
+            None,
+            |old_bits| {
+                if bits::tx_is_set::is_set(old_bits) {
+                    Err("this link already has a Tx")
+                } else {
+                    Ok(AtomicUpdate::Set(bits::tx_is_set::set(old_bits)))
+                }
+            },
+        ) {
+            panic!("{}", err.unwrap_or("failed to perform atomic update"))
+        }
+    }
+    fn set_rx(&self) {
+        if let Err(err) = utils::compare_exchange_loop(
+            &self.bits,
+            self.max_iterations_for_atomic_update(),
+            None,
+            |old_bits| {
+                if bits::rx_is_set::is_set(old_bits) {
+                    Err("this link already has an Rx")
+                } else {
+                    Ok(AtomicUpdate::Set(bits::rx_is_set::set(old_bits)))
+                }
+            },
+        ) {
+            panic!("{}", err.unwrap_or("failed to perform atomic update"))
+        }
+    }
+
+    fn max_iterations_for_atomic_update(&self) -> usize {
+        utils::ATOMIC_UPDATE_MAX_ITERATIONS
+    }
+}
+
+impl<T, B, R> Drop for Link<T, B, R>
+where
+    B: AsRef<[Slot<T>]>,
+{
+    fn drop(&mut self) {
+        let bits = self.bits.load(Ordering::SeqCst);
+
+        let is_closed = bits::is_closed::is_set(bits);
+        let tx_is_set = bits::tx_is_set::is_set(bits);
+        let rx_is_set = bits::rx_is_set::is_set(bits);
+
+        if !is_closed && (tx_is_set || rx_is_set) {
+            crate::leak::report::<Self>("Dropping unclosed Link")
+        }
+
+        unsafe { drop_values(bits, self.buffer.get_mut().as_ref()) };
+    }
+}
+
+impl<T, L, B, R> Drop for Tx<T, L, B, R>
+where
+    B: AsRef<[Slot<T>]>,
+    L: Borrow<Link<T, B, R>>,
+{
+    fn drop(&mut self) {
+        let link = self.link.borrow();
+        link.close(/* notify_tx: */ false, /* notify_rx: */ true);
+        link.detach_tx();
+    }
+}
+
+impl<T, L, B, R> Drop for Rx<T, L, B, R>
+where
+    B: AsRef<[Slot<T>]>,
+    L: Borrow<Link<T, B, R>>,
+{
+    fn drop(&mut self) {
+        let link = self.link.borrow();
+        link.close(/* notify_tx: */ true, /* notify_rx: */ false);
+        link.detach_rx();
+    }
+}
+
+/// Drops the values between the head and the tail.
+///
+/// # Safety
+/// Nothing else may access the buffer.
+unsafe fn drop_values<T>(bits: usize, slots: &[Slot<T>]) {
+    let mut head = bits::head::get(bits);
+    let tail = bits::tail::get(bits);
+
+    while head != tail {
+        unsafe {
+            slots[head].as_maybe_uninit_mut().assume_init_drop();
+        }
+
+        head = (head + 1) % slots.len();
+    }
+}
+
+mod bits;
+
+impl<T, B, R> crate::shutdown::Close for Link<T, B, R>
+where
+    B: AsRef<[Slot<T>]>,
+{
+    fn close(&self) {
+        Link::close(self, true, true)
+    }
+}
+
+impl<T, L, B, R> crate::select::PollRecv for Rx<T, L, B, R>
+where
+    B: AsRef<[Slot<T>]>,
+    L: Borrow<Link<T, B, R>>,
+    R: Clone,
+{
+    type Item = T;
+    type Reason = R;
+
+    fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Result<T, RecvError<R>>> {
+        let link = self.link.borrow();
+        link.poll_recv(cx)
+    }
+
+    fn close(&mut self) {
+        Rx::close(self)
+    }
+}
+
+impl<T, L, B, R> crate::route::PollSend for Tx<T, L, B, R>
+where
+    B: AsRef<[Slot<T>]>,
+    L: Borrow<Link<T, B, R>>,
+    R: Clone,
+{
+    type Item = T;
+    type Reason = R;
+
+    fn poll_send(
+        &mut self,
+        cx: &mut Context<'_>,
+        value: &mut Option<T>,
+    ) -> Poll<Result<(), SendError<T, R>>> {
+        let link = self.link.borrow();
+        link.poll_send(cx, value)
+    }
+
+    fn poll_closed(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        let link = self.link.borrow();
+        link.poll_closed(cx)
+    }
+
+    fn close(&mut self) {
+        Tx::close(self)
+    }
+}
@@ -7,7 +7,8 @@ use core::task::{Context, Poll};
 
 use crate::atomic_waker::AtomicWaker;
 
-use crate::error::{RecvError, RecvErrorNoWait, SendError, SendErrorNoWait};
+use crate::error::{InUse, RecvError, RecvErrorNoWait, SendError, SendErrorNoWait};
+use crate::reason::Reason;
 use crate::slot::Slot;
 use crate::utils;
 use crate::utils::AtomicUpdate;
@@ -18,48 +19,60 @@ const FLAG_TX_IS_SET: u8 = 0b0100;
 const FLAG_RX_IS_SET: u8 = 0b1000;
 
 /// A medium through which [`Rx`] and [`Tx`] communicate.
-pub struct Link<T> {
+///
+/// Either side may close the link with a reason of type `R`.
+pub struct Link<T, R = ()> {
     flags: AtomicU8,
     rx_waker: AtomicWaker,
     tx_waker: AtomicWaker,
     slot: Slot<T>,
+    reason: Reason<R>,
 }
 
 /// The receiving side of the channel
-pub struct Rx<T, L>
+pub struct Rx<T, L, R = ()>
 where
-    L: Borrow<Link<T>>,
+    L: Borrow<Link<T, R>>,
 {
     link: L,
     _value: PhantomData<T>,
+    _reason: PhantomData<R>,
 }
 
 /// The sending side of the channel
-pub struct Tx<T, L>
+pub struct Tx<T, L, R = ()>
 where
-    L: Borrow<Link<T>>,
+    L: Borrow<Link<T, R>>,
 {
     link: L,
     _value: PhantomData<T>,
+    _reason: PhantomData<R>,
 }
 
-impl<T, L> Rx<T, L>
+impl<T, L, R> Rx<T, L, R>
 where
-    L: Borrow<Link<T>>,
+    L: Borrow<Link<T, R>>,
 {
     /// Creates a new [`Rx`].
     pub fn new(link: L) -> Self {
         link.borrow().set_rx();
-        Self { link, _value: Default::default() }
+        Self { link, _value: Default::default(), _reason: Default::default() }
     }
 
     /// Receives a value if it is ready.
-    pub fn recv_nowait(&mut self) -> Result<T, RecvErrorNoWait> {
-        self.link.borrow().recv_nowait()
+    pub fn recv_nowait(&mut self) -> Result<T, RecvErrorNoWait<R>>
+    where
+        R: Clone,
+    {
+        let link = self.link.borrow();
+        link.recv_nowait().map_err(|e| e.with_reason(|| link.reason.get()))
     }
 
     /// Receives a value, waits if necessary.
-    pub async fn recv(&mut self) -> Result<T, RecvError> {
+    pub async fn recv(&mut self) -> Result<T, RecvError<R>>
+    where
+        R: Clone,
+    {
         let link = self.link.borrow();
         future::poll_fn(|cx| link.poll_recv(cx)).await
     }
@@ -68,25 +81,39 @@ where
     pub fn close(&mut self) {
         self.link.borrow().close(true, false)
     }
+
+    /// Closes the channel with a reason.
+    ///
+    /// The reason is dropped if the channel is closed already.
+    pub fn close_with(&mut self, reason: R) {
+        self.link.borrow().close_with(reason, true, false)
+    }
 }
 
-impl<T, L> Tx<T, L>
+impl<T, L, R> Tx<T, L, R>
 where
-    L: Borrow<Link<T>>,
+    L: Borrow<Link<T, R>>,
 {
     /// Creates a new [`Tx`].
     pub fn new(link: L) -> Self {
         link.borrow().set_tx();
-        Self { link, _value: Default::default() }
+        Self { link, _value: Default::default(), _reason: Default::default() }
     }
 
     /// Sends a value if the channel is not full.
-    pub fn send_nowait(&mut self, value: T) -> Result<(), SendErrorNoWait<T>> {
-        self.link.borrow().send_nowait(value)
+    pub fn send_nowait(&mut self, value: T) -> Result<(), SendErrorNoWait<T, R>>
+    where
+        R: Clone,
+    {
+        let link = self.link.borrow();
+        link.send_nowait(value).map_err(|e| e.with_reason(|| link.reason.get()))
     }
 
     /// Sends a value, waits if necessary.
-    pub async fn send(&mut self, value: T) -> Result<(), SendError<T>> {
+    pub async fn send(&mut self, value: T) -> Result<(), SendError<T, R>>
+    where
+        R: Clone,
+    {
         let mut value = Some(value);
         let link = self.link.borrow();
         future::poll_fn(|cx| link.poll_send(cx, &mut value)).await
@@ -96,30 +123,125 @@ where
     pub fn close(&mut self) {
         self.link.borrow().close(false, true)
     }
+
+    /// Closes the channel with a reason.
+    ///
+    /// The reason is dropped if the channel is closed already.
+    pub fn close_with(&mut self, reason: R) {
+        self.link.borrow().close_with(reason, false, true)
+    }
 }
 
-impl<T> Link<T> {
+impl<T, R> Link<T, R> {
     /// Creates a new ['Link`]
     pub fn new() -> Self {
         Default::default()
     }
+
+    /// Calls `f` with a [`Tx`] and an [`Rx`] of a link that lives for the duration of the call.
+    ///
+    /// The endpoints cannot outlive the call. An endpoint leaked inside `f` is not reported
+    /// to [`leak`](crate::leak) as the link is reset before it is dropped.
+    pub fn scoped<F, O>(f: F) -> O
+    where
+        F: for<'a> FnOnce(Tx<T, &'a Self, R>, Rx<T, &'a Self, R>) -> O,
+    {
+        let scope = Scope(Self::new());
+        f(Tx::new(&scope.0), Rx::new(&scope.0))
+    }
+
+    /// Prepares the link for another session: drops the value left in the link along with the
+    /// close reason, and lets a new [`Tx`] and [`Rx`] attach.
+    pub fn reset(&mut self) {
+        unsafe { self.clear(0) }
+    }
+
+    /// Same as [`Link::reset`], but fails if a [`Tx`] or an [`Rx`] is still attached.
+    ///
+    /// Attaching a [`Tx`] or an [`Rx`] while the link is being reset panics.
+    pub fn try_reset(&self) -> Result<(), InUse> {
+        match utils::compare_exchange_loop(
+            &self.flags,
+            self.max_iterations_for_atomic_update(),
+            None,
+            |old_flags| {
+                if old_flags & (FLAG_TX_IS_SET | FLAG_RX_IS_SET) != 0 {
+                    Err(InUse)
+                } else {
+                    Ok(AtomicUpdate::Set(old_flags | FLAG_TX_IS_SET | FLAG_RX_IS_SET))
+                }
+            },
+        ) {
+            Ok(_) => (),
+            Err(None) => panic!("failed to perform atomic update"),
+            Err(Some(in_use)) => return Err(in_use),
+        }
+
+        unsafe { self.clear(0) };
+        Ok(())
+    }
+
+    /// Same as [`Link::try_reset`], but attaches a new [`Tx`] and [`Rx`] to the link right away,
+    /// so that no one else may claim it in between.
+    #[allow(clippy::type_complexity)]
+    pub fn try_reuse(&self) -> Result<(Tx<T, &Self, R>, Rx<T, &Self, R>), InUse> {
+        match utils::compare_exchange_loop(
+            &self.flags,
+            self.max_iterations_for_atomic_update(),
+            None,
+            |old_flags| {
+                if old_flags & (FLAG_TX_IS_SET | FLAG_RX_IS_SET) != 0 {
+                    Err(InUse)
+                } else {
+                    Ok(AtomicUpdate::Set(old_flags | FLAG_TX_IS_SET | FLAG_RX_IS_SET))
+                }
+            },
+        ) {
+            Ok(_) => (),
+            Err(None) => panic!("failed to perform atomic update"),
+            Err(Some(in_use)) => return Err(in_use),
+        }
+
+        unsafe { self.clear(FLAG_TX_IS_SET | FLAG_RX_IS_SET) };
+
+        let tx = Tx { link: self, _value: Default::default(), _reason: Default::default() };
+        let rx = Rx { link: self, _value: Default::default(), _reason: Default::default() };
+        Ok((tx, rx))
+    }
 }
 
-impl<T> Link<T> {
-    fn poll_recv(&self, cx: &mut Context) -> Poll<Result<T, RecvError>> {
+impl<T, R> Link<T, R>
+where
+    R: Clone,
+{
+    fn poll_recv(&self, cx: &mut Context) -> Poll<Result<T, RecvError<R>>> {
         self.rx_waker.register(cx.waker());
-        match self.recv_nowait() {
+        match self.recv_nowait().map_err(|e| e.with_reason(|| self.reason.get())) {
             Ok(value) => Poll::Ready(Ok(value)),
             Err(RecvErrorNoWait::Closed) => Poll::Ready(Err(RecvError::closed())),
+            Err(RecvErrorNoWait::ClosedWith(reason)) =>
+                Poll::Ready(Err(RecvError::closed_with(reason))),
             Err(RecvErrorNoWait::Empty) => Poll::Pending,
         }
     }
 
-    fn poll_send(&self, cx: &mut Context, value: &mut Option<T>) -> Poll<Result<(), SendError<T>>> {
+    fn poll_send(
+        &self,
+        cx: &mut Context,
+        value: &mut Option<T>,
+    ) -> Poll<Result<(), SendError<T, R>>> {
         self.tx_waker.register(cx.waker());
-        match self.send_nowait(value.take().expect("stolen value")) {
+        match self
+            .send_nowait(value.take().expect("stolen value"))
+            .map_err(|e| e.with_reason(|| self.reason.get()))
+        {
             Ok(()) => Poll::Ready(Ok(())),
             Err(SendErrorNoWait::Closed(rejected)) => Poll::Ready(Err(SendError::closed(rejected))),
+            Err(SendErrorNoWait::ClosedWith(rejected, reason)) =>
+                Poll::Ready(Err(SendError::closed_with(rejected, reason))),
+            Err(SendErrorNoWait::Rejected(rejected)) =>
+                Poll::Ready(Err(SendError::rejected(rejected))),
+            Err(SendErrorNoWait::Evicted(evicted)) => Poll::Ready(Err(SendError::evicted(evicted))),
             Err(SendErrorNoWait::Full(rejected)) => {
                 *value = Some(rejected);
                 Poll::Pending
@@ -127,6 +249,17 @@ impl<T> Link<T> {
         }
     }
 
+    fn poll_closed(&self, cx: &mut Context) -> Poll<()> {
+        self.tx_waker.register(cx.waker());
+        if self.flags.load(Ordering::SeqCst) & FLAG_IS_CLOSED != 0 {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+impl<T, R> Link<T, R> {
     fn recv_nowait(&self) -> Result<T, RecvErrorNoWait> {
         let flags = self.flags.load(Ordering::SeqCst);
 
@@ -182,6 +315,13 @@ impl<T> Link<T> {
         }
     }
 
+    fn close_with(&self, reason: R, notify_tx: bool, notify_rx: bool) {
+        if self.flags.load(Ordering::SeqCst) & FLAG_IS_CLOSED == 0 {
+            let _ = self.reason.set(reason);
+        }
+        self.close(notify_tx, notify_rx)
+    }
+
     fn close(&self, notify_tx: bool, notify_rx: bool) {
         utils::compare_exchange_loop(
             &self.flags,
@@ -199,6 +339,29 @@ impl<T> Link<T> {
         }
     }
 
+    fn detach(&self, flag: u8) {
+        utils::compare_exchange_loop(
+            &self.flags,
+            self.max_iterations_for_atomic_update(),
+            None,
+            |old_flags| Ok::<_, Infallible>(AtomicUpdate::Set(old_flags & !flag)),
+        )
+        .expect("failed to perform atomic update");
+    }
+
+    /// Drops the value and the reason, and sets the flags to `flags`.
+    ///
+    /// # Safety
+    /// Nothing else may access the link.
+    unsafe fn clear(&self, flags: u8) {
+        if self.flags.load(Ordering::SeqCst) & FLAG_IS_FULL != 0 {
+            unsafe { self.slot.as_maybe_uninit_mut().assume_init_drop() };
+        }
+        unsafe { self.reason.clear() };
+
+        self.flags.store(flags, Ordering::SeqCst);
+    }
+
     fn set_tx(&self) {
         if let Err(err) = utils::compare_exchange_loop(
             &self.flags,
@@ -237,18 +400,28 @@ impl<T> Link<T> {
     }
 }
 
-impl<T> Default for Link<T> {
+/// Resets the link before it is dropped, so that leaked endpoints go unreported.
+struct Scope<T, R>(Link<T, R>);
+
+impl<T, R> Drop for Scope<T, R> {
+    fn drop(&mut self) {
+        self.0.reset()
+    }
+}
+
+impl<T, R> Default for Link<T, R> {
     fn default() -> Self {
         Self {
             flags: Default::default(),
             rx_waker: Default::default(),
             tx_waker: Default::default(),
             slot: Default::default(),
+            reason: Default::default(),
         }
     }
 }
 
-impl<T> Drop for Link<T> {
+impl<T, R> Drop for Link<T, R> {
     fn drop(&mut self) {
         let flags = self.flags.load(Ordering::SeqCst);
         let is_closed = flags & FLAG_IS_CLOSED != 0;
@@ -256,7 +429,7 @@ impl<T> Drop for Link<T> {
         let rx_is_set = flags & FLAG_RX_IS_SET != 0;
 
         if !is_closed && (tx_is_set || rx_is_set) {
-            panic!("Dropping unclosed Link")
+            crate::leak::report::<Self>("Dropping unclosed Link")
         }
         if flags & FLAG_IS_FULL != 0 {
             unsafe {
@@ -266,20 +439,75 @@ impl<T> Drop for Link<T> {
     }
 }
 
-impl<T, L> Drop for Rx<T, L>
+impl<T, L, R> Drop for Rx<T, L, R>
 where
-    L: Borrow<Link<T>>,
+    L: Borrow<Link<T, R>>,
 {
     fn drop(&mut self) {
-        self.link.borrow().close(/* notify_tx: */ true, /* notify_rx: */ false);
+        let link = self.link.borrow();
+        link.close(/* notify_tx: */ true, /* notify_rx: */ false);
+        link.detach(FLAG_RX_IS_SET);
     }
 }
 
-impl<T, L> Drop for Tx<T, L>
+impl<T, L, R> Drop for Tx<T, L, R>
 where
-    L: Borrow<Link<T>>,
+    L: Borrow<Link<T, R>>,
 {
     fn drop(&mut self) {
-        self.link.borrow().close(/* notify_tx: */ false, /* notify_rx: */ true);
+        let link = self.link.borrow();
+        link.close(/* notify_tx: */ false, /* notify_rx: */ true);
+        link.detach(FLAG_TX_IS_SET);
+    }
+}
+
+impl<T, R> crate::shutdown::Close for Link<T, R> {
+    fn close(&self) {
+        Link::close(self, true, true)
+    }
+}
+
+impl<T, L, R> crate::select::PollRecv for Rx<T, L, R>
+where
+    L: Borrow<Link<T, R>>,
+    R: Clone,
+{
+    type Item = T;
+    type Reason = R;
+
+    fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Result<T, RecvError<R>>> {
+        let link = self.link.borrow();
+        link.poll_recv(cx)
+    }
+
+    fn close(&mut self) {
+        Rx::close(self)
+    }
+}
+
+impl<T, L, R> crate::route::PollSend for Tx<T, L, R>
+where
+    L: Borrow<Link<T, R>>,
+    R: Clone,
+{
+    type Item = T;
+    type Reason = R;
+
+    fn poll_send(
+        &mut self,
+        cx: &mut Context<'_>,
+        value: &mut Option<T>,
+    ) -> Poll<Result<(), SendError<T, R>>> {
+        let link = self.link.borrow();
+        link.poll_send(cx, value)
+    }
+
+    fn poll_closed(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        let link = self.link.borrow();
+        link.poll_closed(cx)
+    }
+
+    fn close(&mut self) {
+        Tx::close(self)
     }
 }
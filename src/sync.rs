@@ -0,0 +1,27 @@
+use core::future;
+use core::sync::atomic::{AtomicBool, AtomicUsize};
+
+use crate::atomic_waker::AtomicWaker;
+use crate::error::LimitReached;
+use crate::mpmc::wakers;
+
+mod barrier;
+mod latch;
+mod mutex;
+mod rwlock;
+mod wait_group;
+
+pub use barrier::{Barrier, BarrierWaitResult};
+pub use latch::CountDownLatch;
+pub use mutex::{Mutex, MutexGuard};
+pub use rwlock::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+pub use wait_group::WaitGroup;
+
+async fn wait_until(
+    refs: &AtomicUsize,
+    wakers: &[(AtomicBool, AtomicWaker)],
+    mut is_ready: impl FnMut() -> bool,
+) -> Result<(), LimitReached> {
+    let mut waiter = wakers::Waiter::new(refs, wakers);
+    future::poll_fn(|cx| waiter.poll(cx.waker(), || is_ready().then_some(()))).await
+}
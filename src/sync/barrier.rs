@@ -0,0 +1,126 @@
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use crate::atomic_waker::AtomicWaker;
+use crate::error::LimitReached;
+use crate::mpmc::wakers;
+use crate::utils::{self, AtomicUpdate};
+
+type Usize = <AtomicUsize as crate::utils::AtomicValue>::Value;
+const HALF_BITS: u8 = (Usize::BITS / 2) as u8;
+
+/// Lets `n` tasks wait until all of them have arrived; then starts over.
+///
+/// A task that arrived stays counted even if its [`Barrier::wait`] future is dropped.
+///
+/// All the parties but the last one wait, each in one of the wakers of `W`: a barrier for `n`
+/// parties needs `n - 1` wakers.
+pub struct Barrier<W>
+where
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    parties: usize,
+
+    /// two halves:
+    /// - arrived    [ 0..=31 / 0..=15  ]
+    /// - generation [32..=63 / 16..=31 ]
+    state: AtomicUsize,
+
+    refs: AtomicUsize,
+
+    wakers: W,
+}
+
+/// Returned by [`Barrier::wait`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BarrierWaitResult(bool);
+
+impl<W> Barrier<W>
+where
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    /// Creates a new [`Barrier`] for `parties` tasks.
+    pub fn new(parties: usize, wakers: W) -> Self {
+        assert!(parties > 0, "a barrier needs at least one party");
+        assert!(parties <= max_parties(), "too many parties");
+
+        Self { parties, state: Default::default(), refs: Default::default(), wakers }
+    }
+
+    /// Waits until all the parties have arrived.
+    ///
+    /// Fails with [`LimitReached`] if it has to wait while all the wakers are taken; the task is
+    /// still counted as arrived.
+    pub async fn wait(&self) -> Result<BarrierWaitResult, LimitReached> {
+        let (generation, is_leader) = self.arrive();
+        if is_leader {
+            wakers::notify(self.wakers.as_ref());
+        } else {
+            super::wait_until(&self.refs, self.wakers.as_ref(), || {
+                self::generation(self.state.load(Ordering::SeqCst)) != generation
+            })
+            .await?
+        }
+        Ok(BarrierWaitResult(is_leader))
+    }
+
+    /// Blocks the current thread until all the parties have arrived.
+    #[cfg(feature = "std")]
+    pub fn wait_blocking(&self) -> Result<BarrierWaitResult, LimitReached> {
+        utils::block_on(self.wait())
+    }
+
+    fn arrive(&self) -> (Usize, bool) {
+        let mut output = None;
+        utils::compare_exchange_loop(
+            &self.state,
+            utils::ATOMIC_UPDATE_MAX_ITERATIONS,
+            None,
+            |state| {
+                let generation = self::generation(state);
+                let arrived = self::arrived(state) + 1;
+                let state = if arrived == self.parties {
+                    output = Some((generation, true));
+                    pack(0, generation.wrapping_add(1))
+                } else {
+                    output = Some((generation, false));
+                    pack(arrived, generation)
+                };
+                Ok::<_, core::convert::Infallible>(AtomicUpdate::Set(state))
+            },
+        )
+        .expect("failed to perform atomic update");
+        output.unwrap()
+    }
+}
+
+impl BarrierWaitResult {
+    /// Whether this task was the last to arrive. Exactly one task per round is the leader.
+    pub fn is_leader(&self) -> bool {
+        self.0
+    }
+}
+
+impl<W> Drop for Barrier<W>
+where
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn drop(&mut self) {
+        if *self.refs.get_mut() != 0 {
+            crate::leak::report::<Self>("Dropping Barrier that is still referenced?")
+        }
+    }
+}
+
+fn max_parties() -> Usize {
+    !(Usize::MAX << HALF_BITS)
+}
+fn arrived(state: Usize) -> Usize {
+    utils::bits::unpack::<Usize, 0, HALF_BITS>(state)
+}
+fn generation(state: Usize) -> Usize {
+    utils::bits::unpack::<Usize, HALF_BITS, HALF_BITS>(state)
+}
+fn pack(arrived: Usize, generation: Usize) -> Usize {
+    let state = utils::bits::pack::<Usize, 0, HALF_BITS>(0, arrived);
+    utils::bits::pack::<Usize, HALF_BITS, HALF_BITS>(state, generation)
+}
@@ -0,0 +1,75 @@
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use crate::atomic_waker::AtomicWaker;
+use crate::error::LimitReached;
+use crate::mpmc::wakers;
+use crate::utils::{self, AtomicUpdate};
+
+/// Lets tasks wait until a counter, set once at creation, is counted down to zero.
+///
+/// Each task waiting for zero holds one of the wakers in `W` until the count reaches it.
+pub struct CountDownLatch<W>
+where
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    count: AtomicUsize,
+
+    refs: AtomicUsize,
+
+    wakers: W,
+}
+
+impl<W> CountDownLatch<W>
+where
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    /// Creates a new [`CountDownLatch`]
+    pub fn new(count: usize, wakers: W) -> Self {
+        Self { count: AtomicUsize::new(count), refs: Default::default(), wakers }
+    }
+
+    /// The current count.
+    pub fn count(&self) -> usize {
+        self.count.load(Ordering::SeqCst)
+    }
+
+    /// Decrements the count, releasing the waiters when it reaches zero.
+    ///
+    /// Does nothing if the count is zero already.
+    pub fn count_down(&self) {
+        match utils::compare_exchange_loop(
+            &self.count,
+            utils::ATOMIC_UPDATE_MAX_ITERATIONS,
+            None,
+            |count| count.checked_sub(1).map(AtomicUpdate::Set).ok_or(()),
+        ) {
+            Ok(0) => wakers::notify(self.wakers.as_ref()),
+            Ok(_) | Err(Some(())) => (),
+            Err(None) => panic!("Failed to perform atomic update"),
+        }
+    }
+
+    /// Waits until the count reaches zero.
+    ///
+    /// Fails with [`LimitReached`] if it has to wait while all the wakers are taken.
+    pub async fn wait(&self) -> Result<(), LimitReached> {
+        super::wait_until(&self.refs, self.wakers.as_ref(), || self.count() == 0).await
+    }
+
+    /// Blocks the current thread until the count reaches zero.
+    #[cfg(feature = "std")]
+    pub fn wait_blocking(&self) -> Result<(), LimitReached> {
+        utils::block_on(self.wait())
+    }
+}
+
+impl<W> Drop for CountDownLatch<W>
+where
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn drop(&mut self) {
+        if *self.refs.get_mut() != 0 {
+            crate::leak::report::<Self>("Dropping CountDownLatch that is still referenced?")
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use core::future;
+use core::marker::PhantomData;
+use core::ops::{Deref, DerefMut};
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use crate::atomic_waker::AtomicWaker;
+use crate::error::LimitReached;
+use crate::mpmc::wakers;
+use crate::slot::Slot;
+
+/// An async mutual exclusion lock.
+///
+/// Each task waiting for the lock holds one of the wakers in `W`; once they are all held, further
+/// attempts to wait fail. Unlocking wakes every waiting task and the first one to retry gets the
+/// lock, so the lock is not fair.
+pub struct Mutex<T, W>
+where
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    value: Slot<T>,
+    is_locked: AtomicBool,
+
+    refs: AtomicUsize,
+
+    wakers: W,
+}
+
+/// Exclusive access to the value of a [`Mutex`], released when dropped.
+pub struct MutexGuard<'a, T, W>
+where
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    _value: PhantomData<&'a mut T>,
+
+    mutex: &'a Mutex<T, W>,
+}
+
+impl<T, W> Mutex<T, W>
+where
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    /// Creates a new [`Mutex`]
+    pub fn new(value: T, wakers: W) -> Self {
+        let slot = Slot::default();
+        unsafe { slot.as_maybe_uninit_mut() }.write(value);
+
+        Self { value: slot, is_locked: AtomicBool::new(false), refs: Default::default(), wakers }
+    }
+
+    /// Locks the mutex if it is not locked.
+    pub fn try_lock(&self) -> Option<MutexGuard<'_, T, W>> {
+        if self.is_locked.swap(true, Ordering::SeqCst) {
+            None
+        } else {
+            Some(MutexGuard { _value: Default::default(), mutex: self })
+        }
+    }
+
+    /// Locks the mutex, waits if necessary.
+    ///
+    /// Fails with [`LimitReached`] if it has to wait while all the wakers are taken.
+    pub async fn lock(&self) -> Result<MutexGuard<'_, T, W>, LimitReached> {
+        let mut waiter = wakers::Waiter::new(&self.refs, self.wakers.as_ref());
+        future::poll_fn(|cx| waiter.poll(cx.waker(), || self.try_lock())).await
+    }
+
+    /// Returns the value; no locking is needed as the mutex is borrowed mutably.
+    pub fn get_mut(&mut self) -> &mut T {
+        unsafe { self.value.as_maybe_uninit_mut().assume_init_mut() }
+    }
+
+    /// Consumes the mutex, returning the value.
+    pub fn into_inner(self) -> T {
+        let this = core::mem::ManuallyDrop::new(self);
+        let value = unsafe { this.value.as_maybe_uninit_mut().assume_init_read() };
+        let _wakers = unsafe { core::ptr::read(&this.wakers) };
+        value
+    }
+
+    fn unlock(&self) {
+        self.is_locked.store(false, Ordering::SeqCst);
+        wakers::notify(self.wakers.as_ref());
+    }
+}
+
+impl<T, W> Deref for MutexGuard<'_, T, W>
+where
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { self.mutex.value.as_maybe_uninit_mut().assume_init_ref() }
+    }
+}
+
+impl<T, W> DerefMut for MutexGuard<'_, T, W>
+where
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { self.mutex.value.as_maybe_uninit_mut().assume_init_mut() }
+    }
+}
+
+impl<T, W> Drop for MutexGuard<'_, T, W>
+where
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn drop(&mut self) {
+        self.mutex.unlock();
+    }
+}
+
+impl<T, W> Drop for Mutex<T, W>
+where
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn drop(&mut self) {
+        if *self.refs.get_mut() != 0 {
+            crate::leak::report::<Self>("Dropping Mutex that is still referenced?")
+        }
+
+        unsafe { self.value.as_maybe_uninit_mut().assume_init_drop() }
+    }
+}
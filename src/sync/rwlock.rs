@@ -0,0 +1,205 @@
+use core::future;
+use core::marker::PhantomData;
+use core::ops::{Deref, DerefMut};
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use crate::atomic_waker::AtomicWaker;
+use crate::error::LimitReached;
+use crate::mpmc::wakers;
+use crate::slot::Slot;
+use crate::utils::{self, AtomicUpdate};
+
+const WRITER: usize = 1;
+const READER: usize = 2;
+
+/// An async readers-writer lock.
+///
+/// Readers and writers that have to wait share the wakers in `W`, one each, and fail to wait once
+/// all of them are held. The lock is not fair: a steady stream of readers may keep a writer
+/// waiting.
+pub struct RwLock<T, W>
+where
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    _value: PhantomData<T>,
+
+    value: Slot<T>,
+
+    /// 1bit writer flag [0]
+    /// readers count [1..]
+    state: AtomicUsize,
+
+    refs: AtomicUsize,
+
+    wakers: W,
+}
+
+/// Shared access to the value of a [`RwLock`], released when dropped.
+pub struct RwLockReadGuard<'a, T, W>
+where
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    _value: PhantomData<&'a T>,
+
+    lock: &'a RwLock<T, W>,
+}
+
+/// Exclusive access to the value of a [`RwLock`], released when dropped.
+pub struct RwLockWriteGuard<'a, T, W>
+where
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    _value: PhantomData<&'a mut T>,
+
+    lock: &'a RwLock<T, W>,
+}
+
+impl<T, W> RwLock<T, W>
+where
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    /// Creates a new [`RwLock`]
+    pub fn new(value: T, wakers: W) -> Self {
+        let slot = Slot::default();
+        unsafe { slot.as_maybe_uninit_mut() }.write(value);
+
+        Self {
+            _value: Default::default(),
+            value: slot,
+            state: AtomicUsize::new(0),
+            refs: Default::default(),
+            wakers,
+        }
+    }
+
+    /// Locks for reading if there is no writer.
+    pub fn try_read(&self) -> Option<RwLockReadGuard<'_, T, W>> {
+        match utils::compare_exchange_loop(
+            &self.state,
+            utils::ATOMIC_UPDATE_MAX_ITERATIONS,
+            None,
+            |state| {
+                if state & WRITER != 0 {
+                    Err(())
+                } else {
+                    let state = state.checked_add(READER).expect("too many readers");
+                    Ok(AtomicUpdate::Set(state))
+                }
+            },
+        ) {
+            Ok(_) => Some(RwLockReadGuard { _value: Default::default(), lock: self }),
+            Err(None) => panic!("Failed to perform atomic update"),
+            Err(Some(())) => None,
+        }
+    }
+
+    /// Locks for writing if there are neither readers nor a writer.
+    pub fn try_write(&self) -> Option<RwLockWriteGuard<'_, T, W>> {
+        self.state
+            .compare_exchange(0, WRITER, Ordering::SeqCst, Ordering::SeqCst)
+            .ok()
+            .map(|_| RwLockWriteGuard { _value: Default::default(), lock: self })
+    }
+
+    /// Locks for reading, waits if necessary.
+    ///
+    /// Fails with [`LimitReached`] if it has to wait while all the wakers are taken.
+    pub async fn read(&self) -> Result<RwLockReadGuard<'_, T, W>, LimitReached> {
+        let mut waiter = wakers::Waiter::new(&self.refs, self.wakers.as_ref());
+        future::poll_fn(|cx| waiter.poll(cx.waker(), || self.try_read())).await
+    }
+
+    /// Locks for writing, waits if necessary.
+    ///
+    /// Fails with [`LimitReached`] if it has to wait while all the wakers are taken.
+    pub async fn write(&self) -> Result<RwLockWriteGuard<'_, T, W>, LimitReached> {
+        let mut waiter = wakers::Waiter::new(&self.refs, self.wakers.as_ref());
+        future::poll_fn(|cx| waiter.poll(cx.waker(), || self.try_write())).await
+    }
+
+    /// Returns the value; no locking is needed as the lock is borrowed mutably.
+    pub fn get_mut(&mut self) -> &mut T {
+        unsafe { self.value.as_maybe_uninit_mut().assume_init_mut() }
+    }
+
+    /// Consumes the lock, returning the value.
+    pub fn into_inner(self) -> T {
+        let this = core::mem::ManuallyDrop::new(self);
+        let value = unsafe { this.value.as_maybe_uninit_mut().assume_init_read() };
+        let _wakers = unsafe { core::ptr::read(&this.wakers) };
+        value
+    }
+
+    fn unlock_read(&self) {
+        if self.state.fetch_sub(READER, Ordering::SeqCst) == READER {
+            wakers::notify(self.wakers.as_ref());
+        }
+    }
+
+    fn unlock_write(&self) {
+        self.state.store(0, Ordering::SeqCst);
+        wakers::notify(self.wakers.as_ref());
+    }
+}
+
+impl<T, W> Deref for RwLockReadGuard<'_, T, W>
+where
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { self.lock.value.as_maybe_uninit_mut().assume_init_ref() }
+    }
+}
+
+impl<T, W> Deref for RwLockWriteGuard<'_, T, W>
+where
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { self.lock.value.as_maybe_uninit_mut().assume_init_ref() }
+    }
+}
+
+impl<T, W> DerefMut for RwLockWriteGuard<'_, T, W>
+where
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { self.lock.value.as_maybe_uninit_mut().assume_init_mut() }
+    }
+}
+
+impl<T, W> Drop for RwLockReadGuard<'_, T, W>
+where
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn drop(&mut self) {
+        self.lock.unlock_read();
+    }
+}
+
+impl<T, W> Drop for RwLockWriteGuard<'_, T, W>
+where
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn drop(&mut self) {
+        self.lock.unlock_write();
+    }
+}
+
+impl<T, W> Drop for RwLock<T, W>
+where
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn drop(&mut self) {
+        if *self.refs.get_mut() != 0 {
+            crate::leak::report::<Self>("Dropping RwLock that is still referenced?")
+        }
+
+        unsafe { self.value.as_maybe_uninit_mut().assume_init_drop() }
+    }
+}
@@ -0,0 +1,88 @@
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use crate::atomic_waker::AtomicWaker;
+use crate::error::LimitReached;
+use crate::mpmc::wakers;
+use crate::utils::{self, AtomicUpdate};
+
+/// Lets tasks wait until all the pieces of work added to the group are done.
+///
+/// Work can be added while tasks wait; each of them holds one of the wakers in `W` until it sees
+/// no work left.
+pub struct WaitGroup<W>
+where
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    count: AtomicUsize,
+
+    refs: AtomicUsize,
+
+    wakers: W,
+}
+
+impl<W> WaitGroup<W>
+where
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    /// Creates a new [`WaitGroup`] with nothing to wait for.
+    pub fn new(wakers: W) -> Self {
+        Self { count: Default::default(), refs: Default::default(), wakers }
+    }
+
+    /// The number of pieces of work not done yet.
+    pub fn count(&self) -> usize {
+        self.count.load(Ordering::SeqCst)
+    }
+
+    /// Adds `n` pieces of work to wait for.
+    pub fn add(&self, n: usize) {
+        if let Err(err) = utils::compare_exchange_loop(
+            &self.count,
+            utils::ATOMIC_UPDATE_MAX_ITERATIONS,
+            None,
+            |count| count.checked_add(n).map(AtomicUpdate::Set).ok_or("wait-group count overflow"),
+        ) {
+            panic!("{}", err.unwrap_or("failed to perform atomic update"))
+        }
+    }
+
+    /// Marks a piece of work done, releasing the waiters when none is left.
+    ///
+    /// Panics if there is no work left.
+    pub fn done(&self) {
+        match utils::compare_exchange_loop(
+            &self.count,
+            utils::ATOMIC_UPDATE_MAX_ITERATIONS,
+            None,
+            |count| count.checked_sub(1).map(AtomicUpdate::Set).ok_or("done without work left"),
+        ) {
+            Ok(0) => wakers::notify(self.wakers.as_ref()),
+            Ok(_) => (),
+            Err(err) => panic!("{}", err.unwrap_or("failed to perform atomic update")),
+        }
+    }
+
+    /// Waits until all the work is done.
+    ///
+    /// Fails with [`LimitReached`] if it has to wait while all the wakers are taken.
+    pub async fn wait(&self) -> Result<(), LimitReached> {
+        super::wait_until(&self.refs, self.wakers.as_ref(), || self.count() == 0).await
+    }
+
+    /// Blocks the current thread until all the work is done.
+    #[cfg(feature = "std")]
+    pub fn wait_blocking(&self) -> Result<(), LimitReached> {
+        utils::block_on(self.wait())
+    }
+}
+
+impl<W> Drop for WaitGroup<W>
+where
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn drop(&mut self) {
+        if *self.refs.get_mut() != 0 {
+            crate::leak::report::<Self>("Dropping WaitGroup that is still referenced?")
+        }
+    }
+}
@@ -0,0 +1,382 @@
+use core::borrow::Borrow;
+use core::future;
+use core::marker::PhantomData;
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use core::task::{Context, Poll};
+
+use crate::atomic_waker::AtomicWaker;
+use crate::error::{LimitReached, RecvError, RecvErrorNoWait, SendError, SendErrorNoWait};
+use crate::mpmc::wakers;
+use crate::reason::Reason;
+
+mod segment;
+
+/// A medium through which [`Rx`] and [`Tx`] communicate.
+///
+/// The values are stored in segments allocated as the channel grows, so it is never full. There
+/// is no limit on the number of [`Tx`]s; the number of [`Rx`]s is limited by the number of
+/// rx-wakers.
+///
+/// Any endpoint may close the link with a reason of type `R`. The link is closed when the last
+/// [`Tx`] is dropped.
+pub struct Link<T, W, R = ()>
+where
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    _value: PhantomData<T>,
+
+    queue: segment::Queue<T>,
+
+    refs: AtomicUsize,
+    txs: AtomicUsize,
+
+    reason: Reason<R>,
+
+    rx_wakers: W,
+
+    /// the [`Tx`]s never wait to send: only one polled for the closing of the link is woken
+    closed_waker: AtomicWaker,
+}
+
+/// The sending side of the channel
+pub struct Tx<T, L, W, R = ()>
+where
+    L: Borrow<Link<T, W, R>>,
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    _value: PhantomData<T>,
+    _rx_wakers: PhantomData<W>,
+    _reason: PhantomData<R>,
+
+    link: L,
+}
+
+/// The receiving side of the channel
+pub struct Rx<T, L, W, R = ()>
+where
+    L: Borrow<Link<T, W, R>>,
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    _value: PhantomData<T>,
+    _rx_wakers: PhantomData<W>,
+    _reason: PhantomData<R>,
+
+    link: L,
+    idx: usize,
+}
+
+impl<T, L, W, R> Tx<T, L, W, R>
+where
+    L: Borrow<Link<T, W, R>>,
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    /// Creates a new [`Tx`]
+    pub fn new(link: L) -> Self {
+        link.borrow().attach_tx();
+
+        Self {
+            _value: Default::default(),
+            _rx_wakers: Default::default(),
+            _reason: Default::default(),
+            link,
+        }
+    }
+
+    /// Sends a value.
+    ///
+    /// Fails only if the channel is closed.
+    pub fn send_nowait(&mut self, value: T) -> Result<(), SendErrorNoWait<T, R>>
+    where
+        R: Clone,
+    {
+        let link = self.link.borrow();
+        link.send_nowait(value).map_err(|e| e.with_reason(|| link.reason.get()))
+    }
+
+    /// Sends a value.
+    ///
+    /// Never waits: same as [`Tx::send_nowait`].
+    pub async fn send(&mut self, value: T) -> Result<(), SendError<T, R>>
+    where
+        R: Clone,
+    {
+        self.send_now(value)
+    }
+
+    fn send_now(&mut self, value: T) -> Result<(), SendError<T, R>>
+    where
+        R: Clone,
+    {
+        self.send_nowait(value).map_err(|e| match e {
+            SendErrorNoWait::Closed(rejected) => SendError::closed(rejected),
+            SendErrorNoWait::ClosedWith(rejected, reason) =>
+                SendError::closed_with(rejected, reason),
+            SendErrorNoWait::Full(_) |
+            SendErrorNoWait::Rejected(_) |
+            SendErrorNoWait::Evicted(_) => unreachable!("unbounded link rejected a value"),
+        })
+    }
+
+    /// Closes the channel.
+    pub fn close(&mut self) {
+        self.link.borrow().close()
+    }
+
+    /// Closes the channel with a reason.
+    ///
+    /// The reason is dropped if the channel is closed already.
+    pub fn close_with(&mut self, reason: R) {
+        self.link.borrow().close_with(reason)
+    }
+}
+
+impl<T, L, W, R> Clone for Tx<T, L, W, R>
+where
+    L: Borrow<Link<T, W, R>> + Clone,
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn clone(&self) -> Self {
+        Self::new(self.link.clone())
+    }
+}
+
+impl<T, L, W, R> Rx<T, L, W, R>
+where
+    L: Borrow<Link<T, W, R>>,
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    /// Creates a new [`Rx`]
+    pub fn new(link: L) -> Self {
+        let idx = link.borrow().try_attach_rx().expect("all rx-wakers are taken");
+
+        Self {
+            _value: Default::default(),
+            _rx_wakers: Default::default(),
+            _reason: Default::default(),
+            link,
+            idx,
+        }
+    }
+
+    /// Try cloning this [`Rx`].
+    ///
+    /// Fails when all wakers are taken.
+    pub fn try_clone(&self) -> Result<Self, LimitReached>
+    where
+        L: Clone,
+    {
+        let idx = self.link.borrow().try_attach_rx().map_err(|()| LimitReached)?;
+
+        Ok(Self {
+            _value: Default::default(),
+            _rx_wakers: Default::default(),
+            _reason: Default::default(),
+            link: self.link.clone(),
+            idx,
+        })
+    }
+
+    /// Receives a value if it is ready.
+    pub fn recv_nowait(&mut self) -> Result<T, RecvErrorNoWait<R>>
+    where
+        R: Clone,
+    {
+        let link = self.link.borrow();
+        link.queue.recv().map_err(|e| e.with_reason(|| link.reason.get()))
+    }
+
+    /// Receives a value, waits if necessary.
+    pub async fn recv(&mut self) -> Result<T, RecvError<R>>
+    where
+        R: Clone,
+    {
+        let link = self.link.borrow();
+        future::poll_fn(|cx| link.poll_recv(cx, self.idx)).await
+    }
+
+    /// Closes the channel.
+    pub fn close(&mut self) {
+        self.link.borrow().close()
+    }
+
+    /// Closes the channel with a reason.
+    ///
+    /// The reason is dropped if the channel is closed already.
+    pub fn close_with(&mut self, reason: R) {
+        self.link.borrow().close_with(reason)
+    }
+}
+
+impl<T, W, R> Link<T, W, R>
+where
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    /// Creates a new [`Link`]
+    pub fn new(rx_wakers: W) -> Self {
+        Self {
+            _value: Default::default(),
+            queue: segment::Queue::new(),
+            refs: Default::default(),
+            txs: Default::default(),
+            reason: Default::default(),
+            rx_wakers,
+            closed_waker: Default::default(),
+        }
+    }
+}
+
+impl<T, W, R> Link<T, W, R>
+where
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+    R: Clone,
+{
+    fn poll_recv(&self, cx: &mut Context, idx: usize) -> Poll<Result<T, RecvError<R>>> {
+        self.rx_wakers.as_ref()[idx].1.register(cx.waker());
+        match self.queue.recv().map_err(|e| e.with_reason(|| self.reason.get())) {
+            Ok(value) => Poll::Ready(Ok(value)),
+            Err(RecvErrorNoWait::Closed) => Poll::Ready(Err(RecvError::closed())),
+            Err(RecvErrorNoWait::ClosedWith(reason)) =>
+                Poll::Ready(Err(RecvError::closed_with(reason))),
+            Err(RecvErrorNoWait::Empty) => Poll::Pending,
+        }
+    }
+}
+
+impl<T, W, R> Link<T, W, R>
+where
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn send_nowait(&self, value: T) -> Result<(), SendErrorNoWait<T>> {
+        self.queue.send(value)?;
+        self.notify_rxs();
+        Ok(())
+    }
+
+    fn attach_tx(&self) {
+        self.txs.fetch_add(1, Ordering::SeqCst);
+    }
+    fn detach_tx(&self) {
+        if self.txs.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.close();
+        }
+    }
+    fn try_attach_rx(&self) -> Result<usize, ()> {
+        wakers::try_attach(&self.refs, self.rx_wakers.as_ref())
+    }
+    fn detach_rx(&self, idx: usize) {
+        wakers::detach(&self.refs, self.rx_wakers.as_ref(), idx)
+    }
+
+    fn notify_rxs(&self) {
+        wakers::notify(self.rx_wakers.as_ref());
+    }
+
+    fn close_with(&self, reason: R) {
+        if !self.queue.is_closed() {
+            let _ = self.reason.set(reason);
+        }
+        self.close()
+    }
+
+    fn close(&self) {
+        self.queue.close();
+        self.notify_rxs();
+        self.closed_waker.wake();
+    }
+
+    fn poll_closed(&self, cx: &mut Context) -> Poll<()> {
+        self.closed_waker.register(cx.waker());
+        if self.queue.is_closed() {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+impl<T, L, W, R> Drop for Tx<T, L, W, R>
+where
+    L: Borrow<Link<T, W, R>>,
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn drop(&mut self) {
+        self.link.borrow().detach_tx();
+    }
+}
+
+impl<T, L, W, R> Drop for Rx<T, L, W, R>
+where
+    L: Borrow<Link<T, W, R>>,
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn drop(&mut self) {
+        self.link.borrow().detach_rx(self.idx);
+    }
+}
+
+impl<T, W, R> Drop for Link<T, W, R>
+where
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn drop(&mut self) {
+        if *self.refs.get_mut() != 0 || *self.txs.get_mut() != 0 {
+            crate::leak::report::<Self>("Dropping Link that is still referenced?")
+        }
+    }
+}
+
+impl<T, W, R> crate::shutdown::Close for Link<T, W, R>
+where
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+{
+    fn close(&self) {
+        Link::close(self)
+    }
+}
+
+impl<T, L, W, R> crate::select::PollRecv for Rx<T, L, W, R>
+where
+    L: Borrow<Link<T, W, R>>,
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+    R: Clone,
+{
+    type Item = T;
+    type Reason = R;
+
+    fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Result<T, RecvError<R>>> {
+        let link = self.link.borrow();
+        link.poll_recv(cx, self.idx)
+    }
+
+    fn close(&mut self) {
+        Rx::close(self)
+    }
+}
+
+impl<T, L, W, R> crate::route::PollSend for Tx<T, L, W, R>
+where
+    L: Borrow<Link<T, W, R>>,
+    W: AsRef<[(AtomicBool, AtomicWaker)]>,
+    R: Clone,
+{
+    type Item = T;
+    type Reason = R;
+
+    fn poll_send(
+        &mut self,
+        _cx: &mut Context<'_>,
+        value: &mut Option<T>,
+    ) -> Poll<Result<(), SendError<T, R>>> {
+        Poll::Ready(self.send_now(value.take().expect("stolen value")))
+    }
+
+    /// Only the last [`Tx`] polled this way is woken when the link closes.
+    fn poll_closed(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        let link = self.link.borrow();
+        link.poll_closed(cx)
+    }
+
+    fn close(&mut self) {
+        Tx::close(self)
+    }
+}
@@ -0,0 +1,254 @@
+// Adapted from the list flavor of crossbeam-channel (crossbeam-channel/src/flavors/list.rs,
+// https://github.com/crossbeam-rs/crossbeam), Copyright (c) 2019 The Crossbeam Project
+// Developers, dual-licensed under Apache-2.0 and MIT. See NOTICE for the license terms.
+
+use core::ptr;
+use core::sync::atomic::{self, AtomicPtr, AtomicU8, AtomicUsize, Ordering};
+
+use alloc::boxed::Box;
+
+use crate::error::{RecvErrorNoWait, SendErrorNoWait};
+use crate::slot::Slot;
+
+/// Indexes per segment: one for each slot, and one more marking the end of the segment.
+const LAP: usize = 32;
+const SEGMENT_CAP: usize = LAP - 1;
+
+/// The lowest bit of each index is a flag, the position is stored above it.
+const SHIFT: usize = 1;
+/// Set in the tail index once the queue is closed.
+const MARK_BIT: usize = 1;
+/// Set in the head index if the head segment is not the last one.
+const HAS_NEXT: usize = 1;
+
+/// The value has been written into the slot.
+const WRITE: u8 = 1;
+/// The value has been read from the slot.
+const READ: u8 = 2;
+/// The segment is to be destroyed by whoever reads the slot.
+const DESTROY: u8 = 4;
+
+/// A lock-free queue of values stored in linked fixed-size segments.
+pub(super) struct Queue<T> {
+    head: AtomicUsize,
+    head_segment: AtomicPtr<Segment<T>>,
+
+    tail: AtomicUsize,
+    tail_segment: AtomicPtr<Segment<T>>,
+}
+
+struct Segment<T> {
+    next: AtomicPtr<Segment<T>>,
+    slots: [Slot<T>; SEGMENT_CAP],
+    states: [AtomicU8; SEGMENT_CAP],
+}
+
+impl<T> Queue<T> {
+    pub(super) fn new() -> Self {
+        let segment = Box::into_raw(Segment::new());
+        Self {
+            head: Default::default(),
+            head_segment: AtomicPtr::new(segment),
+            tail: Default::default(),
+            tail_segment: AtomicPtr::new(segment),
+        }
+    }
+
+    pub(super) fn send(&self, value: T) -> Result<(), SendErrorNoWait<T>> {
+        let mut tail = self.tail.load(Ordering::Acquire);
+        let mut segment = self.tail_segment.load(Ordering::Acquire);
+        let mut next_segment = None;
+
+        loop {
+            if tail & MARK_BIT != 0 {
+                return Err(SendErrorNoWait::closed(value))
+            }
+
+            let offset = (tail >> SHIFT) % LAP;
+
+            // Another sender is installing the next segment.
+            if offset == SEGMENT_CAP {
+                core::hint::spin_loop();
+                tail = self.tail.load(Ordering::Acquire);
+                segment = self.tail_segment.load(Ordering::Acquire);
+                continue
+            }
+
+            // Allocated ahead, so that the next segment is installed as soon as possible.
+            if offset + 1 == SEGMENT_CAP && next_segment.is_none() {
+                next_segment = Some(Segment::new());
+            }
+
+            let new_tail = tail + (1 << SHIFT);
+            match self.tail.compare_exchange_weak(
+                tail,
+                new_tail,
+                Ordering::SeqCst,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => unsafe {
+                    if offset + 1 == SEGMENT_CAP {
+                        let next_segment =
+                            Box::into_raw(next_segment.take().expect("allocated ahead"));
+                        self.tail_segment.store(next_segment, Ordering::Release);
+                        self.tail.fetch_add(1 << SHIFT, Ordering::Release);
+                        (*segment).next.store(next_segment, Ordering::Release);
+                    }
+
+                    (*segment).slots[offset].as_maybe_uninit_mut().write(value);
+                    (*segment).states[offset].fetch_or(WRITE, Ordering::Release);
+
+                    return Ok(())
+                },
+                Err(actual) => {
+                    tail = actual;
+                    segment = self.tail_segment.load(Ordering::Acquire);
+                },
+            }
+        }
+    }
+
+    pub(super) fn recv(&self) -> Result<T, RecvErrorNoWait> {
+        let mut head = self.head.load(Ordering::Acquire);
+        let mut segment = self.head_segment.load(Ordering::Acquire);
+
+        loop {
+            let offset = (head >> SHIFT) % LAP;
+
+            // Another receiver is moving to the next segment.
+            if offset == SEGMENT_CAP {
+                core::hint::spin_loop();
+                head = self.head.load(Ordering::Acquire);
+                segment = self.head_segment.load(Ordering::Acquire);
+                continue
+            }
+
+            let mut new_head = head + (1 << SHIFT);
+
+            if new_head & HAS_NEXT == 0 {
+                atomic::fence(Ordering::SeqCst);
+                let tail = self.tail.load(Ordering::Relaxed);
+
+                if head >> SHIFT == tail >> SHIFT {
+                    return if tail & MARK_BIT != 0 {
+                        Err(RecvErrorNoWait::closed())
+                    } else {
+                        Err(RecvErrorNoWait::empty())
+                    }
+                }
+
+                if (head >> SHIFT) / LAP != (tail >> SHIFT) / LAP {
+                    new_head |= HAS_NEXT;
+                }
+            }
+
+            match self.head.compare_exchange_weak(
+                head,
+                new_head,
+                Ordering::SeqCst,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => unsafe {
+                    if offset + 1 == SEGMENT_CAP {
+                        let next = Segment::wait_next(segment);
+                        let mut next_head = (new_head & !HAS_NEXT).wrapping_add(1 << SHIFT);
+                        if !(*next).next.load(Ordering::Relaxed).is_null() {
+                            next_head |= HAS_NEXT;
+                        }
+
+                        self.head_segment.store(next, Ordering::Release);
+                        self.head.store(next_head, Ordering::Release);
+                    }
+
+                    let state = &(*segment).states[offset];
+                    while state.load(Ordering::Acquire) & WRITE == 0 {
+                        core::hint::spin_loop();
+                    }
+                    let value = (*segment).slots[offset].as_maybe_uninit_mut().assume_init_read();
+
+                    if offset + 1 == SEGMENT_CAP {
+                        Segment::destroy(segment, 0);
+                    } else if state.fetch_or(READ, Ordering::AcqRel) & DESTROY != 0 {
+                        Segment::destroy(segment, offset + 1);
+                    }
+
+                    return Ok(value)
+                },
+                Err(actual) => {
+                    head = actual;
+                    segment = self.head_segment.load(Ordering::Acquire);
+                },
+            }
+        }
+    }
+
+    pub(super) fn close(&self) {
+        self.tail.fetch_or(MARK_BIT, Ordering::SeqCst);
+    }
+
+    pub(super) fn is_closed(&self) -> bool {
+        self.tail.load(Ordering::SeqCst) & MARK_BIT != 0
+    }
+}
+
+impl<T> Drop for Queue<T> {
+    fn drop(&mut self) {
+        let mut head = *self.head.get_mut() & !((1 << SHIFT) - 1);
+        let tail = *self.tail.get_mut() & !((1 << SHIFT) - 1);
+        let mut segment = *self.head_segment.get_mut();
+
+        unsafe {
+            while head != tail {
+                let offset = (head >> SHIFT) % LAP;
+
+                if offset < SEGMENT_CAP {
+                    (*segment).slots[offset].as_maybe_uninit_mut().assume_init_drop();
+                } else {
+                    let next = *(*segment).next.get_mut();
+                    drop(Box::from_raw(segment));
+                    segment = next;
+                }
+
+                head = head.wrapping_add(1 << SHIFT);
+            }
+
+            drop(Box::from_raw(segment));
+        }
+    }
+}
+
+impl<T> Segment<T> {
+    fn new() -> Box<Self> {
+        Box::new(Self {
+            next: AtomicPtr::new(ptr::null_mut()),
+            slots: core::array::from_fn(|_| Default::default()),
+            states: Default::default(),
+        })
+    }
+
+    /// Waits until the sender that filled `this` up installs the next segment.
+    unsafe fn wait_next(this: *mut Self) -> *mut Self {
+        loop {
+            let next = (*this).next.load(Ordering::Acquire);
+            if !next.is_null() {
+                return next
+            }
+            core::hint::spin_loop();
+        }
+    }
+
+    /// Frees `this` unless a receiver is still reading one of the slots from `start` on: that
+    /// receiver frees it instead.
+    unsafe fn destroy(this: *mut Self, start: usize) {
+        // The last slot is not checked: its receiver is the one that started the destruction.
+        let states = &(*this).states;
+        for state in &states[start..SEGMENT_CAP - 1] {
+            if state.load(Ordering::Acquire) & READ == 0 &&
+                state.fetch_or(DESTROY, Ordering::AcqRel) & READ == 0
+            {
+                return
+            }
+        }
+        drop(Box::from_raw(this));
+    }
+}
@@ -32,7 +32,10 @@ where
 {
     let mut old_value = old_value.unwrap_or_else(|| atomic_value.load(Ordering::SeqCst));
     for _ in 0..max_attempts {
-        let AtomicUpdate::Set(new_value) = map_value(old_value).map_err(Some)? else { continue };
+        let AtomicUpdate::Set(new_value) = map_value(old_value).map_err(Some)? else {
+            old_value = atomic_value.load(Ordering::SeqCst);
+            continue
+        };
 
         match atomic_value.compare_exchange(
             old_value,
@@ -151,3 +154,34 @@ pub mod bits {
         }
     }
 }
+
+/// Polls the future on the current thread, parking it while the future is pending.
+#[cfg(feature = "std")]
+pub(crate) fn block_on<F: core::future::Future>(future: F) -> F::Output {
+    use core::task::{Context, Poll, Waker};
+    use std::sync::Arc;
+    use std::task::Wake;
+    use std::thread::{self, Thread};
+
+    struct Unpark(Thread);
+
+    impl Wake for Unpark {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark()
+        }
+        fn wake_by_ref(self: &Arc<Self>) {
+            self.0.unpark()
+        }
+    }
+
+    let waker = Waker::from(Arc::new(Unpark(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    let mut future = core::pin::pin!(future);
+
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output
+        }
+        thread::park();
+    }
+}
@@ -0,0 +1,231 @@
+use std::sync::Arc;
+
+use airlock::duplex::*;
+use airlock::error::{RecvError, SendErrorNoWait};
+use airlock::slot::Slot;
+
+mod utils;
+use futures::future;
+use utils::{Counted, Counter};
+
+type Value = Counted<usize>;
+
+const BUFFER_SIZE: usize = 32;
+
+#[test]
+fn t_00() {
+    let buffer_a = make_buffer::<BUFFER_SIZE>();
+    let buffer_b = make_buffer::<BUFFER_SIZE>();
+    let _link = Link::<Value, Value, _, _>::new(&buffer_a, &buffer_b);
+}
+
+#[test]
+fn t_01() {
+    let counter = Counter::new();
+    {
+        let buffer_a = make_buffer::<BUFFER_SIZE>();
+        let buffer_b = make_buffer::<BUFFER_SIZE>();
+        let link = Link::<Value, Value, _, _>::new(&buffer_a, &buffer_b);
+        let _a = EndA::new(&link);
+        let _b = EndB::new(&link);
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[test]
+#[should_panic]
+fn t_02() {
+    let buffer_a = make_buffer::<BUFFER_SIZE>();
+    let buffer_b = make_buffer::<BUFFER_SIZE>();
+    let link = Link::<Value, Value, _, _>::new(&buffer_a, &buffer_b);
+    let _a_1 = EndA::new(&link);
+    let _a_2 = EndA::new(&link);
+}
+
+#[test]
+fn t_03() {
+    let counter = Counter::new();
+    {
+        let buffer_a = make_buffer::<3>();
+        let buffer_b = make_buffer::<3>();
+        let link = Link::<Value, Value, _, _>::new(&buffer_a, &buffer_b);
+        let mut a = EndA::new(&link);
+        let mut b = EndB::new(&link);
+
+        a.send_nowait(counter.add(1)).expect("a.send-nowait");
+        a.send_nowait(counter.add(2)).expect("a.send-nowait");
+        assert!(a.send_nowait(counter.add(3)).expect_err("a.send-nowait").is_full());
+        b.send_nowait(counter.add(10)).expect("b.send-nowait");
+
+        assert_eq!(b.recv_nowait().expect("b.recv-nowait"), counter.add(1));
+        assert_eq!(b.recv_nowait().expect("b.recv-nowait"), counter.add(2));
+        assert!(b.recv_nowait().expect_err("b.recv-nowait").is_empty());
+        assert_eq!(a.recv_nowait().expect("a.recv-nowait"), counter.add(10));
+        assert!(a.recv_nowait().expect_err("a.recv-nowait").is_empty());
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[test]
+fn t_04() {
+    let counter = Counter::new();
+    {
+        let buffer_a = make_buffer::<BUFFER_SIZE>();
+        let buffer_b = make_buffer::<BUFFER_SIZE>();
+        let link = Link::<Value, Value, _, _>::new(&buffer_a, &buffer_b);
+        let mut a = EndA::new(&link);
+        let mut b = EndB::new(&link);
+
+        a.send_nowait(counter.add(1)).expect("a.send-nowait");
+        b.send_nowait(counter.add(2)).expect("b.send-nowait");
+        a.close();
+
+        assert!(a.send_nowait(counter.add(3)).expect_err("a.send-nowait").is_closed());
+        assert!(b.send_nowait(counter.add(4)).expect_err("b.send-nowait").is_closed());
+        assert!(a.recv_nowait().expect_err("a.recv-nowait").is_closed());
+
+        assert_eq!(b.recv_nowait().expect("b.recv-nowait"), counter.add(1));
+        assert!(b.recv_nowait().expect_err("b.recv-nowait").is_closed());
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[test]
+fn t_05() {
+    let counter = Counter::new();
+    {
+        let buffer_a = make_buffer::<BUFFER_SIZE>();
+        let buffer_b = make_buffer::<BUFFER_SIZE>();
+        let link = Link::<Value, Value, _, _>::new(&buffer_a, &buffer_b);
+        let mut a = EndA::new(&link);
+        let b = EndB::new(&link);
+
+        a.send_nowait(counter.add(1)).expect("a.send-nowait");
+        drop(b);
+
+        assert!(a.recv_nowait().expect_err("a.recv-nowait").is_closed());
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[tokio::test]
+async fn t_06() {
+    let counter = Counter::new();
+    {
+        let buffer_a = make_buffer::<BUFFER_SIZE>();
+        let buffer_b = make_buffer::<BUFFER_SIZE>();
+        let link = Link::<Value, Value, _, _, &str>::new(&buffer_a, &buffer_b);
+        let mut a = EndA::new(&link);
+        let mut b = EndB::new(&link);
+
+        b.send(counter.add(1)).await.expect("b.send");
+        b.close_with("done");
+
+        assert!(matches!(
+            a.send_nowait(counter.add(2)),
+            Err(SendErrorNoWait::ClosedWith(_, "done"))
+        ));
+        assert_eq!(a.recv().await.expect("a.recv"), counter.add(1));
+        assert!(matches!(a.recv().await, Err(RecvError::ClosedWith("done"))));
+        assert!(matches!(b.recv().await, Err(RecvError::ClosedWith("done"))));
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[tokio::test]
+async fn t_07() {
+    let counter = Counter::new();
+    {
+        let buffer_a = make_buffer::<4>();
+        let buffer_b = make_buffer::<4>();
+        let link = Link::<Value, Value, _, _>::new(&buffer_a, &buffer_b);
+        let mut a = EndA::new(&link);
+        let mut b = EndB::new(&link);
+
+        let client = async {
+            for i in 0..100 {
+                a.send(counter.add(i)).await.expect("a.send");
+                assert_eq!(a.recv().await.expect("a.recv"), counter.add(i * 2));
+            }
+            a.close();
+        };
+        let server = async {
+            while let Ok(request) = b.recv().await {
+                b.send(counter.add(request.unwrap() * 2)).await.expect("b.send");
+            }
+        };
+        future::join(client, server).await;
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[tokio::test]
+async fn t_08() {
+    let counter = Counter::new();
+
+    const ITERATIONS: usize = 10_000;
+
+    {
+        let link = Arc::new(Link::<Value, Value, _, _>::new(
+            make_buffer::<BUFFER_SIZE>(),
+            make_buffer::<BUFFER_SIZE>(),
+        ));
+
+        let a = {
+            let counter = counter.clone();
+            let mut a = EndA::new(Arc::clone(&link));
+            async move {
+                for i in 0..ITERATIONS {
+                    a.send(counter.add(i)).await.expect("a.send");
+                }
+                for i in 0..ITERATIONS {
+                    assert_eq!(a.recv().await.expect("a.recv"), counter.add(i));
+                }
+            }
+        };
+        let b = {
+            let counter = counter.clone();
+            let mut b = EndB::new(Arc::clone(&link));
+            async move {
+                for i in 0..ITERATIONS {
+                    assert_eq!(b.recv().await.expect("b.recv"), counter.add(i));
+                }
+                for i in 0..ITERATIONS {
+                    b.send(counter.add(i)).await.expect("b.send");
+                }
+            }
+        };
+
+        let (a, b) = future::join(tokio::spawn(a), tokio::spawn(b)).await;
+        a.expect("a");
+        b.expect("b");
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[tokio::test]
+async fn t_09() {
+    let counter = Counter::new();
+    {
+        let buffer_a = make_buffer::<BUFFER_SIZE>();
+        let buffer_b = make_buffer::<BUFFER_SIZE>();
+        let link = Link::<Value, Value, _, _>::new(&buffer_a, &buffer_b);
+        let mut a = EndA::new(&link);
+        let mut b = EndB::new(&link);
+
+        b.send_nowait(counter.add(10)).expect("b.send-nowait");
+        a.send_nowait(counter.add(1)).expect("a.send-nowait");
+        a.send_nowait(counter.add(2)).expect("a.send-nowait");
+        drop(a);
+
+        // only the direction the dropped end sent in is closed: the rest is drained
+        assert_eq!(b.recv().await.expect("b.recv"), counter.add(1));
+        assert_eq!(b.recv().await.expect("b.recv"), counter.add(2));
+        assert!(matches!(b.recv().await, Err(RecvError::Closed)));
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+fn make_buffer<const SIZE: usize>() -> [Slot<Value>; SIZE] {
+    core::array::from_fn(|_| Default::default())
+}
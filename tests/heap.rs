@@ -0,0 +1,150 @@
+use std::sync::Arc;
+
+use airlock::heap::*;
+use airlock::slot::Slot;
+
+mod utils;
+use futures::future;
+use utils::{Counted, Counter};
+
+type Value = Counted<usize>;
+
+const BUFFER_SIZE: usize = 32;
+
+#[test]
+fn t_00() {
+    let buffer = make_buffer::<BUFFER_SIZE>();
+    let _link = Link::<usize, _>::new(&buffer);
+}
+
+#[test]
+fn t_01() {
+    let buffer = make_buffer::<BUFFER_SIZE>();
+    let link = Link::<usize, _>::new(&buffer);
+    let mut tx = Tx::new(&link);
+    let mut rx = Rx::new(&link);
+
+    assert!(rx.recv_nowait().expect_err("rx.recv-nowait").is_empty());
+
+    for v in [5, 1, 9, 3, 7, 9, 0, 4] {
+        tx.send_nowait(v).expect("tx.send-nowait");
+    }
+    let received = (0..8).map(|_| rx.recv_nowait().expect("rx.recv-nowait")).collect::<Vec<_>>();
+    assert_eq!(received, [9, 9, 7, 5, 4, 3, 1, 0]);
+
+    assert!(rx.recv_nowait().expect_err("rx.recv-nowait").is_empty());
+}
+
+#[test]
+fn t_02() {
+    let counter = Counter::new();
+    {
+        let buffer = make_counted_buffer::<2>();
+        let link = Link::<Value, _>::new(&buffer);
+        let mut tx = Tx::new(&link);
+        let mut rx = Rx::new(&link);
+
+        tx.send_nowait(counter.add(1)).expect("tx.send-nowait");
+        tx.send_nowait(counter.add(2)).expect("tx.send-nowait");
+        assert!(tx.send_nowait(counter.add(3)).expect_err("tx.send-nowait").is_full());
+
+        assert_eq!(rx.recv_nowait().expect("rx.recv-nowait").unwrap(), 2);
+        tx.send_nowait(counter.add(3)).expect("tx.send-nowait");
+        assert_eq!(rx.recv_nowait().expect("rx.recv-nowait").unwrap(), 3);
+
+        tx.send_nowait(counter.add(4)).expect("tx.send-nowait");
+        tx.close();
+        assert!(tx.send_nowait(counter.add(5)).expect_err("tx.send-nowait").is_closed());
+        assert_eq!(rx.recv_nowait().expect("rx.recv-nowait").unwrap(), 4);
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[test]
+fn t_03() {
+    let counter = Counter::new();
+    {
+        let buffer = make_counted_buffer::<BUFFER_SIZE>();
+        let link = Link::<Value, _>::new(&buffer);
+        let mut tx = Tx::new(&link);
+        let mut rx = Rx::new(&link);
+
+        tx.send_nowait(counter.add(1)).expect("tx.send-nowait");
+        tx.close();
+        assert_eq!(rx.recv_nowait().expect("rx.recv-nowait").unwrap(), 1);
+        assert!(rx.recv_nowait().expect_err("rx.recv-nowait").is_closed());
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[test]
+fn t_04() {
+    let counter = Counter::new();
+    {
+        let buffer = make_counted_buffer::<BUFFER_SIZE>();
+        let link = Link::<Value, _>::new(&buffer);
+        let mut tx = Tx::new(&link);
+        let mut rx = Rx::new(&link);
+
+        for i in 0..10 {
+            tx.send_nowait(counter.add(i)).expect("tx.send-nowait");
+        }
+        rx.close();
+        assert!(tx.send_nowait(counter.add(10)).expect_err("tx.send-nowait").is_closed());
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[tokio::test]
+async fn t_05() {
+    const ITERATIONS: usize = 100_000;
+
+    let counter = Counter::new();
+    {
+        let buffer = make_counted_buffer::<BUFFER_SIZE>();
+        let link = Arc::new(Link::<Value, _>::new(buffer));
+
+        let producer = {
+            let counter = counter.clone();
+            let link = Arc::clone(&link);
+            async move {
+                let mut tx = Tx::new(link);
+                for i in 0..ITERATIONS {
+                    tx.send(counter.add(i)).await.expect("tx.send");
+                }
+            }
+        };
+        let consumer = {
+            let link = Arc::clone(&link);
+            async move {
+                let mut rx = Rx::new(link);
+                let mut count = 0;
+                while let Ok(_v) = rx.recv().await {
+                    count += 1;
+                }
+                count
+            }
+        };
+
+        let (_, count) = future::join(tokio::spawn(producer), tokio::spawn(consumer)).await;
+        assert_eq!(count.expect("consumer.join"), ITERATIONS);
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[test]
+#[should_panic]
+fn t_06() {
+    let buffer = make_buffer::<BUFFER_SIZE>();
+    let link = Link::<usize, _>::new(&buffer);
+    let mut _rx_1 = Rx::new(&link);
+    let mut _rx_2 = Rx::new(&link);
+}
+
+fn make_buffer<const SIZE: usize>() -> [Slot<usize>; SIZE] {
+    core::array::from_fn(|_| Default::default())
+}
+
+fn make_counted_buffer<const SIZE: usize>() -> [Slot<Value>; SIZE] {
+    core::array::from_fn(|_| Default::default())
+}
@@ -0,0 +1,51 @@
+use std::panic;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use airlock::leak::{self, Policy};
+use airlock::spsc::direct::*;
+
+mod utils;
+use utils::{Counted, Counter};
+
+type Value = Counted<usize>;
+
+static REPORTED: AtomicUsize = AtomicUsize::new(0);
+
+fn hook(link: &'static str) {
+    assert!(link.contains("spsc::direct::Link"));
+    REPORTED.fetch_add(1, Ordering::SeqCst);
+}
+
+fn leak_endpoint(counter: &Counter) -> Result<(), Box<dyn std::any::Any + Send>> {
+    panic::catch_unwind(|| {
+        let link = Link::<Value>::new();
+        let mut tx = Tx::new(&link);
+        tx.send_nowait(counter.add(1)).expect("tx.send-nowait");
+        std::mem::forget(tx);
+    })
+}
+
+#[test]
+fn t_00() {
+    let counter = Counter::new();
+
+    assert_eq!(leak::policy(), Policy::Panic);
+    assert!(leak_endpoint(&counter).is_err());
+    assert_eq!(counter.count(), 1);
+
+    leak::set_hook(Some(hook));
+    leak::set_policy(Policy::Close);
+    assert!(leak_endpoint(&counter).is_ok());
+    assert_eq!(REPORTED.load(Ordering::SeqCst), 1);
+    assert_eq!(counter.count(), 1);
+
+    leak::set_policy(Policy::DebugPanic);
+    assert_eq!(leak_endpoint(&counter).is_err(), cfg!(debug_assertions));
+    assert_eq!(REPORTED.load(Ordering::SeqCst), 2);
+    assert_eq!(counter.count(), if cfg!(debug_assertions) { 2 } else { 1 });
+
+    leak::set_hook(None);
+    leak::set_policy(Policy::Close);
+    assert!(leak_endpoint(&counter).is_ok());
+    assert_eq!(REPORTED.load(Ordering::SeqCst), 2);
+}
@@ -0,0 +1,177 @@
+use std::sync::atomic::AtomicBool;
+
+use airlock::atomic_waker::AtomicWaker;
+use airlock::error::NoSuchLane;
+use airlock::mpmc::prio::*;
+use airlock::slot::Slot;
+
+mod utils;
+use futures::future;
+use utils::{Counted, Counter};
+
+type Value = Counted<usize>;
+
+const BUFFER_SIZE: usize = 16;
+const WAKERS_COUNT: usize = 4;
+
+#[test]
+fn t_00() {
+    let tx_wakers = make_wakers::<WAKERS_COUNT>();
+    let rx_wakers = make_wakers::<WAKERS_COUNT>();
+    let buffers = [make_buffer::<BUFFER_SIZE>(), make_buffer::<BUFFER_SIZE>()];
+    let _link = Link::<Value, _, _, _, 2>::new(buffers, &tx_wakers, &rx_wakers);
+}
+
+#[test]
+fn t_01() {
+    let counter = Counter::new();
+
+    {
+        let tx_wakers = make_wakers::<1>();
+        let rx_wakers = make_wakers::<1>();
+        let buffers = [make_buffer::<3>(), make_buffer::<3>(), make_buffer::<3>()];
+        let link = Link::<Value, _, _, _, 3>::new(buffers, &tx_wakers, &rx_wakers);
+
+        let mut tx = Tx::new(&link);
+        let mut rx = Rx::new(&link);
+
+        tx.send_nowait(lane(0), counter.add(1)).expect("tx.send-nowait");
+        tx.send_nowait(lane(0), counter.add(2)).expect("tx.send-nowait");
+        assert!(tx.send_nowait(lane(0), counter.add(3)).expect_err("tx.send-nowait").is_full());
+        tx.send_nowait(lane(2), counter.add(4)).expect("tx.send-nowait");
+        tx.send_nowait(lane(1), counter.add(5)).expect("tx.send-nowait");
+        tx.send_nowait(lane(2), counter.add(6)).expect("tx.send-nowait");
+
+        let received = (0..5)
+            .map(|_| rx.recv_nowait().expect("rx.recv-nowait").unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(received, [4, 6, 5, 1, 2]);
+        assert!(rx.recv_nowait().expect_err("rx.recv-nowait").is_empty());
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[test]
+fn t_02() {
+    let counter = Counter::new();
+
+    {
+        let tx_wakers = make_wakers::<1>();
+        let rx_wakers = make_wakers::<1>();
+        let buffers = [make_buffer::<3>(), make_buffer::<3>()];
+        let link = Link::<Value, _, _, _, 2>::new(buffers, &tx_wakers, &rx_wakers);
+
+        let mut tx = Tx::new(&link);
+        let mut rx = Rx::new(&link);
+
+        tx.send_nowait(lane(0), counter.add(1)).expect("tx.send-nowait");
+        tx.send_nowait(lane(1), counter.add(2)).expect("tx.send-nowait");
+        tx.close();
+
+        assert!(tx.send_nowait(lane(0), counter.add(3)).expect_err("tx.send-nowait").is_closed());
+        assert!(tx.send_nowait(lane(1), counter.add(4)).expect_err("tx.send-nowait").is_closed());
+
+        assert_eq!(rx.recv_nowait().expect("rx.recv-nowait").unwrap(), 2);
+        assert_eq!(rx.recv_nowait().expect("rx.recv-nowait").unwrap(), 1);
+        assert!(rx.recv_nowait().expect_err("rx.recv-nowait").is_closed());
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[test]
+fn t_03() {
+    let counter = Counter::new();
+
+    {
+        let tx_wakers = make_wakers::<1>();
+        let rx_wakers = make_wakers::<1>();
+        let buffers = [make_buffer::<3>(), make_buffer::<3>()];
+        let link = Link::<Value, _, _, _, 2>::new(buffers, &tx_wakers, &rx_wakers);
+
+        let mut tx = Tx::new(&link);
+        let _rx = Rx::new(&link);
+
+        tx.send_nowait(lane(0), counter.add(1)).expect("tx.send-nowait");
+        tx.send_nowait(lane(1), counter.add(2)).expect("tx.send-nowait");
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[tokio::test]
+async fn t_04() {
+    const ITERATIONS: usize = 10_000;
+    const LANES: usize = 3;
+
+    let counter = Counter::new();
+
+    {
+        let tx_wakers = make_wakers::<WAKERS_COUNT>();
+        let rx_wakers = make_wakers::<WAKERS_COUNT>();
+        let buffers = core::array::from_fn(|_| make_buffer::<BUFFER_SIZE>());
+        let link = Link::<Value, _, _, _, LANES>::new(buffers, &tx_wakers, &rx_wakers);
+
+        let producers = (0..WAKERS_COUNT).map(|p| {
+            let counter = &counter;
+            let link = &link;
+            async move {
+                let mut tx = Tx::new(link);
+                for i in 0..ITERATIONS {
+                    tx.send(lane((p + i) % LANES), counter.add(i)).await.expect("tx.send");
+                }
+            }
+        });
+        let consumers = (0..WAKERS_COUNT).map(|_| async {
+            let mut rx = Rx::new(&link);
+            for _i in 0..ITERATIONS {
+                rx.recv().await.expect("rx.recv");
+            }
+        });
+
+        future::join(future::join_all(producers), future::join_all(consumers)).await;
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[test]
+fn t_05() {
+    assert_eq!(Lane::<2>::new(1), Ok(Lane::HIGHEST));
+    assert_eq!(Lane::<2>::new(0), Ok(Lane::LOWEST));
+    assert_eq!(Lane::<2>::new(2), Err(NoSuchLane));
+}
+
+#[test]
+fn t_06() {
+    let counter = Counter::new();
+
+    {
+        let tx_wakers = make_wakers::<2>();
+        let rx_wakers = make_wakers::<1>();
+        let buffers = [make_buffer::<3>(), make_buffer::<3>()];
+        let link = Link::<Value, _, _, _, 2>::new(buffers, &tx_wakers, &rx_wakers);
+
+        let mut tx_1 = Tx::new(&link);
+        let tx_2 = tx_1.try_clone().expect("tx-1.try-clone");
+        let mut rx = Rx::new(&link);
+
+        tx_1.send_nowait(Lane::HIGHEST, counter.add(1)).expect("tx-1.send-nowait");
+        drop(tx_1);
+        assert_eq!(rx.recv_nowait().expect("rx.recv-nowait").unwrap(), 1);
+        assert!(rx.recv_nowait().expect_err("rx.recv-nowait").is_empty());
+
+        drop(tx_2);
+        assert!(rx.recv_nowait().expect_err("rx.recv-nowait").is_closed());
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+fn lane<const K: usize>(index: usize) -> Lane<K> {
+    Lane::new(index).expect("lane")
+}
+
+fn make_buffer<const SIZE: usize>() -> [Slot<Value>; SIZE] {
+    core::array::from_fn(|_| Default::default())
+}
+
+fn make_wakers<const SIZE: usize>() -> [(AtomicBool, AtomicWaker); SIZE] {
+    core::array::from_fn(|_| Default::default())
+}
@@ -1,7 +1,8 @@
-use std::sync::atomic::AtomicBool;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
 use airlock::atomic_waker::AtomicWaker;
+use airlock::error::{SendError, SendErrorNoWait};
 use airlock::mpmc::*;
 use airlock::slot::Slot;
 
@@ -282,6 +283,300 @@ async fn t_11() {
     assert_eq!(counter.count(), 0);
 }
 
+#[test]
+fn t_12() {
+    let counter = Counter::new();
+
+    {
+        let tx_wakers = make_wakers::<1>();
+        let rx_wakers = make_wakers::<1>();
+        let buffer = make_buffer::<3>();
+        let link = Link::<Value, _, _, _>::with_overflow(
+            &buffer,
+            &tx_wakers,
+            &rx_wakers,
+            Overflow::DropNewest,
+        );
+
+        let mut tx = Tx::new(&link);
+        let mut rx = Rx::new(&link);
+
+        tx.send_nowait(counter.add(1)).expect("tx.send-nowait");
+        tx.send_nowait(counter.add(2)).expect("tx.send-nowait");
+        let rejected = tx.send_nowait(counter.add(3)).expect_err("tx.send-nowait");
+        assert!(rejected.is_rejected());
+        assert_eq!(rx.rejected(), 1);
+
+        assert_eq!(rx.recv_nowait().expect("rx.recv-nowait").unwrap(), 1);
+        tx.send_nowait(counter.add(4)).expect("tx.send-nowait");
+        assert!(tx.send_nowait(counter.add(5)).expect_err("tx.send-nowait").is_rejected());
+        assert_eq!(rx.rejected(), 2);
+
+        assert_eq!(rx.recv_nowait().expect("rx.recv-nowait").unwrap(), 2);
+        assert_eq!(rx.recv_nowait().expect("rx.recv-nowait").unwrap(), 4);
+        assert!(rx.recv_nowait().expect_err("rx.recv-nowait").is_empty());
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[tokio::test]
+async fn t_13() {
+    let counter = Counter::new();
+
+    {
+        let tx_wakers = make_wakers::<1>();
+        let rx_wakers = make_wakers::<1>();
+        let buffer = make_buffer::<3>();
+        let link = Link::<Value, _, _, _>::with_overflow(
+            &buffer,
+            &tx_wakers,
+            &rx_wakers,
+            Overflow::DropOldest,
+        );
+
+        let mut tx = Tx::new(&link);
+        let mut rx = Rx::new(&link);
+
+        tx.send_nowait(counter.add(1)).expect("tx.send-nowait");
+        tx.send_nowait(counter.add(2)).expect("tx.send-nowait");
+        match tx.send_nowait(counter.add(3)) {
+            Err(SendErrorNoWait::Evicted(evicted)) => assert_eq!(evicted.unwrap(), 1),
+            unexpected => panic!("unexpected: {:?}", unexpected),
+        }
+        match tx.send(counter.add(4)).await {
+            Err(SendError::Evicted(evicted)) => assert_eq!(evicted.unwrap(), 2),
+            unexpected => panic!("unexpected: {:?}", unexpected),
+        }
+        assert_eq!(rx.rejected(), 0);
+        assert_eq!(rx.evicted(), 2);
+
+        assert_eq!(rx.recv().await.expect("rx.recv").unwrap(), 3);
+        tx.send(counter.add(5)).await.expect("tx.send");
+        assert_eq!(rx.recv().await.expect("rx.recv").unwrap(), 4);
+        assert_eq!(rx.recv().await.expect("rx.recv").unwrap(), 5);
+
+        tx.send_nowait(counter.add(6)).expect("tx.send-nowait");
+        tx.send_nowait(counter.add(7)).expect("tx.send-nowait");
+        tx.close();
+        assert!(tx.send_nowait(counter.add(8)).expect_err("tx.send-nowait").is_closed());
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[tokio::test]
+async fn t_14() {
+    let counter = Counter::new();
+
+    {
+        let tx_wakers = make_wakers::<WAKERS_COUNT>();
+        let rx_wakers = make_wakers::<WAKERS_COUNT>();
+        let buffer = make_buffer::<BUFFER_SIZE>();
+        let link = Link::<Value, _, _, _, &str>::new(&buffer, &tx_wakers, &rx_wakers);
+
+        let mut tx_1 = Tx::new(&link);
+        let mut tx_2 = Tx::new(&link);
+        let mut rx_1 = Rx::new(&link);
+        let mut rx_2 = Rx::new(&link);
+
+        tx_1.send_nowait(counter.add(1)).expect("tx.send-nowait");
+        tx_1.close_with("tx-1");
+        tx_2.close_with("tx-2");
+
+        match tx_2.send(counter.add(2)).await {
+            Err(SendError::ClosedWith(rejected, reason)) => {
+                assert_eq!(rejected.unwrap(), 2);
+                assert_eq!(reason, "tx-1");
+            },
+            unexpected => panic!("unexpected: {:?}", unexpected),
+        }
+        assert_eq!(rx_1.recv().await.expect("rx.recv").unwrap(), 1);
+        assert_eq!(rx_1.recv().await.expect_err("rx.recv").reason(), Some(&"tx-1"));
+        assert_eq!(rx_2.recv_nowait().expect_err("rx.recv-nowait").reason(), Some(&"tx-1"));
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[tokio::test]
+async fn t_15() {
+    let counter = Counter::new();
+
+    {
+        let tx_wakers = make_wakers::<WAKERS_COUNT>();
+        let rx_wakers = make_wakers::<WAKERS_COUNT>();
+        let buffer = make_buffer::<BUFFER_SIZE>();
+        let link = Link::<Value, _, _, _>::new(&buffer, &tx_wakers, &rx_wakers);
+
+        let mut tx_1 = Tx::new(&link);
+        let mut tx_2 = Tx::new(&link);
+        let mut rx = Rx::new(&link);
+
+        for i in 0..BUFFER_SIZE / 2 {
+            tx_1.send_nowait(counter.add(i)).expect("tx.send-nowait");
+        }
+        tx_2.abort();
+        assert_eq!(counter.count(), 0);
+
+        assert!(rx.recv().await.expect_err("rx.recv").is_closed());
+        assert!(tx_1.send(counter.add(0)).await.expect_err("tx.send").is_closed());
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[test]
+fn t_16() {
+    let counter = Counter::new();
+
+    {
+        let tx_wakers = make_wakers::<WAKERS_COUNT>();
+        let rx_wakers = make_wakers::<WAKERS_COUNT>();
+        let buffer = make_buffer::<BUFFER_SIZE>();
+        let link = Link::<Value, _, _, _>::new(&buffer, &tx_wakers, &rx_wakers);
+
+        let mut tx = Tx::new(&link);
+        let mut rx = Rx::new(&link);
+
+        for i in 0..5 {
+            tx.send_nowait(counter.add(i)).expect("tx.send-nowait");
+        }
+        assert_eq!(tx.reclaim().count(), 0);
+
+        assert_eq!(rx.recv_nowait().expect("rx.recv-nowait").unwrap(), 0);
+        rx.close_for_reclaim();
+        assert!(rx.recv_nowait().expect_err("rx.recv-nowait").is_closed());
+
+        assert_eq!(tx.reclaim().map(Counted::unwrap).collect::<Vec<_>>(), [1, 2, 3, 4]);
+        assert_eq!(tx.reclaim().count(), 0);
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[test]
+fn t_17() {
+    let counter = Counter::new();
+
+    let tx_wakers = make_wakers::<WAKERS_COUNT>();
+    let rx_wakers = make_wakers::<WAKERS_COUNT>();
+    let buffer = make_buffer::<BUFFER_SIZE>();
+    let received = Link::<Value, _, _, _>::scoped(&buffer, &tx_wakers, &rx_wakers, |tx, mut rx| {
+        let mut tx_2 = tx.try_clone().expect("tx.try-clone");
+        for i in 0..5 {
+            tx_2.send_nowait(counter.add(i)).expect("tx.send-nowait");
+        }
+        std::mem::forget(tx_2);
+        rx.recv_nowait().expect("rx.recv-nowait").unwrap()
+    });
+    assert_eq!(received, 0);
+    assert_eq!(counter.count(), 0);
+    assert!(tx_wakers
+        .iter()
+        .chain(&rx_wakers)
+        .all(|(taken, _)| !taken.load(Ordering::SeqCst)));
+}
+
+#[test]
+fn t_18() {
+    let counter = Counter::new();
+
+    {
+        let tx_wakers = make_wakers::<WAKERS_COUNT>();
+        let rx_wakers = make_wakers::<WAKERS_COUNT>();
+        let buffer = make_buffer::<BUFFER_SIZE>();
+        let link = Link::<Value, _, _, _>::new(&buffer, &tx_wakers, &rx_wakers);
+
+        let mut tx_1 = Tx::new(&link);
+        let mut tx_2 = tx_1.try_clone().expect("tx.try-clone");
+        let mut rx = Rx::new(&link);
+
+        tx_1.send_nowait(counter.add(1)).expect("tx.send-nowait");
+        tx_2.send_nowait(counter.add(2)).expect("tx.send-nowait");
+
+        drop(tx_1);
+        assert_eq!(rx.recv_nowait().expect("rx.recv-nowait").unwrap(), 1);
+        assert_eq!(rx.recv_nowait().expect("rx.recv-nowait").unwrap(), 2);
+        assert!(!rx.recv_nowait().expect_err("rx.recv-nowait").is_closed());
+
+        drop(tx_2);
+        assert!(rx.recv_nowait().expect_err("rx.recv-nowait").is_closed());
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[test]
+fn t_19() {
+    let counter = Counter::new();
+
+    {
+        let tx_wakers = make_wakers::<2>();
+        let rx_wakers = make_wakers::<WAKERS_COUNT>();
+        let buffer = make_buffer::<BUFFER_SIZE>();
+        let link = Link::<Value, _, _, _>::new(&buffer, &tx_wakers, &rx_wakers);
+
+        let tx = Tx::new(&link);
+        let mut rx = Rx::new(&link);
+
+        let weak = tx.downgrade();
+        let mut upgraded = weak.upgrade().expect("weak.upgrade");
+        assert!(weak.clone().upgrade().is_none());
+
+        upgraded.send_nowait(counter.add(1)).expect("tx.send-nowait");
+        drop(upgraded);
+        assert!(weak.upgrade().is_some());
+
+        drop(tx);
+        assert!(weak.upgrade().is_none());
+        assert_eq!(rx.recv_nowait().expect("rx.recv-nowait").unwrap(), 1);
+        assert!(rx.recv_nowait().expect_err("rx.recv-nowait").is_closed());
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[test]
+fn t_20() {
+    let tx_wakers = make_wakers::<WAKERS_COUNT>();
+    let rx_wakers = make_wakers::<WAKERS_COUNT>();
+    let buffer = make_buffer::<BUFFER_SIZE>();
+    let link = Link::<Value, _, _, _>::new(&buffer, &tx_wakers, &rx_wakers);
+
+    let mut tx = Tx::new(&link);
+    let weak = tx.downgrade();
+
+    tx.close();
+    assert!(weak.upgrade().is_none());
+}
+
+#[test]
+fn t_21() {
+    let counter = Counter::new();
+
+    {
+        let tx_wakers = make_wakers::<WAKERS_COUNT>();
+        let rx_wakers = make_wakers::<WAKERS_COUNT>();
+        let buffer = make_buffer::<BUFFER_SIZE>();
+        let link = Link::<Value, _, _, _>::new(&buffer, &tx_wakers, &rx_wakers);
+
+        let mut tx = Tx::new(&link);
+        let mut rx_1 = Rx::new(&link);
+        let mut rx_2 = rx_1.try_clone().expect("rx.try-clone");
+
+        for i in 0..5 {
+            tx.send_nowait(counter.add(i)).expect("tx.send-nowait");
+        }
+        tx.close();
+        assert_eq!(tx.reclaim().count(), 0);
+
+        assert_eq!(rx_1.recv_nowait().expect("rx.recv-nowait").unwrap(), 0);
+        assert_eq!(rx_2.recv_nowait().expect("rx.recv-nowait").unwrap(), 1);
+
+        drop(rx_1);
+        assert_eq!(tx.reclaim().count(), 0);
+        assert_eq!(rx_2.recv_nowait().expect("rx.recv-nowait").unwrap(), 2);
+
+        drop(rx_2);
+        assert_eq!(tx.reclaim().map(Counted::unwrap).collect::<Vec<_>>(), [3, 4]);
+    }
+    assert_eq!(counter.count(), 0);
+}
+
 fn make_buffer<const SIZE: usize>() -> [Slot<Value>; SIZE] {
     core::array::from_fn(|_| Default::default())
 }
@@ -0,0 +1,238 @@
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+use airlock::atomic_waker::AtomicWaker;
+use airlock::error::{RecvError, SendErrorNoWait};
+use airlock::mpsc::*;
+use airlock::slot::Slot;
+
+mod utils;
+use futures::future;
+use utils::{Counted, Counter};
+
+type Value = Counted<usize>;
+
+const BUFFER_SIZE: usize = 64;
+const WAKERS_COUNT: usize = 8;
+
+#[test]
+fn t_00() {
+    let tx_wakers = make_wakers::<WAKERS_COUNT>();
+    let buffer = make_buffer::<BUFFER_SIZE>();
+    let _link = Link::<Value, _, _>::new(&buffer, &tx_wakers);
+}
+
+#[test]
+fn t_01() {
+    let tx_wakers = make_wakers::<WAKERS_COUNT>();
+    let buffer = make_buffer::<BUFFER_SIZE>();
+    let link = Link::<Value, _, _>::new(&buffer, &tx_wakers);
+
+    let _tx_1 = Tx::new(&link);
+    let _tx_2 = Tx::new(&link);
+
+    let _rx = Rx::new(&link);
+}
+
+#[test]
+#[should_panic]
+fn t_02() {
+    let tx_wakers = make_wakers::<WAKERS_COUNT>();
+    let buffer = make_buffer::<BUFFER_SIZE>();
+    let link = Link::<Value, _, _>::new(&buffer, &tx_wakers);
+
+    let _rx_1 = Rx::new(&link);
+    let _rx_2 = Rx::new(&link);
+}
+
+#[test]
+fn t_03() {
+    let counter = Counter::new();
+
+    {
+        let tx_wakers = make_wakers::<2>();
+        let buffer = make_buffer::<4>();
+        let link = Link::<Value, _, _>::new(&buffer, &tx_wakers);
+
+        let mut tx_1 = Tx::new(&link);
+        let mut tx_2 = tx_1.try_clone().expect("tx-1.try-clone");
+        assert!(tx_1.try_clone().is_err());
+        let mut rx = Rx::new(&link);
+
+        for round in 0..3 {
+            tx_1.send_nowait(counter.add(round * 3)).expect("tx-1.send-nowait");
+            tx_2.send_nowait(counter.add(round * 3 + 1)).expect("tx-2.send-nowait");
+            tx_1.send_nowait(counter.add(round * 3 + 2)).expect("tx-1.send-nowait");
+            assert!(tx_2.send_nowait(counter.add(0)).expect_err("tx-2.send-nowait").is_full());
+
+            for i in 0..3 {
+                assert_eq!(rx.recv_nowait().expect("rx.recv-nowait").unwrap(), round * 3 + i);
+            }
+            assert!(rx.recv_nowait().expect_err("rx.recv-nowait").is_empty());
+        }
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[test]
+fn t_04() {
+    let counter = Counter::new();
+
+    {
+        let tx_wakers = make_wakers::<WAKERS_COUNT>();
+        let buffer = make_buffer::<BUFFER_SIZE>();
+        let link = Link::<Value, _, _>::new(&buffer, &tx_wakers);
+
+        let mut tx_1 = Tx::new(&link);
+        let tx_2 = Tx::new(&link);
+        let mut rx = Rx::new(&link);
+
+        tx_1.send_nowait(counter.add(1)).expect("tx.send-nowait");
+        drop(tx_1);
+        assert_eq!(rx.recv_nowait().expect("rx.recv-nowait").unwrap(), 1);
+        assert!(rx.recv_nowait().expect_err("rx.recv-nowait").is_empty());
+
+        drop(tx_2);
+        assert!(rx.recv_nowait().expect_err("rx.recv-nowait").is_closed());
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[test]
+fn t_05() {
+    let counter = Counter::new();
+
+    {
+        let tx_wakers = make_wakers::<WAKERS_COUNT>();
+        let buffer = make_buffer::<BUFFER_SIZE>();
+        let link = Link::<Value, _, _>::new(&buffer, &tx_wakers);
+
+        let mut tx = Tx::new(&link);
+        let rx = Rx::new(&link);
+
+        tx.send_nowait(counter.add(1)).expect("tx.send-nowait");
+        drop(rx);
+        assert!(tx.send_nowait(counter.add(2)).expect_err("tx.send-nowait").is_closed());
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[tokio::test]
+async fn t_06() {
+    let counter = Counter::new();
+
+    {
+        let tx_wakers = make_wakers::<WAKERS_COUNT>();
+        let buffer = make_buffer::<BUFFER_SIZE>();
+        let link = Link::<Value, _, _, &str>::new(&buffer, &tx_wakers);
+
+        let mut tx = Tx::new(&link);
+        let mut rx = Rx::new(&link);
+
+        tx.send(counter.add(1)).await.expect("tx.send");
+        tx.close_with("done");
+
+        assert!(matches!(
+            tx.send_nowait(counter.add(2)),
+            Err(SendErrorNoWait::ClosedWith(_, "done"))
+        ));
+        assert_eq!(rx.recv().await.expect("rx.recv").unwrap(), 1);
+        assert!(matches!(rx.recv().await, Err(RecvError::ClosedWith("done"))));
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[tokio::test]
+async fn t_07() {
+    let counter = Counter::new();
+
+    {
+        let tx_wakers = make_wakers::<2>();
+        let buffer = make_buffer::<3>();
+        let link = Link::<Value, _, _>::new(&buffer, &tx_wakers);
+
+        let mut tx_1 = Tx::new(&link);
+        let mut tx_2 = Tx::new(&link);
+        let mut rx = Rx::new(&link);
+
+        let producers = future::join(
+            async {
+                for i in 0..100 {
+                    tx_1.send(counter.add(i)).await.expect("tx-1.send");
+                }
+            },
+            async {
+                for i in 100..200 {
+                    tx_2.send(counter.add(i)).await.expect("tx-2.send");
+                }
+            },
+        );
+        let consumer = async {
+            let mut received = vec![];
+            for _ in 0..200 {
+                received.push(rx.recv().await.expect("rx.recv").unwrap());
+            }
+            received
+        };
+        let (_, mut received) = future::join(producers, consumer).await;
+
+        let (first, second): (Vec<usize>, Vec<usize>) = received.iter().partition(|v| **v < 100);
+        assert_eq!(first, (0..100).collect::<Vec<_>>());
+        assert_eq!(second, (100..200).collect::<Vec<_>>());
+
+        received.sort();
+        assert_eq!(received, (0..200).collect::<Vec<_>>());
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[tokio::test]
+async fn t_08() {
+    let counter = Counter::new();
+
+    const ITERATIONS: usize = 125_000;
+
+    {
+        let tx_wakers = make_wakers::<WAKERS_COUNT>();
+        let buffer = make_buffer::<BUFFER_SIZE>();
+        let link = Arc::new(Link::<Value, _, _>::new(buffer, tx_wakers));
+
+        let producers = (0..WAKERS_COUNT)
+            .map(|_| {
+                let counter = counter.clone();
+                let link = Arc::clone(&link);
+                async move {
+                    let mut tx = Tx::new(Arc::clone(&link));
+                    for i in 0..ITERATIONS {
+                        tx.send(counter.add(i)).await.expect("tx.send");
+                    }
+                }
+            })
+            .map(tokio::spawn);
+        let consumer = {
+            let link = Arc::clone(&link);
+            async move {
+                let mut rx = Rx::new(Arc::clone(&link));
+                for _i in 0..ITERATIONS * WAKERS_COUNT {
+                    rx.recv().await.expect("rx.recv");
+                }
+            }
+        };
+
+        let producers = future::try_join_all(producers);
+        let consumer = tokio::spawn(consumer);
+
+        let (producers, consumer) = future::join(producers, consumer).await;
+        producers.expect("producers");
+        consumer.expect("consumer");
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+fn make_buffer<const SIZE: usize>() -> [Slot<Value>; SIZE] {
+    core::array::from_fn(|_| Default::default())
+}
+
+fn make_wakers<const SIZE: usize>() -> [(AtomicBool, AtomicWaker); SIZE] {
+    core::array::from_fn(|_| Default::default())
+}
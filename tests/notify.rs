@@ -0,0 +1,152 @@
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use airlock::atomic_waker::AtomicWaker;
+use airlock::error::LimitReached;
+use airlock::notify::*;
+
+use futures::{future, task};
+
+const WAKERS_COUNT: usize = 4;
+
+#[tokio::test]
+async fn t_00() {
+    let wakers = make_wakers::<WAKERS_COUNT>();
+    let notify = Notify::new(&wakers);
+
+    notify.notify_one();
+    notify.notify_one();
+    notify.notified().await.expect("notify.notified");
+
+    let timeout = tokio::time::timeout(Duration::from_millis(10), notify.notified()).await;
+    assert!(timeout.is_err(), "a single permit is left");
+}
+
+#[tokio::test]
+async fn t_01() {
+    let wakers = make_wakers::<WAKERS_COUNT>();
+    let notify = Notify::new(&wakers);
+
+    let waiting = notify.notified();
+    let notifying = async {
+        tokio::task::yield_now().await;
+        notify.notify_one();
+    };
+    let (notified, ()) = future::join(waiting, notifying).await;
+    notified.expect("notify.notified");
+}
+
+#[tokio::test]
+async fn t_02() {
+    let wakers = make_wakers::<8>();
+    let notify = Notify::new(&wakers);
+
+    let waiting = future::join_all((0..8).map(|_| notify.notified()));
+    notify.notify_all();
+    for notified in waiting.await {
+        notified.expect("notify.notified");
+    }
+
+    let timeout = tokio::time::timeout(Duration::from_millis(10), notify.notified()).await;
+    assert!(timeout.is_err(), "notify-all leaves no permit");
+}
+
+#[tokio::test]
+async fn t_03() {
+    let wakers = make_wakers::<WAKERS_COUNT>();
+    let notify = Notify::new(&wakers);
+
+    let first = notify.notified();
+    let second = notify.notified();
+    let third = notify.notified();
+
+    notify.notify_one();
+    let (notified, _, rest) = future::select_all([first, second, third].map(Box::pin)).await;
+    notified.expect("notify.notified");
+
+    let rest = future::join_all(rest);
+    let timeout = tokio::time::timeout(Duration::from_millis(10), rest).await;
+    assert!(timeout.is_err(), "notify-one completes a single waiter");
+}
+
+#[tokio::test]
+async fn t_04() {
+    let wakers = make_wakers::<WAKERS_COUNT>();
+    let notify = Notify::new(&wakers);
+
+    let cancelled = tokio::time::timeout(Duration::from_millis(10), notify.notified()).await;
+    assert!(cancelled.is_err());
+
+    // the cancelled waiter gave its waker back
+    notify.notify_one();
+    notify.notified().await.expect("notify.notified");
+}
+
+#[test]
+fn t_05() {
+    let wakers = make_wakers::<WAKERS_COUNT>();
+    let notify = Notify::new(&wakers);
+    let wakes = Arc::new(Wakes::default());
+    let waker = task::waker(wakes.clone());
+    let mut cx = Context::from_waker(&waker);
+
+    let mut waiting = [(); 3].map(|_| Box::pin(notify.notified()));
+    for notified in &mut waiting {
+        assert!(notified.as_mut().poll(&mut cx).is_pending());
+    }
+
+    notify.notify_one();
+    assert_eq!(wakes.0.load(Ordering::SeqCst), 1, "notify-one wakes a single waiter");
+}
+
+#[test]
+fn t_06() {
+    let wakers = make_wakers::<WAKERS_COUNT>();
+    let notify = Notify::new(&wakers);
+    let wakes = Arc::new(Wakes::default());
+    let waker = task::waker(wakes.clone());
+    let mut cx = Context::from_waker(&waker);
+
+    let mut first = Box::pin(notify.notified());
+    let mut second = Box::pin(notify.notified());
+    assert!(first.as_mut().poll(&mut cx).is_pending());
+    assert!(second.as_mut().poll(&mut cx).is_pending());
+
+    notify.notify_one();
+    assert_eq!(wakes.0.load(Ordering::SeqCst), 1);
+
+    // the woken waiter is dropped before it takes the permit: it goes to the other one
+    drop(first);
+    assert_eq!(wakes.0.load(Ordering::SeqCst), 2);
+    assert!(matches!(second.as_mut().poll(&mut cx), Poll::Ready(Ok(()))));
+}
+
+#[tokio::test]
+async fn t_07() {
+    let wakers = make_wakers::<1>();
+    let notify = Notify::new(&wakers);
+
+    let mut waiting = std::pin::pin!(notify.notified());
+    assert!(future::poll_immediate(&mut waiting).await.is_none());
+
+    assert_eq!(notify.notified().await.expect_err("notify.notified"), LimitReached);
+
+    notify.notify_one();
+    waiting.await.expect("notify.notified");
+}
+
+#[derive(Default)]
+struct Wakes(AtomicUsize);
+
+impl task::ArcWake for Wakes {
+    fn wake_by_ref(arc_self: &Arc<Self>) {
+        arc_self.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+fn make_wakers<const SIZE: usize>() -> [(AtomicBool, AtomicWaker); SIZE] {
+    core::array::from_fn(|_| Default::default())
+}
@@ -0,0 +1,166 @@
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use std::time::Duration;
+
+use airlock::atomic_waker::AtomicWaker;
+use airlock::error::LimitReached;
+use airlock::pool::*;
+use airlock::slot::Slot;
+
+mod utils;
+use futures::future;
+use utils::{Counted, Counter};
+
+type Value = Counted<usize>;
+
+const WAKERS_COUNT: usize = 4;
+
+#[test]
+fn t_00() {
+    let counter = Counter::new();
+
+    {
+        let buffer = make_buffer::<3>();
+        let wakers = make_wakers::<WAKERS_COUNT>();
+        let pool = Pool::new(&buffer, &wakers);
+
+        assert_eq!(pool.capacity(), 2);
+        assert!(pool.is_empty());
+        assert!(pool.try_get().is_none());
+
+        pool.put(counter.add(1)).expect("pool.put");
+        pool.put(counter.add(2)).expect("pool.put");
+        assert_eq!(pool.put(counter.add(3)).expect_err("pool.put"), counter.add(3));
+        assert_eq!(pool.len(), 2);
+
+        let first = pool.try_get().expect("pool.try-get");
+        let second = pool.try_get().expect("pool.try-get");
+        assert!(pool.try_get().is_none());
+        assert_eq!(*first, counter.add(1));
+        assert_eq!(*second, counter.add(2));
+
+        drop(first);
+        assert_eq!(*pool.try_get().expect("pool.try-get"), counter.add(1));
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[test]
+fn t_01() {
+    let counter = Counter::new();
+
+    {
+        let buffer = make_buffer::<2>();
+        let wakers = make_wakers::<WAKERS_COUNT>();
+        let pool = Pool::new(&buffer, &wakers);
+
+        pool.put(counter.add(1)).expect("pool.put");
+        let guard = pool.try_get().expect("pool.try-get");
+        assert!(pool.put(counter.add(2)).is_err());
+
+        assert_eq!(guard.detach().unwrap(), 1);
+        assert!(pool.is_empty());
+        pool.put(counter.add(3)).expect("pool.put");
+
+        let mut guard = pool.try_get().expect("pool.try-get");
+        *guard = counter.add(4);
+        drop(guard);
+        assert_eq!(*pool.try_get().expect("pool.try-get"), counter.add(4));
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[tokio::test]
+async fn t_02() {
+    let counter = Counter::new();
+
+    {
+        let buffer = make_buffer::<2>();
+        let wakers = make_wakers::<WAKERS_COUNT>();
+        let pool = Pool::new(&buffer, &wakers);
+        pool.put(counter.add(1)).expect("pool.put");
+
+        let guard = pool.get().await.expect("pool.get");
+        let timeout = tokio::time::timeout(Duration::from_millis(10), pool.get()).await;
+        assert!(timeout.is_err());
+
+        let waiting = async {
+            assert_eq!(*pool.get().await.expect("pool.get"), counter.add(1));
+        };
+        let releasing = async {
+            tokio::task::yield_now().await;
+            drop(guard);
+        };
+        future::join(waiting, releasing).await;
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[tokio::test]
+async fn t_03() {
+    const OBJECTS: usize = 3;
+    const TASKS: usize = OBJECTS + WAKERS_COUNT;
+
+    let counter = Counter::new();
+
+    {
+        let buffer = make_buffer::<{ OBJECTS + 1 }>();
+        let wakers = make_wakers::<WAKERS_COUNT>();
+        let pool = Arc::new(Pool::new(buffer, wakers));
+        for i in 0..OBJECTS {
+            pool.put(counter.add(i)).expect("pool.put");
+        }
+
+        let tasks = (0..TASKS)
+            .map(|_| {
+                let pool = Arc::clone(&pool);
+                async move {
+                    for _ in 0..100 {
+                        let guard = pool.get().await.expect("pool.get");
+                        tokio::task::yield_now().await;
+                        drop(guard);
+                    }
+                }
+            })
+            .map(tokio::spawn);
+        future::try_join_all(tasks).await.expect("tasks");
+
+        let mut idle = vec![];
+        while let Some(guard) = pool.try_get() {
+            idle.push(guard.detach().unwrap());
+        }
+        idle.sort();
+        assert_eq!(idle, (0..OBJECTS).collect::<Vec<_>>());
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[tokio::test]
+async fn t_04() {
+    let counter = Counter::new();
+
+    {
+        let buffer = make_buffer::<2>();
+        let wakers = make_wakers::<1>();
+        let pool = Pool::new(&buffer, &wakers);
+        pool.put(counter.add(1)).expect("pool.put");
+
+        let guard = pool.get().await.expect("pool.get");
+        let mut waiting = std::pin::pin!(pool.get());
+        assert!(future::poll_immediate(&mut waiting).await.is_none());
+
+        assert_eq!(pool.get().await.err(), Some(LimitReached));
+
+        drop(guard);
+        assert_eq!(*waiting.await.expect("pool.get"), counter.add(1));
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+fn make_buffer<const SIZE: usize>() -> [Slot<Value>; SIZE] {
+    core::array::from_fn(|_| Default::default())
+}
+
+fn make_wakers<const SIZE: usize>() -> [(AtomicBool, AtomicWaker); SIZE] {
+    core::array::from_fn(|_| Default::default())
+}
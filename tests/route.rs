@@ -0,0 +1,287 @@
+use std::borrow::Borrow;
+use std::collections::hash_map::RandomState;
+use std::sync::atomic::AtomicBool;
+
+use airlock::atomic_waker::AtomicWaker;
+use airlock::route::*;
+use airlock::slot::Slot;
+use airlock::{mpsc, spsc};
+
+mod utils;
+use futures::future;
+use utils::{Counted, Counter};
+
+type Value = Counted<usize>;
+
+const BUFFER_SIZE: usize = 16;
+
+#[tokio::test]
+async fn t_00() {
+    let counter = Counter::new();
+    {
+        let input = spsc::direct::Link::<Value>::new();
+        let buffers: [[Slot<Value>; BUFFER_SIZE]; 3] = core::array::from_fn(|_| make_buffer());
+        let outputs = buffers.each_ref().map(spsc::buffered::Link::<Value, _>::new);
+
+        let mut tx = spsc::direct::Tx::new(&input);
+        let rx = spsc::direct::Rx::new(&input);
+        let txs = outputs.each_ref().map(spsc::buffered::Tx::new);
+        let mut rxs = outputs.each_ref().map(spsc::buffered::Rx::new);
+
+        let producer = async {
+            for i in 0..9 {
+                tx.send(counter.add(i)).await.expect("tx.send");
+            }
+            drop(tx);
+        };
+        let (_, undelivered) = future::join(producer, fan_out(rx, txs, RoundRobin::new())).await;
+        assert!(undelivered.is_none());
+
+        for (idx, rx) in rxs.iter_mut().enumerate() {
+            for i in 0..3 {
+                assert_eq!(rx.recv_nowait().expect("rx.recv-nowait"), counter.add(i * 3 + idx));
+            }
+            assert!(rx.recv_nowait().expect_err("rx.recv-nowait").is_closed());
+        }
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[tokio::test]
+async fn t_01() {
+    let counter = Counter::new();
+    {
+        let input_buffer = make_buffer::<BUFFER_SIZE>();
+        let input = spsc::buffered::Link::<Value, _>::new(&input_buffer);
+        let buffers: [[Slot<Value>; BUFFER_SIZE]; 2] = core::array::from_fn(|_| make_buffer());
+        let outputs = buffers.each_ref().map(spsc::buffered::Link::<Value, _>::new);
+
+        let mut tx = spsc::buffered::Tx::new(&input);
+        let rx = spsc::buffered::Rx::new(&input);
+        let txs = outputs.each_ref().map(spsc::buffered::Tx::new);
+        let mut rxs = outputs.each_ref().map(spsc::buffered::Rx::new);
+
+        for i in 0..12 {
+            tx.send_nowait(counter.add(i)).expect("tx.send-nowait");
+        }
+        drop(tx);
+
+        let strategy = KeyHash::new(|v: &Value| Borrow::<usize>::borrow(v) % 3, RandomState::new());
+        assert!(fan_out(rx, txs, strategy).await.is_none());
+
+        let mut received = vec![];
+        let mut keys = [None, None, None];
+        for (idx, rx) in rxs.iter_mut().enumerate() {
+            while let Ok(value) = rx.recv_nowait() {
+                let value = value.unwrap();
+                assert_eq!(*keys[value % 3].get_or_insert(idx), idx);
+                received.push(value);
+            }
+        }
+        received.sort();
+        assert_eq!(received, (0..12).collect::<Vec<_>>());
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[tokio::test]
+async fn t_02() {
+    let counter = Counter::new();
+    {
+        let input_buffer = make_buffer::<BUFFER_SIZE>();
+        let input = spsc::buffered::Link::<Value, _>::new(&input_buffer);
+        let outputs: [spsc::direct::Link<Value>; 2] = Default::default();
+
+        let mut tx = spsc::buffered::Tx::new(&input);
+        let rx = spsc::buffered::Rx::new(&input);
+        let txs = outputs.each_ref().map(spsc::direct::Tx::new);
+        let [rx_0, rx_1] = outputs.each_ref().map(spsc::direct::Rx::new);
+        drop(rx_1);
+
+        for i in 0..3 {
+            tx.send_nowait(counter.add(i)).expect("tx.send-nowait");
+        }
+
+        let undelivered = fan_out(rx, txs, RoundRobin::new()).await;
+        assert_eq!(undelivered.expect("undelivered"), counter.add(1));
+        assert!(tx.send_nowait(counter.add(3)).expect_err("tx.send-nowait").is_closed());
+        drop(rx_0);
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[tokio::test]
+async fn t_03() {
+    let counter = Counter::new();
+    {
+        let inputs: [spsc::direct::Link<Value>; 3] = Default::default();
+        let tx_wakers = make_wakers::<1>();
+        let output_buffer = make_buffer::<BUFFER_SIZE>();
+        let output = mpsc::Link::<Value, _, _>::new(&output_buffer, &tx_wakers);
+
+        let mut txs = inputs.each_ref().map(spsc::direct::Tx::new);
+        let rxs = inputs.each_ref().map(spsc::direct::Rx::new);
+        let tx = mpsc::Tx::new(&output);
+        let mut rx = mpsc::Rx::new(&output);
+
+        let producers = async {
+            for i in 0..30 {
+                txs[i % 3].send(counter.add(i)).await.expect("tx.send");
+            }
+            drop(txs);
+        };
+        let consumer = async {
+            let mut received = vec![];
+            while let Ok(value) = rx.recv().await {
+                received.push(value.unwrap());
+            }
+            received.sort();
+            received
+        };
+        let (_, undelivered, received) = future::join3(producers, fan_in(rxs, tx), consumer).await;
+        assert!(undelivered.is_none());
+        assert_eq!(received, (0..30).collect::<Vec<_>>());
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[tokio::test]
+async fn t_04() {
+    let counter = Counter::new();
+    {
+        let inputs: [spsc::direct::Link<Value>; 2] = Default::default();
+        let output = spsc::direct::Link::<Value>::new();
+
+        let mut txs = inputs.each_ref().map(spsc::direct::Tx::new);
+        let rxs = inputs.each_ref().map(spsc::direct::Rx::new);
+        let tx = spsc::direct::Tx::new(&output);
+        let rx = spsc::direct::Rx::new(&output);
+        drop(rx);
+
+        txs[0].send_nowait(counter.add(1)).expect("tx.send-nowait");
+        assert_eq!(fan_in(rxs, tx).await.expect("undelivered"), counter.add(1));
+
+        for tx in txs.iter_mut() {
+            assert!(tx.send_nowait(counter.add(2)).expect_err("tx.send-nowait").is_closed());
+        }
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[tokio::test]
+async fn t_05() {
+    const ITERATIONS: usize = 1_000;
+
+    let counter = Counter::new();
+    {
+        let source = spsc::direct::Link::<Value>::new();
+        let workers: [spsc::direct::Link<Value>; 4] = Default::default();
+        let sink_buffer = make_buffer::<BUFFER_SIZE>();
+        let sink = spsc::buffered::Link::<Value, _>::new(&sink_buffer);
+
+        let mut tx = spsc::direct::Tx::new(&source);
+        let mut rx = spsc::buffered::Rx::new(&sink);
+
+        let producer = async {
+            for i in 0..ITERATIONS {
+                tx.send(counter.add(i)).await.expect("tx.send");
+            }
+            drop(tx);
+        };
+        let router_out = fan_out(
+            spsc::direct::Rx::new(&source),
+            workers.each_ref().map(spsc::direct::Tx::new),
+            RoundRobin::new(),
+        );
+        let router_in =
+            fan_in(workers.each_ref().map(spsc::direct::Rx::new), spsc::buffered::Tx::new(&sink));
+        let consumer = async {
+            let mut sum = 0;
+            while let Ok(value) = rx.recv().await {
+                sum += value.unwrap();
+            }
+            sum
+        };
+
+        let (_, out, in_, sum) = future::join4(producer, router_out, router_in, consumer).await;
+        assert!(out.is_none());
+        assert!(in_.is_none());
+        assert_eq!(sum, (0..ITERATIONS).sum::<usize>());
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[tokio::test]
+async fn t_06() {
+    let counter = Counter::new();
+    {
+        let inputs: [spsc::direct::Link<Value>; 2] = Default::default();
+        let output = spsc::direct::Link::<Value>::new();
+
+        let mut txs = inputs.each_ref().map(spsc::direct::Tx::new);
+        let rxs = inputs.each_ref().map(spsc::direct::Rx::new);
+        let tx = spsc::direct::Tx::new(&output);
+        let rx = spsc::direct::Rx::new(&output);
+
+        // the output is closed while the router waits on the inputs
+        let closing = async {
+            tokio::task::yield_now().await;
+            drop(rx);
+        };
+        let (undelivered, ()) = future::join(fan_in(rxs, tx), closing).await;
+        assert!(undelivered.is_none());
+
+        for tx in txs.iter_mut() {
+            assert!(tx.send_nowait(counter.add(1)).expect_err("tx.send-nowait").is_closed());
+        }
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[tokio::test]
+async fn t_07() {
+    let counter = Counter::new();
+    {
+        let input = spsc::direct::Link::<Value>::new();
+        let outputs: [spsc::direct::Link<Value>; 2] = Default::default();
+
+        let mut tx = spsc::direct::Tx::new(&input);
+        let rx = spsc::direct::Rx::new(&input);
+        let txs = outputs.each_ref().map(spsc::direct::Tx::new);
+        let [rx_1, _rx_2] = outputs.each_ref().map(spsc::direct::Rx::new);
+
+        let closing = async {
+            tokio::task::yield_now().await;
+            drop(rx_1);
+        };
+        let (undelivered, ()) = future::join(fan_out(rx, txs, RoundRobin::new()), closing).await;
+        assert!(undelivered.is_none());
+
+        assert!(tx.send_nowait(counter.add(1)).expect_err("tx.send-nowait").is_closed());
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[tokio::test]
+async fn t_08() {
+    let counter = Counter::new();
+    {
+        let input = spsc::direct::Link::<Value>::new();
+
+        let mut tx = spsc::direct::Tx::new(&input);
+        let rx = spsc::direct::Rx::new(&input);
+        let txs: [spsc::direct::Tx<Value, &spsc::direct::Link<Value>>; 0] = [];
+
+        assert!(fan_out(rx, txs, RoundRobin::new()).await.is_none());
+        assert!(tx.send_nowait(counter.add(1)).expect_err("tx.send-nowait").is_closed());
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+fn make_buffer<const SIZE: usize>() -> [Slot<Value>; SIZE] {
+    core::array::from_fn(|_| Default::default())
+}
+
+fn make_wakers<const SIZE: usize>() -> [(AtomicBool, AtomicWaker); SIZE] {
+    core::array::from_fn(|_| Default::default())
+}
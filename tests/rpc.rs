@@ -0,0 +1,240 @@
+use std::sync::atomic::AtomicBool;
+
+use airlock::atomic_waker::AtomicWaker;
+use airlock::error::RpcError;
+use airlock::rpc::*;
+use airlock::slot::Slot;
+
+mod utils;
+use futures::future;
+use utils::{Counted, Counter};
+
+type Req = Counted<usize>;
+type Resp = Counted<usize>;
+
+const BUFFER_SIZE: usize = 4;
+const WAKERS_COUNT: usize = 4;
+
+#[tokio::test]
+async fn t_00() {
+    let counter = Counter::new();
+
+    {
+        let replies = make_replies::<2>();
+        let buffer = make_buffer::<BUFFER_SIZE>();
+        let (tx_wakers, rx_wakers) = (make_wakers::<WAKERS_COUNT>(), make_wakers::<WAKERS_COUNT>());
+        let requests = RequestLink::new(&buffer, &tx_wakers, &rx_wakers);
+
+        let mut client = Client::new(&requests, &replies);
+        let mut server = Server::new(&requests);
+
+        let calling = async {
+            for i in 0..10 {
+                let resp = client.call(counter.add(i)).await.expect("client.call");
+                assert_eq!(resp.unwrap(), i * 2);
+            }
+            drop(client);
+        };
+        let serving = async {
+            while let Some((req, responder)) = server.next().await {
+                responder.respond(counter.add(req.unwrap() * 2)).expect("responder.respond");
+            }
+        };
+        future::join(calling, serving).await;
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[tokio::test]
+async fn t_01() {
+    let counter = Counter::new();
+
+    {
+        let replies = make_replies::<2>();
+        let buffer = make_buffer::<BUFFER_SIZE>();
+        let (tx_wakers, rx_wakers) = (make_wakers::<WAKERS_COUNT>(), make_wakers::<WAKERS_COUNT>());
+        let requests = RequestLink::new(&buffer, &tx_wakers, &rx_wakers);
+
+        let mut client = Client::new(&requests, &replies);
+        let mut server = Server::new(&requests);
+
+        let calling = client.call(counter.add(1));
+        let serving = async {
+            let (_req, responder) = server.next().await.expect("server.next");
+            drop(responder);
+        };
+        let (result, ()) = future::join(calling, serving).await;
+        assert_eq!(result, Err(RpcError::NoReply));
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[tokio::test]
+async fn t_02() {
+    let counter = Counter::new();
+
+    {
+        let replies = make_replies::<2>();
+        let buffer = make_buffer::<BUFFER_SIZE>();
+        let (tx_wakers, rx_wakers) = (make_wakers::<WAKERS_COUNT>(), make_wakers::<WAKERS_COUNT>());
+        let requests = RequestLink::new(&buffer, &tx_wakers, &rx_wakers);
+
+        let mut client = Client::new(&requests, &replies);
+        let mut server = Server::new(&requests);
+        server.close();
+        drop(server);
+
+        assert_eq!(client.call(counter.add(1)).await, Err(RpcError::Closed));
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[tokio::test]
+async fn t_03() {
+    let counter = Counter::new();
+
+    {
+        let replies = make_replies::<2>();
+        let buffer = make_buffer::<BUFFER_SIZE>();
+        let (tx_wakers, rx_wakers) = (make_wakers::<WAKERS_COUNT>(), make_wakers::<WAKERS_COUNT>());
+        let requests = RequestLink::new(&buffer, &tx_wakers, &rx_wakers);
+
+        let mut clients = [
+            Client::new(&requests, &replies),
+            Client::new(&requests, &replies),
+            Client::new(&requests, &replies),
+        ];
+        let mut server = Server::new(&requests);
+
+        // two reply links for three calls: the third one waits for a link to be let go
+        let [c_1, c_2, c_3] = &mut clients;
+        let calls = future::join3(
+            c_1.call(counter.add(1)),
+            c_2.call(counter.add(2)),
+            c_3.call(counter.add(3)),
+        );
+        let serving = async {
+            for _ in 0..3 {
+                let (req, responder) = server.next().await.expect("server.next");
+                let _ = responder.respond(req);
+            }
+        };
+        let ((r_1, r_2, r_3), ()) = future::join(calls, serving).await;
+
+        assert_eq!(r_1.expect("c-1.call").unwrap(), 1);
+        assert_eq!(r_2.expect("c-2.call").unwrap(), 2);
+        assert_eq!(r_3.expect("c-3.call").unwrap(), 3);
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[tokio::test]
+async fn t_04() {
+    let counter = Counter::new();
+
+    {
+        let replies = make_replies::<1>();
+        let buffer = make_buffer::<BUFFER_SIZE>();
+        let (tx_wakers, rx_wakers) = (make_wakers::<WAKERS_COUNT>(), make_wakers::<WAKERS_COUNT>());
+        let requests = RequestLink::new(&buffer, &tx_wakers, &rx_wakers);
+
+        let mut client = Client::new(&requests, &replies);
+        let mut server = Server::new(&requests);
+
+        // the call is abandoned: the response is handed back to the server
+        let calling = client.call(counter.add(1));
+        drop(tokio::time::timeout(std::time::Duration::from_millis(1), calling).await);
+        let (req, responder) = server.next().await.expect("server.next");
+        assert_eq!(responder.respond(req).expect_err("responder.respond").unwrap(), 1);
+
+        // the reply link is free again
+        let calling = client.call(counter.add(2));
+        let serving = async {
+            let (req, responder) = server.next().await.expect("server.next");
+            responder.respond(req).expect("responder.respond");
+        };
+        let (result, ()) = future::join(calling, serving).await;
+        assert_eq!(result.expect("client.call").unwrap(), 2);
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[tokio::test]
+async fn t_05() {
+    let counter = Counter::new();
+
+    {
+        let replies = make_replies::<2>();
+        let buffer = make_buffer::<BUFFER_SIZE>();
+        let (tx_wakers, rx_wakers) = (make_wakers::<WAKERS_COUNT>(), make_wakers::<WAKERS_COUNT>());
+        let requests = RequestLink::new(&buffer, &tx_wakers, &rx_wakers);
+
+        let mut client = Client::new(&requests, &replies);
+        let server = Server::new(&requests);
+
+        // the server is dropped without being closed while the request waits in the link
+        let calling = client.call(counter.add(1));
+        let dropping = async {
+            tokio::task::yield_now().await;
+            drop(server);
+        };
+        let (result, ()) = future::join(calling, dropping).await;
+        assert_eq!(result, Err(RpcError::Closed));
+
+        assert_eq!(client.call(counter.add(2)).await, Err(RpcError::Closed));
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[tokio::test]
+async fn t_06() {
+    let counter = Counter::new();
+
+    {
+        let replies = Replies::new(make_reply_links::<1>(), make_wakers::<1>());
+        let buffer = make_buffer::<BUFFER_SIZE>();
+        let (tx_wakers, rx_wakers) = (make_wakers::<WAKERS_COUNT>(), make_wakers::<WAKERS_COUNT>());
+        let requests = RequestLink::new(&buffer, &tx_wakers, &rx_wakers);
+
+        let mut c_1 = Client::new(&requests, &replies);
+        let mut c_2 = c_1.try_clone().expect("client.try-clone");
+        let mut c_3 = c_1.try_clone().expect("client.try-clone");
+        let mut server = Server::new(&requests);
+
+        let mut first = std::pin::pin!(c_1.call(counter.add(1)));
+        let mut second = std::pin::pin!(c_2.call(counter.add(2)));
+        assert!(future::poll_immediate(&mut first).await.is_none());
+        assert!(future::poll_immediate(&mut second).await.is_none());
+
+        // the only reply link is in use, the only waker is taken by the second call
+        assert_eq!(c_3.call(counter.add(3)).await, Err(RpcError::Busy));
+
+        let serving = async {
+            for _ in 0..2 {
+                let (req, responder) = server.next().await.expect("server.next");
+                responder.respond(req).expect("responder.respond");
+            }
+        };
+        let (r_1, r_2, ()) = future::join3(first, second, serving).await;
+        assert_eq!(r_1.expect("c-1.call").unwrap(), 1);
+        assert_eq!(r_2.expect("c-2.call").unwrap(), 2);
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+fn make_replies<const SIZE: usize>(
+) -> Replies<Resp, [ReplyLink<Resp>; SIZE], [(AtomicBool, AtomicWaker); WAKERS_COUNT]> {
+    Replies::new(make_reply_links(), make_wakers())
+}
+
+fn make_reply_links<const SIZE: usize>() -> [ReplyLink<Resp>; SIZE] {
+    core::array::from_fn(|_| Default::default())
+}
+
+fn make_buffer<'a, const SIZE: usize>() -> [Slot<Request<'a, Req, Resp>>; SIZE] {
+    core::array::from_fn(|_| Default::default())
+}
+
+fn make_wakers<const SIZE: usize>() -> [(AtomicBool, AtomicWaker); SIZE] {
+    core::array::from_fn(|_| Default::default())
+}
@@ -0,0 +1,151 @@
+use airlock::error::RecvErrorNoWait;
+use airlock::scope::Storage;
+
+mod utils;
+use futures::future;
+use utils::{Counted, Counter};
+
+type Value = Counted<usize>;
+
+#[test]
+fn t_00() {
+    let mut direct = Storage::new();
+    let mut buffered = Storage::new();
+    let mut mpmc = Storage::new();
+    airlock::scope(|s| {
+        let (_tx, _rx) = s.spsc_direct::<Value>(&mut direct);
+        let (_tx, _rx) = s.spsc_buffered::<Value, 4>(&mut buffered);
+        let (_tx, _rx) = s.mpmc::<Value, 4, 2>(&mut mpmc);
+    });
+}
+
+#[test]
+fn t_01() {
+    let counter = Counter::new();
+
+    let mut direct = Storage::new();
+    let mut buffered = Storage::new();
+    let mut mpmc = Storage::new();
+    let received = airlock::scope(|s| {
+        let (mut d_tx, mut d_rx) = s.spsc_direct::<Value>(&mut direct);
+        let (mut b_tx, mut b_rx) = s.spsc_buffered::<Value, 4>(&mut buffered);
+        let (mut m_tx, mut m_rx) = s.mpmc::<Value, 4, 2>(&mut mpmc);
+
+        d_tx.send_nowait(counter.add(1)).expect("d_tx.send-nowait");
+        b_tx.send_nowait(counter.add(2)).expect("b_tx.send-nowait");
+        m_tx.send_nowait(counter.add(3)).expect("m_tx.send-nowait");
+
+        [
+            d_rx.recv_nowait().expect("d_rx.recv-nowait").unwrap(),
+            b_rx.recv_nowait().expect("b_rx.recv-nowait").unwrap(),
+            m_rx.recv_nowait().expect("m_rx.recv-nowait").unwrap(),
+        ]
+    });
+    assert_eq!(received, [1, 2, 3]);
+    assert_eq!(counter.count(), 0);
+}
+
+#[test]
+fn t_02() {
+    let counter = Counter::new();
+
+    let mut direct = Storage::new();
+    let mut buffered = Storage::new();
+    let mut mpmc = Storage::new();
+    airlock::scope(|s| {
+        let (mut d_tx, d_rx) = s.spsc_direct::<Value>(&mut direct);
+        let (mut b_tx, b_rx) = s.spsc_buffered::<Value, 4>(&mut buffered);
+        let (mut m_tx, m_rx) = s.mpmc::<Value, 4, 2>(&mut mpmc);
+
+        d_tx.send_nowait(counter.add(1)).expect("d_tx.send-nowait");
+        for i in 0..3 {
+            b_tx.send_nowait(counter.add(i)).expect("b_tx.send-nowait");
+            m_tx.send_nowait(counter.add(i)).expect("m_tx.send-nowait");
+        }
+        let m_tx_2 = m_tx.try_clone().expect("m_tx.try-clone");
+
+        std::mem::forget(d_tx);
+        std::mem::forget(d_rx);
+        std::mem::forget(b_tx);
+        std::mem::forget(b_rx);
+        std::mem::forget(m_tx);
+        std::mem::forget(m_tx_2);
+        std::mem::forget(m_rx);
+    });
+    assert_eq!(counter.count(), 0);
+}
+
+#[test]
+fn t_03() {
+    let counter = Counter::new();
+
+    let mut storage = Storage::new();
+    let received = airlock::scope(|s| {
+        let (mut tx, mut rx) = s.spsc_buffered::<Value, 2>(&mut storage);
+        block_on(future::join(
+            async {
+                for i in 0..100 {
+                    tx.send(counter.add(i)).await.expect("tx.send");
+                }
+                tx.close();
+            },
+            async {
+                let mut received = 0;
+                while rx.recv().await.is_ok() {
+                    received += 1;
+                }
+                received
+            },
+        ))
+        .1
+    });
+    assert_eq!(received, 100);
+    assert_eq!(counter.count(), 0);
+}
+
+#[test]
+fn t_04() {
+    let counter = Counter::new();
+
+    let mut storage = Storage::new();
+    let (sent, received) = airlock::scope(|s| {
+        let (mut tx, mut rx) = s.mpmc::<Value, 4, 1>(&mut storage);
+        block_on(future::join(
+            async {
+                tx.send(counter.add(1)).await.expect("tx.send");
+                tx.close();
+                1
+            },
+            async {
+                let value = rx.recv().await.expect("rx.recv").unwrap();
+                assert!(matches!(rx.recv_nowait(), Err(RecvErrorNoWait::Closed)));
+                value
+            },
+        ))
+    });
+    assert_eq!(sent, received);
+    assert_eq!(counter.count(), 0);
+}
+
+#[test]
+fn t_05() {
+    let counter = Counter::new();
+
+    let mut storage = Storage::new();
+    for round in 0..3 {
+        airlock::scope(|s| {
+            let (mut tx, rx) = s.spsc_buffered::<Value, 4>(&mut storage);
+            tx.send_nowait(counter.add(round)).expect("tx.send-nowait");
+            std::mem::forget(tx);
+            std::mem::forget(rx);
+        });
+        assert_eq!(counter.count(), 0);
+    }
+}
+
+fn block_on<F: std::future::Future>(f: F) -> F::Output {
+    tokio::runtime::Builder::new_current_thread()
+        .build()
+        .expect("runtime")
+        .block_on(f)
+}
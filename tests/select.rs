@@ -0,0 +1,276 @@
+use std::sync::atomic::AtomicBool;
+
+use airlock::atomic_waker::AtomicWaker;
+use airlock::error::RecvError;
+use airlock::select::*;
+use airlock::slot::Slot;
+use airlock::{duplex, mpmc, spsc};
+
+mod utils;
+use futures::future;
+use utils::{Counted, Counter};
+
+type Value = Counted<usize>;
+
+const BUFFER_SIZE: usize = 8;
+
+#[tokio::test]
+async fn t_00() {
+    let counter = Counter::new();
+    {
+        let direct = spsc::direct::Link::<Value>::new();
+        let buffer = make_buffer::<_, BUFFER_SIZE>();
+        let buffered = spsc::buffered::Link::<Counted<&str>, _>::new(&buffer);
+
+        let _tx_d = spsc::direct::Tx::new(&direct);
+        let mut rx_d = spsc::direct::Rx::new(&direct);
+        let mut tx_b = spsc::buffered::Tx::new(&buffered);
+        let mut rx_b = spsc::buffered::Rx::new(&buffered);
+
+        tx_b.send_nowait(counter.add("one")).expect("tx-b.send-nowait");
+
+        let received = airlock::select! {
+            n = rx_d => n.expect("rx-d.recv").unwrap().to_string(),
+            s = rx_b => s.expect("rx-b.recv").unwrap().to_string(),
+        };
+        assert_eq!(received, "one");
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[tokio::test]
+async fn t_01() {
+    let counter = Counter::new();
+    {
+        let link_1 = spsc::direct::Link::<Value, &str>::new();
+        let link_2 = spsc::direct::Link::<Value, &str>::new();
+
+        let mut tx_1 = spsc::direct::Tx::new(&link_1);
+        let mut rx_1 = spsc::direct::Rx::new(&link_1);
+        let _tx_2 = spsc::direct::Tx::new(&link_2);
+        let mut rx_2 = spsc::direct::Rx::new(&link_2);
+
+        tx_1.close_with("done");
+
+        let closed_with = airlock::select! {
+            result = rx_1 => {
+                match result {
+                    Err(RecvError::ClosedWith(reason)) => Some(reason),
+                    _ => None,
+                }
+            }
+            _ = &mut rx_2 => None
+        };
+        assert_eq!(closed_with, Some("done"));
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[tokio::test]
+async fn t_02() {
+    let counter = Counter::new();
+    {
+        let buffer_1 = make_buffer::<_, BUFFER_SIZE>();
+        let buffer_2 = make_buffer::<_, BUFFER_SIZE>();
+        let link_1 = spsc::buffered::Link::<Value, _>::new(&buffer_1);
+        let link_2 = spsc::buffered::Link::<Value, _>::new(&buffer_2);
+
+        let mut tx_1 = spsc::buffered::Tx::new(&link_1);
+        let mut rx_1 = spsc::buffered::Rx::new(&link_1);
+        let mut tx_2 = spsc::buffered::Tx::new(&link_2);
+        let mut rx_2 = spsc::buffered::Rx::new(&link_2);
+
+        let mut hits = [0, 0];
+        for i in 0..100 {
+            let _ = tx_1.send_nowait(counter.add(i));
+            let _ = tx_2.send_nowait(counter.add(i));
+
+            let idx = airlock::select! {
+                _ = rx_1 => 0,
+                _ = rx_2 => 1,
+            };
+            hits[idx] += 1;
+        }
+        assert!(hits[0] > 0);
+        assert!(hits[1] > 0);
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[tokio::test]
+async fn t_03() {
+    let counter = Counter::new();
+    {
+        let link_1 = spsc::direct::Link::<Value>::new();
+        let link_2 = spsc::direct::Link::<Value>::new();
+
+        let mut tx_1 = spsc::direct::Tx::new(&link_1);
+        let mut rx_1 = spsc::direct::Rx::new(&link_1);
+        let mut tx_2 = spsc::direct::Tx::new(&link_2);
+        let mut rx_2 = spsc::direct::Rx::new(&link_2);
+
+        let producers = async {
+            for i in 0..50 {
+                tx_1.send(counter.add(i)).await.expect("tx-1.send");
+                tx_2.send(counter.add(i + 50)).await.expect("tx-2.send");
+            }
+        };
+        let consumer = async {
+            let mut received = vec![];
+            for _ in 0..100 {
+                received.push(airlock::select! {
+                    v = rx_1 => v.expect("rx-1.recv").unwrap(),
+                    v = rx_2 => v.expect("rx-2.recv").unwrap(),
+                });
+            }
+            received.sort();
+            received
+        };
+        let (_, received) = future::join(producers, consumer).await;
+        assert_eq!(received, (0..100).collect::<Vec<_>>());
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[tokio::test]
+async fn t_04() {
+    let counter = Counter::new();
+    {
+        let links: [spsc::direct::Link<Value>; 3] = Default::default();
+        let mut txs = links.each_ref().map(spsc::direct::Tx::new);
+        let mut merge = Merge::new(links.each_ref().map(spsc::direct::Rx::new));
+
+        for (i, tx) in txs.iter_mut().enumerate() {
+            tx.send_nowait(counter.add(i)).expect("tx.send-nowait");
+        }
+
+        let mut received = vec![];
+        for _ in 0..3 {
+            let (idx, value) = merge.recv().await.expect("merge.recv");
+            assert_eq!(value.expect("rx.recv"), counter.add(idx));
+            received.push(idx);
+        }
+        received.sort();
+        assert_eq!(received, [0, 1, 2]);
+
+        drop(txs);
+        let mut closed = vec![];
+        while let Some((idx, result)) = merge.recv().await {
+            assert!(result.is_err());
+            assert!(merge.is_closed(idx));
+            closed.push(idx);
+        }
+        closed.sort();
+        assert_eq!(closed, [0, 1, 2]);
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[tokio::test]
+async fn t_05() {
+    let counter = Counter::new();
+    {
+        let buffers: [[Slot<Value>; BUFFER_SIZE]; 2] = [make_buffer(), make_buffer()];
+        let links = buffers.each_ref().map(spsc::buffered::Link::<Value, _>::new);
+        let mut txs = links.each_ref().map(spsc::buffered::Tx::new);
+        let mut merge = Merge::new(links.each_ref().map(spsc::buffered::Rx::new));
+
+        for i in 0..4 {
+            for tx in txs.iter_mut() {
+                tx.send_nowait(counter.add(i)).expect("tx.send-nowait");
+            }
+        }
+
+        let mut indices = vec![];
+        for _ in 0..8 {
+            let (idx, _) = merge.recv().await.expect("merge.recv");
+            indices.push(idx);
+        }
+        assert_eq!(indices, [0, 1, 0, 1, 0, 1, 0, 1]);
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[tokio::test]
+async fn t_06() {
+    let counter = Counter::new();
+    {
+        let tx_wakers = make_wakers::<2>();
+        let rx_wakers = make_wakers::<2>();
+        let buffer = make_buffer::<_, BUFFER_SIZE>();
+        let requests = mpmc::Link::<Value, _, _, _>::new(&buffer, &tx_wakers, &rx_wakers);
+
+        let buffer_a = make_buffer::<_, BUFFER_SIZE>();
+        let buffer_b = make_buffer::<_, BUFFER_SIZE>();
+        let duplex = duplex::Link::<Value, Value, _, _>::new(&buffer_a, &buffer_b);
+
+        let mut tx = mpmc::Tx::new(&requests);
+        let mut rx = mpmc::Rx::new(&requests);
+        let mut end_a = duplex::EndA::new(&duplex);
+        let mut end_b = duplex::EndB::new(&duplex);
+
+        let producers = async {
+            for i in 0..20 {
+                tx.send(counter.add(i)).await.expect("tx.send");
+                end_b.send(counter.add(i + 20)).await.expect("end-b.send");
+            }
+        };
+        let consumer = async {
+            let mut received = vec![];
+            for _ in 0..40 {
+                received.push(airlock::select! {
+                    v = rx => v.expect("rx.recv").unwrap(),
+                    v = end_a => v.expect("end-a.recv").unwrap(),
+                });
+            }
+            received.sort();
+            received
+        };
+        let (_, received) = future::join(producers, consumer).await;
+        assert_eq!(received, (0..40).collect::<Vec<_>>());
+
+        tx.close();
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[tokio::test]
+async fn t_07() {
+    let counter = Counter::new();
+    {
+        let buffers = [(); 4].map(|_| make_buffer::<_, BUFFER_SIZE>());
+        let links = buffers.each_ref().map(spsc::buffered::Link::<Value, _>::new);
+        let mut txs = links.each_ref().map(spsc::buffered::Tx::new);
+        let [mut rx_1, mut rx_2, mut rx_3, mut rx_4] =
+            links.each_ref().map(spsc::buffered::Rx::new);
+
+        for (i, tx) in txs.iter_mut().enumerate() {
+            for j in 0..4 {
+                tx.send_nowait(counter.add(i * 10 + j)).expect("tx.send-nowait");
+            }
+        }
+
+        // each select! rotates on its own: the other one running in between does not skew it
+        let mut firsts = vec![];
+        for _ in 0..4 {
+            firsts.push(airlock::select! {
+                _ = rx_1 => 0,
+                _ = rx_2 => 1,
+            });
+            let _ = airlock::select! {
+                _ = rx_3 => 0,
+                _ = rx_4 => 1,
+            };
+        }
+        assert_eq!(firsts, [0, 1, 0, 1]);
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+fn make_buffer<T, const SIZE: usize>() -> [Slot<T>; SIZE] {
+    core::array::from_fn(|_| Default::default())
+}
+
+fn make_wakers<const SIZE: usize>() -> [(AtomicBool, AtomicWaker); SIZE] {
+    core::array::from_fn(|_| Default::default())
+}
@@ -0,0 +1,124 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use airlock::atomic_waker::AtomicWaker;
+use airlock::error::{AcquireError, NoPermits};
+use airlock::semaphore::*;
+
+use futures::future;
+
+const WAKERS_COUNT: usize = 4;
+
+#[test]
+fn t_00() {
+    let wakers = make_wakers::<WAKERS_COUNT>();
+    let semaphore = Semaphore::new(3, &wakers);
+
+    let p_1 = semaphore.try_acquire(2).expect("semaphore.try-acquire");
+    assert_eq!(p_1.count(), 2);
+    assert_eq!(semaphore.available_permits(), 1);
+    assert_eq!(semaphore.try_acquire(2).expect_err("semaphore.try-acquire"), NoPermits);
+
+    let p_2 = semaphore.try_acquire(1).expect("semaphore.try-acquire");
+    assert_eq!(semaphore.available_permits(), 0);
+
+    drop(p_1);
+    assert_eq!(semaphore.available_permits(), 2);
+    drop(p_2);
+    assert_eq!(semaphore.available_permits(), 3);
+}
+
+#[tokio::test]
+async fn t_01() {
+    let wakers = make_wakers::<WAKERS_COUNT>();
+    let semaphore = Semaphore::new(1, &wakers);
+
+    let permit = semaphore.acquire(1).await.expect("semaphore.acquire");
+    let waiting = async {
+        let permit = semaphore.acquire(1).await.expect("semaphore.acquire");
+        assert_eq!(permit.count(), 1);
+    };
+    let releasing = async {
+        tokio::task::yield_now().await;
+        drop(permit);
+    };
+    future::join(waiting, releasing).await;
+
+    assert_eq!(semaphore.available_permits(), 1);
+}
+
+#[tokio::test]
+async fn t_02() {
+    let wakers = make_wakers::<WAKERS_COUNT>();
+    let semaphore = Semaphore::new(1, &wakers);
+
+    let permit = semaphore.acquire(1).await.expect("semaphore.acquire");
+    let timeout =
+        tokio::time::timeout(std::time::Duration::from_millis(10), semaphore.acquire(1)).await;
+    assert!(timeout.is_err());
+    drop(permit);
+
+    // the cancelled waiter gave its waker back
+    let _ = semaphore.acquire(1).await.expect("semaphore.acquire");
+}
+
+#[tokio::test]
+async fn t_03() {
+    let wakers = make_wakers::<WAKERS_COUNT>();
+    let semaphore = Semaphore::new(1, &wakers);
+
+    assert_eq!(semaphore.acquire(2).await.expect_err("semaphore.acquire"), AcquireError::TooMany);
+    assert_eq!(semaphore.available_permits(), 1);
+}
+
+#[tokio::test]
+async fn t_04() {
+    const PERMITS: usize = 3;
+    const TASKS: usize = PERMITS + WAKERS_COUNT;
+    const ITERATIONS: usize = 1_000;
+
+    let wakers = make_wakers::<WAKERS_COUNT>();
+    let semaphore = Arc::new(Semaphore::new(PERMITS, wakers));
+    let inside = Arc::new(AtomicUsize::new(0));
+
+    let tasks = (0..TASKS)
+        .map(|_| {
+            let semaphore = Arc::clone(&semaphore);
+            let inside = Arc::clone(&inside);
+            async move {
+                for _ in 0..ITERATIONS {
+                    let _permit = semaphore.acquire(1).await.expect("semaphore.acquire");
+                    assert!(inside.fetch_add(1, Ordering::SeqCst) < PERMITS);
+                    tokio::task::yield_now().await;
+                    inside.fetch_sub(1, Ordering::SeqCst);
+                }
+            }
+        })
+        .map(tokio::spawn);
+    future::try_join_all(tasks).await.expect("tasks");
+
+    assert_eq!(semaphore.available_permits(), PERMITS);
+}
+
+#[tokio::test]
+async fn t_05() {
+    let wakers = make_wakers::<1>();
+    let semaphore = Semaphore::new(1, &wakers);
+
+    let permit = semaphore.acquire(1).await.expect("semaphore.acquire");
+    let mut waiting = std::pin::pin!(semaphore.acquire(1));
+    assert!(future::poll_immediate(&mut waiting).await.is_none());
+
+    assert_eq!(
+        semaphore.acquire(1).await.expect_err("semaphore.acquire"),
+        AcquireError::LimitReached
+    );
+
+    drop(permit);
+    let permit = waiting.await.expect("semaphore.acquire");
+    assert_eq!(permit.count(), 1);
+}
+
+fn make_wakers<const SIZE: usize>() -> [(AtomicBool, AtomicWaker); SIZE] {
+    core::array::from_fn(|_| Default::default())
+}
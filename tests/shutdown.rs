@@ -0,0 +1,136 @@
+use std::sync::atomic::AtomicBool;
+
+use airlock::atomic_waker::AtomicWaker;
+use airlock::error::LimitReached;
+use airlock::shutdown::*;
+use airlock::slot::Slot;
+use airlock::{mpmc, spsc};
+
+mod utils;
+use futures::future;
+use utils::{Counted, Counter};
+
+type Value = Counted<usize>;
+
+const WAKERS_COUNT: usize = 4;
+
+#[test]
+fn t_00() {
+    let counter = Counter::new();
+
+    {
+        let direct = spsc::direct::Link::<Value>::new();
+        let buffer = make_buffer::<4>();
+        let buffered = spsc::buffered::Link::<Value, _>::new(&buffer);
+        let (mpmc_buffer, mpmc_tx_wakers, mpmc_rx_wakers) =
+            (make_buffer::<4>(), make_wakers::<2>(), make_wakers::<2>());
+        let mpmc =
+            mpmc::Link::<Value, _, _, _>::new(&mpmc_buffer, &mpmc_tx_wakers, &mpmc_rx_wakers);
+
+        let registry = make_registry::<3>();
+        let wakers = make_wakers::<WAKERS_COUNT>();
+        let token = Token::new(&registry, &wakers);
+
+        token.register(&direct).expect("token.register");
+        token.register(&buffered).expect("token.register");
+        token.register(&mpmc).expect("token.register");
+
+        let mut direct_tx = spsc::direct::Tx::new(&direct);
+        let mut buffered_rx = spsc::buffered::Rx::new(&buffered);
+        let mut mpmc_tx = mpmc::Tx::new(&mpmc);
+        let _direct_rx = spsc::direct::Rx::new(&direct);
+        let _buffered_tx = spsc::buffered::Tx::new(&buffered);
+        let _mpmc_rx = mpmc::Rx::new(&mpmc);
+
+        assert!(!token.is_cancelled());
+        token.cancel();
+        assert!(token.is_cancelled());
+
+        assert!(direct_tx.send_nowait(counter.add(1)).expect_err("tx.send-nowait").is_closed());
+        assert!(buffered_rx.recv_nowait().expect_err("rx.recv-nowait").is_closed());
+        assert!(mpmc_tx.send_nowait(counter.add(2)).expect_err("tx.send-nowait").is_closed());
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[test]
+fn t_01() {
+    let first = spsc::direct::Link::<Value>::new();
+    let second = spsc::direct::Link::<Value>::new();
+
+    let registry = make_registry::<1>();
+    let wakers = make_wakers::<WAKERS_COUNT>();
+    let token = Token::new(&registry, &wakers);
+
+    token.register(&first).expect("token.register");
+    assert_eq!(token.register(&second), Err(LimitReached));
+}
+
+#[test]
+fn t_02() {
+    let link = spsc::direct::Link::<Value>::new();
+
+    let registry = make_registry::<1>();
+    let wakers = make_wakers::<WAKERS_COUNT>();
+    let token = Token::new(&registry, &wakers);
+
+    token.cancel();
+    token.register(&link).expect("token.register");
+
+    let mut rx = spsc::direct::Rx::new(&link);
+    assert!(rx.recv_nowait().expect_err("rx.recv-nowait").is_closed());
+}
+
+#[tokio::test]
+async fn t_03() {
+    let link = spsc::direct::Link::<Value>::new();
+
+    let registry = make_registry::<1>();
+    let wakers = make_wakers::<WAKERS_COUNT>();
+    let token = Token::new(&registry, &wakers);
+    token.register(&link).expect("token.register");
+
+    let mut rx = spsc::direct::Rx::new(&link);
+    let _tx = spsc::direct::Tx::new(&link);
+
+    let receiving = async {
+        assert!(rx.recv().await.expect_err("rx.recv").is_closed());
+    };
+    let waiting = future::join_all((0..WAKERS_COUNT).map(|_| token.cancelled()));
+    let cancelling = async {
+        tokio::task::yield_now().await;
+        token.cancel();
+    };
+    let ((), waited, ()) = future::join3(receiving, waiting, cancelling).await;
+    for waited in waited {
+        waited.expect("token.cancelled");
+    }
+}
+
+#[tokio::test]
+async fn t_04() {
+    let registry = make_registry::<1>();
+    let wakers = make_wakers::<1>();
+    let token = Token::new(&registry, &wakers);
+
+    let mut waiting = std::pin::pin!(token.cancelled());
+    assert!(future::poll_immediate(&mut waiting).await.is_none());
+
+    assert_eq!(token.cancelled().await.expect_err("token.cancelled"), LimitReached);
+
+    token.cancel();
+    waiting.await.expect("token.cancelled");
+    token.cancelled().await.expect("token.cancelled");
+}
+
+fn make_registry<'a, const SIZE: usize>() -> [Registration<'a>; SIZE] {
+    core::array::from_fn(|_| Default::default())
+}
+
+fn make_buffer<const SIZE: usize>() -> [Slot<Value>; SIZE] {
+    core::array::from_fn(|_| Default::default())
+}
+
+fn make_wakers<const SIZE: usize>() -> [(AtomicBool, AtomicWaker); SIZE] {
+    core::array::from_fn(|_| Default::default())
+}
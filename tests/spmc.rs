@@ -0,0 +1,245 @@
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+use airlock::atomic_waker::AtomicWaker;
+use airlock::error::{RecvError, SendErrorNoWait};
+use airlock::slot::Slot;
+use airlock::spmc::*;
+
+mod utils;
+use futures::future;
+use utils::{Counted, Counter};
+
+type Value = Counted<usize>;
+
+const BUFFER_SIZE: usize = 64;
+const WAKERS_COUNT: usize = 8;
+
+#[test]
+fn t_00() {
+    let rx_wakers = make_wakers::<WAKERS_COUNT>();
+    let buffer = make_buffer::<BUFFER_SIZE>();
+    let _link = Link::<Value, _, _>::new(&buffer, &rx_wakers);
+}
+
+#[test]
+fn t_01() {
+    let rx_wakers = make_wakers::<WAKERS_COUNT>();
+    let buffer = make_buffer::<BUFFER_SIZE>();
+    let link = Link::<Value, _, _>::new(&buffer, &rx_wakers);
+
+    let _tx = Tx::new(&link);
+
+    let _rx_1 = Rx::new(&link);
+    let _rx_2 = Rx::new(&link);
+}
+
+#[test]
+#[should_panic]
+fn t_02() {
+    let rx_wakers = make_wakers::<WAKERS_COUNT>();
+    let buffer = make_buffer::<BUFFER_SIZE>();
+    let link = Link::<Value, _, _>::new(&buffer, &rx_wakers);
+
+    let _tx_1 = Tx::new(&link);
+    let _tx_2 = Tx::new(&link);
+}
+
+#[test]
+fn t_03() {
+    let counter = Counter::new();
+
+    {
+        let rx_wakers = make_wakers::<2>();
+        let buffer = make_buffer::<4>();
+        let link = Link::<Value, _, _>::new(&buffer, &rx_wakers);
+
+        let mut tx = Tx::new(&link);
+        let mut rx_1 = Rx::new(&link);
+        let mut rx_2 = rx_1.try_clone().expect("rx-1.try-clone");
+        assert!(rx_1.try_clone().is_err());
+
+        for round in 0..3 {
+            for i in 0..3 {
+                tx.send_nowait(counter.add(round * 3 + i)).expect("tx.send-nowait");
+            }
+            assert!(tx.send_nowait(counter.add(0)).expect_err("tx.send-nowait").is_full());
+
+            assert_eq!(rx_1.recv_nowait().expect("rx-1.recv-nowait").unwrap(), round * 3);
+            assert_eq!(rx_2.recv_nowait().expect("rx-2.recv-nowait").unwrap(), round * 3 + 1);
+            assert_eq!(rx_1.recv_nowait().expect("rx-1.recv-nowait").unwrap(), round * 3 + 2);
+            assert!(rx_2.recv_nowait().expect_err("rx-2.recv-nowait").is_empty());
+        }
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[test]
+fn t_04() {
+    let counter = Counter::new();
+
+    {
+        let rx_wakers = make_wakers::<WAKERS_COUNT>();
+        let buffer = make_buffer::<BUFFER_SIZE>();
+        let link = Link::<Value, _, _>::new(&buffer, &rx_wakers);
+
+        let mut tx = Tx::new(&link);
+        let mut rx = Rx::new(&link);
+
+        tx.send_nowait(counter.add(1)).expect("tx.send-nowait");
+        tx.send_nowait(counter.add(2)).expect("tx.send-nowait");
+        drop(tx);
+
+        assert_eq!(rx.recv_nowait().expect("rx.recv-nowait").unwrap(), 1);
+        assert!(rx.recv_nowait().is_ok());
+        assert!(rx.recv_nowait().expect_err("rx.recv-nowait").is_closed());
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[test]
+fn t_05() {
+    let counter = Counter::new();
+
+    {
+        let rx_wakers = make_wakers::<WAKERS_COUNT>();
+        let buffer = make_buffer::<BUFFER_SIZE>();
+        let link = Link::<Value, _, _>::new(&buffer, &rx_wakers);
+
+        let mut tx = Tx::new(&link);
+        let rx_1 = Rx::new(&link);
+        let rx_2 = Rx::new(&link);
+
+        tx.send_nowait(counter.add(1)).expect("tx.send-nowait");
+        drop(rx_1);
+        tx.send_nowait(counter.add(2)).expect("tx.send-nowait");
+        drop(rx_2);
+        assert!(tx.send_nowait(counter.add(3)).expect_err("tx.send-nowait").is_closed());
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[tokio::test]
+async fn t_06() {
+    let counter = Counter::new();
+
+    {
+        let rx_wakers = make_wakers::<WAKERS_COUNT>();
+        let buffer = make_buffer::<BUFFER_SIZE>();
+        let link = Link::<Value, _, _, &str>::new(&buffer, &rx_wakers);
+
+        let mut tx = Tx::new(&link);
+        let mut rx = Rx::new(&link);
+
+        tx.send(counter.add(1)).await.expect("tx.send");
+        rx.close_with("done");
+
+        assert!(matches!(
+            tx.send_nowait(counter.add(2)),
+            Err(SendErrorNoWait::ClosedWith(_, "done"))
+        ));
+        assert_eq!(rx.recv().await.expect("rx.recv").unwrap(), 1);
+        assert!(matches!(rx.recv().await, Err(RecvError::ClosedWith("done"))));
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[tokio::test]
+async fn t_07() {
+    let counter = Counter::new();
+
+    {
+        let rx_wakers = make_wakers::<2>();
+        let buffer = make_buffer::<3>();
+        let link = Link::<Value, _, _>::new(&buffer, &rx_wakers);
+
+        let tx = Tx::new(&link);
+        let mut rx_1 = Rx::new(&link);
+        let mut rx_2 = Rx::new(&link);
+
+        let producer = async {
+            let mut tx = tx;
+            for i in 0..200 {
+                tx.send(counter.add(i)).await.expect("tx.send");
+            }
+        };
+        let consumers = future::join(
+            async {
+                let mut received = vec![];
+                while let Ok(value) = rx_1.recv().await {
+                    received.push(value.unwrap());
+                }
+                received
+            },
+            async {
+                let mut received = vec![];
+                while let Ok(value) = rx_2.recv().await {
+                    received.push(value.unwrap());
+                }
+                received
+            },
+        );
+        let (_, (first, second)) = future::join(producer, consumers).await;
+
+        assert!(first.windows(2).all(|w| w[0] < w[1]));
+        assert!(second.windows(2).all(|w| w[0] < w[1]));
+
+        let mut received = [first, second].concat();
+        received.sort();
+        assert_eq!(received, (0..200).collect::<Vec<_>>());
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[tokio::test]
+async fn t_08() {
+    let counter = Counter::new();
+
+    const ITERATIONS: usize = 1_000_000;
+
+    {
+        let rx_wakers = make_wakers::<WAKERS_COUNT>();
+        let buffer = make_buffer::<BUFFER_SIZE>();
+        let link = Arc::new(Link::<Value, _, _>::new(buffer, rx_wakers));
+
+        let producer = {
+            let counter = counter.clone();
+            let link = Arc::clone(&link);
+            async move {
+                let mut tx = Tx::new(Arc::clone(&link));
+                for i in 0..ITERATIONS {
+                    tx.send(counter.add(i)).await.expect("tx.send");
+                }
+            }
+        };
+        let consumers = (0..WAKERS_COUNT)
+            .map(|_| {
+                let link = Arc::clone(&link);
+                async move {
+                    let mut rx = Rx::new(Arc::clone(&link));
+                    let mut received = 0;
+                    while rx.recv().await.is_ok() {
+                        received += 1;
+                    }
+                    received
+                }
+            })
+            .map(tokio::spawn)
+            .collect::<Vec<_>>();
+
+        tokio::spawn(producer).await.expect("producer");
+        let consumers = future::try_join_all(consumers).await.expect("consumers");
+
+        let received: usize = consumers.iter().sum();
+        assert_eq!(received, ITERATIONS);
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+fn make_buffer<const SIZE: usize>() -> [Slot<Value>; SIZE] {
+    core::array::from_fn(|_| Default::default())
+}
+
+fn make_wakers<const SIZE: usize>() -> [(AtomicBool, AtomicWaker); SIZE] {
+    core::array::from_fn(|_| Default::default())
+}
@@ -0,0 +1,246 @@
+use std::sync::Arc;
+
+use airlock::error::{ResizeError, SendErrorNoWait};
+use airlock::slot::Slot;
+use airlock::spsc::buffered::growable::*;
+
+mod utils;
+use futures::future;
+use utils::{Counted, Counter};
+
+type Value = Counted<usize>;
+
+#[test]
+fn t_00() {
+    let _link = Link::<Value, _>::new(make_buffer(4));
+}
+
+#[test]
+fn t_01() {
+    let counter = Counter::new();
+
+    {
+        let link = Link::<Value, _>::new(make_buffer(4));
+        let mut tx = Tx::new(&link);
+        let mut rx = Rx::new(&link);
+
+        assert_eq!(rx.capacity(), 3);
+        for i in 0..3 {
+            tx.send_nowait(counter.add(i)).expect("tx.send-nowait");
+        }
+        assert!(tx.send_nowait(counter.add(3)).expect_err("tx.send-nowait").is_full());
+
+        let old = rx.resize(make_buffer(8)).expect("rx.resize");
+        assert_eq!(old.len(), 4);
+        assert_eq!(rx.capacity(), 7);
+
+        for i in 3..7 {
+            tx.send_nowait(counter.add(i)).expect("tx.send-nowait");
+        }
+        assert!(tx.send_nowait(counter.add(7)).expect_err("tx.send-nowait").is_full());
+
+        for i in 0..7 {
+            assert_eq!(rx.recv_nowait().expect("rx.recv-nowait").unwrap(), i);
+        }
+        assert!(rx.recv_nowait().expect_err("rx.recv-nowait").is_empty());
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[test]
+fn t_02() {
+    let counter = Counter::new();
+
+    {
+        let link = Link::<Value, _>::new(make_buffer(4));
+        let mut tx = Tx::new(&link);
+        let mut rx = Rx::new(&link);
+
+        for i in 0..3 {
+            tx.send_nowait(counter.add(i)).expect("tx.send-nowait");
+        }
+        for i in 0..2 {
+            assert_eq!(rx.recv_nowait().expect("rx.recv-nowait").unwrap(), i);
+        }
+        for i in 3..5 {
+            tx.send_nowait(counter.add(i)).expect("tx.send-nowait");
+        }
+
+        rx.resize(make_buffer(6)).expect("rx.resize");
+        for i in 5..7 {
+            tx.send_nowait(counter.add(i)).expect("tx.send-nowait");
+        }
+
+        for i in 2..5 {
+            assert_eq!(rx.recv_nowait().expect("rx.recv-nowait").unwrap(), i);
+        }
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[test]
+fn t_03() {
+    let counter = Counter::new();
+
+    {
+        let link = Link::<Value, _>::new(make_buffer(8));
+        let mut tx = Tx::new(&link);
+        let mut rx = Rx::new(&link);
+
+        tx.send_nowait(counter.add(1)).expect("tx.send-nowait");
+        let rejected = rx.resize(make_buffer(4)).expect_err("rx.resize");
+        assert!(matches!(rejected, ResizeError::NotEmpty(_)));
+        assert_eq!(rejected.into_buffer().len(), 4);
+        assert_eq!(rx.capacity(), 7);
+
+        assert_eq!(rx.recv_nowait().expect("rx.recv-nowait").unwrap(), 1);
+        rx.resize(make_buffer(2)).expect("rx.resize");
+        assert_eq!(rx.capacity(), 1);
+
+        tx.send_nowait(counter.add(2)).expect("tx.send-nowait");
+        assert!(tx.send_nowait(counter.add(3)).expect_err("tx.send-nowait").is_full());
+        assert_eq!(rx.recv_nowait().expect("rx.recv-nowait").unwrap(), 2);
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[test]
+fn t_04() {
+    let counter = Counter::new();
+
+    {
+        let link = Link::<Value, _>::new(make_buffer(2));
+        let mut resizer = Resizer::new(&link);
+        let mut tx = Tx::new(&link);
+        let mut rx = Rx::new(&link);
+
+        tx.send_nowait(counter.add(0)).expect("tx.send-nowait");
+        resizer.resize(make_buffer(16)).expect("resizer.resize");
+        assert_eq!(resizer.capacity(), 15);
+        assert_eq!(rx.capacity(), 15);
+
+        for i in 1..10 {
+            tx.send_nowait(counter.add(i)).expect("tx.send-nowait");
+        }
+        let old = resizer.resize(make_buffer(32)).expect("resizer.resize");
+        assert_eq!(old.len(), 16);
+
+        for i in 0..10 {
+            assert_eq!(rx.recv_nowait().expect("rx.recv-nowait").unwrap(), i);
+        }
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[tokio::test]
+async fn t_05() {
+    let counter = Counter::new();
+
+    {
+        let link = Link::<Value, _>::new(make_buffer(2));
+        let mut tx = Tx::new(&link);
+        let mut rx = Rx::new(&link);
+
+        let (_, received) = future::join(
+            async {
+                for i in 0..100 {
+                    tx.send(counter.add(i)).await.expect("tx.send");
+                }
+                tx.close();
+            },
+            async {
+                let mut received = vec![];
+                while let Ok(value) = rx.recv().await {
+                    received.push(value.unwrap());
+                    if received.len() % 10 == 0 {
+                        let len = rx.capacity() + 1;
+                        rx.resize(make_buffer(len * 2)).expect("rx.resize");
+                    }
+                }
+                received
+            },
+        )
+        .await;
+        assert_eq!(received, (0..100).collect::<Vec<_>>());
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[test]
+fn t_06() {
+    let counter = Counter::new();
+
+    const ITERATIONS: usize = 100_000;
+
+    {
+        let link = Arc::new(Link::<Value, _>::new(make_buffer(4)));
+        let mut tx = Tx::new(Arc::clone(&link));
+        let mut rx = Rx::new(Arc::clone(&link));
+
+        let producer = {
+            let counter = counter.clone();
+            std::thread::spawn(move || {
+                for i in 0..ITERATIONS {
+                    let mut value = counter.add(i);
+                    loop {
+                        match tx.send_nowait(value) {
+                            Ok(()) => break,
+                            Err(SendErrorNoWait::Full(rejected)) => value = rejected,
+                            Err(e) => panic!("tx.send-nowait: {:?}", e),
+                        }
+                        std::thread::yield_now();
+                    }
+                }
+            })
+        };
+
+        let consumer = std::thread::spawn(move || {
+            let mut expected = 0;
+            let mut lens = [4, 64, 8, 256].into_iter().cycle();
+            while expected < ITERATIONS {
+                match rx.recv_nowait() {
+                    Ok(value) => {
+                        assert_eq!(value.unwrap(), expected);
+                        expected += 1;
+                        if expected % 1_000 == 0 {
+                            let _ = rx.resize(make_buffer(lens.next().unwrap()));
+                        }
+                    },
+                    Err(e) => {
+                        assert!(e.is_empty());
+                        std::thread::yield_now();
+                    },
+                }
+            }
+        });
+
+        producer.join().expect("producer.join");
+        consumer.join().expect("consumer.join");
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[test]
+fn t_07() {
+    let counter = Counter::new();
+
+    {
+        let link = Link::<Value, _>::new(make_buffer(4));
+        let mut resizer = Resizer::new(&link);
+        let mut tx = Tx::new(&link);
+        let mut rx = Rx::new(&link);
+
+        tx.send_nowait(counter.add(1)).expect("tx.send-nowait");
+        let rejected = resizer.resize(make_buffer(0)).expect_err("resizer.resize");
+        assert!(matches!(rejected, ResizeError::Empty(_)));
+        assert!(matches!(rx.resize(make_buffer(0)), Err(ResizeError::Empty(_))));
+        assert_eq!(rx.capacity(), 3);
+
+        assert_eq!(rx.recv_nowait().expect("rx.recv-nowait").unwrap(), 1);
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+fn make_buffer(len: usize) -> Box<[Slot<Value>]> {
+    (0..len).map(|_| Default::default()).collect()
+}
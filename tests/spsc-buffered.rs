@@ -1,5 +1,6 @@
 use std::sync::Arc;
 
+use airlock::error::InUse;
 use airlock::slot::Slot;
 use airlock::spsc::buffered::*;
 
@@ -271,6 +272,317 @@ fn t_12() {
     assert_eq!(counter.count(), 0);
 }
 
+#[test]
+fn t_13() {
+    let counter = Counter::new();
+    {
+        let buffer = make_buffer::<3>();
+        let link = Link::<Value, _>::new_lossy(&buffer);
+        let mut tx = Tx::new(&link);
+        let mut rx = Rx::new(&link);
+
+        for i in 1..=5 {
+            tx.send_nowait(counter.add(i)).expect("tx.send-nowait");
+        }
+        assert_eq!(rx.dropped(), 3);
+        assert_eq!(counter.count(), 2);
+
+        assert_eq!(rx.recv_nowait().expect("rx.recv-nowait").unwrap(), 4);
+        tx.send_nowait(counter.add(6)).expect("tx.send-nowait");
+        tx.send_nowait(counter.add(7)).expect("tx.send-nowait");
+        assert_eq!(rx.dropped(), 4);
+
+        assert_eq!(rx.recv_nowait().expect("rx.recv-nowait").unwrap(), 6);
+        assert_eq!(rx.recv_nowait().expect("rx.recv-nowait").unwrap(), 7);
+        assert!(rx.recv_nowait().expect_err("rx.recv-nowait").is_empty());
+
+        tx.send_nowait(counter.add(8)).expect("tx.send-nowait");
+        tx.close();
+        assert!(tx.send_nowait(counter.add(9)).expect_err("tx.send-nowait").is_closed());
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[tokio::test]
+async fn t_14() {
+    const ITERATIONS: usize = 100_000;
+
+    let counter = Counter::new();
+    {
+        let buffer = make_buffer::<BUFFER_SIZE>();
+        let link = Arc::new(Link::<Value, _>::new_lossy(buffer));
+
+        let producer = {
+            let counter = counter.clone();
+            let link = Arc::clone(&link);
+            async move {
+                let mut tx = Tx::new(link);
+                for i in 0..ITERATIONS {
+                    tx.send(counter.add(i)).await.expect("tx.send");
+                    if i % 7 == 0 {
+                        tokio::task::yield_now().await;
+                    }
+                }
+            }
+        };
+        let consumer = {
+            let link = Arc::clone(&link);
+            async move {
+                let mut rx = Rx::new(link);
+
+                let mut count = 0;
+                let mut last = None;
+                while let Ok(v) = rx.recv().await.map(Counted::unwrap) {
+                    assert!(last.is_none_or(|last| last < v));
+                    last = Some(v);
+                    count += 1;
+                }
+
+                (count, rx.dropped())
+            }
+        };
+
+        let producer = tokio::spawn(producer);
+        let consumer = tokio::spawn(consumer);
+
+        producer.await.expect("producer.join");
+        let (count, dropped) = consumer.await.expect("consumer.join");
+
+        eprintln!("count:   {:?}", count);
+        eprintln!("dropped: {:?}", dropped);
+
+        assert_eq!(count + dropped, ITERATIONS);
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[tokio::test]
+async fn t_15() {
+    const ITERATIONS: usize = 1_000;
+
+    let counter = Counter::new();
+    {
+        let buffer = make_buffer::<BUFFER_SIZE>();
+        let link = Arc::new(Link::<Value, _, String>::new(buffer));
+
+        let producer = {
+            let counter = counter.clone();
+            let link = Arc::clone(&link);
+            async move {
+                let mut tx = Tx::new(link);
+                for i in 0..ITERATIONS {
+                    tx.send(counter.add(i)).await.expect("tx.send");
+                }
+                tx.close_with("done".to_owned());
+            }
+        };
+        let consumer = {
+            let link = Arc::clone(&link);
+            async move {
+                let mut rx = Rx::new(link);
+
+                let mut count = 0;
+                let err = loop {
+                    match rx.recv().await {
+                        Ok(v) => assert_eq!(v.unwrap(), count),
+                        Err(err) => break err,
+                    }
+                    count += 1;
+                };
+
+                (count, err)
+            }
+        };
+
+        let producer = tokio::spawn(producer);
+        let consumer = tokio::spawn(consumer);
+
+        producer.await.expect("producer.join");
+        let (count, err) = consumer.await.expect("consumer.join");
+
+        assert_eq!(count, ITERATIONS);
+        assert_eq!(err.reason().map(String::as_str), Some("done"));
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[test]
+fn t_16() {
+    let counter = Counter::new();
+    {
+        let buffer = make_buffer::<BUFFER_SIZE>();
+        let link = Link::<Value, _>::new(&buffer);
+        let mut tx = Tx::new(&link);
+        let mut rx = Rx::new(&link);
+
+        tx.send_nowait(counter.add(1)).expect("tx.send-nowait");
+        tx.send_nowait(counter.add(2)).expect("tx.send-nowait");
+        rx.abort();
+        assert_eq!(counter.count(), 0);
+
+        assert!(rx.recv_nowait().expect_err("rx.recv-nowait").is_closed());
+        assert!(tx.send_nowait(counter.add(3)).expect_err("tx.send-nowait").is_closed());
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[test]
+fn t_17() {
+    let counter = Counter::new();
+    {
+        let buffer = make_buffer::<BUFFER_SIZE>();
+        let link = Link::<Value, _>::new(&buffer);
+        let mut tx = Tx::new(&link);
+        let mut rx = Rx::new(&link);
+
+        tx.send_nowait(counter.add(1)).expect("tx.send-nowait");
+        tx.send_nowait(counter.add(2)).expect("tx.send-nowait");
+        tx.abort();
+        assert_eq!(counter.count(), 0);
+
+        assert!(rx.recv_nowait().expect_err("rx.recv-nowait").is_closed());
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[test]
+fn t_18() {
+    let counter = Counter::new();
+    {
+        let buffer = make_buffer::<BUFFER_SIZE>();
+        let link = Link::<Value, _>::new(&buffer);
+        let mut tx = Tx::new(&link);
+        let mut rx = Rx::new(&link);
+
+        for i in 0..5 {
+            tx.send_nowait(counter.add(i)).expect("tx.send-nowait");
+        }
+        assert_eq!(tx.reclaim().count(), 0);
+
+        assert_eq!(rx.recv_nowait().expect("rx.recv-nowait").unwrap(), 0);
+        rx.close_for_reclaim();
+        assert!(rx.recv_nowait().expect_err("rx.recv-nowait").is_closed());
+
+        assert_eq!(tx.reclaim().map(Counted::unwrap).collect::<Vec<_>>(), [1, 2, 3, 4]);
+        assert_eq!(tx.reclaim().count(), 0);
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[test]
+fn t_19() {
+    let counter = Counter::new();
+    {
+        let buffer = make_buffer::<4>();
+        let link = Link::<Value, _>::new_lossy(&buffer);
+        let mut tx = Tx::new(&link);
+        let rx = Rx::new(&link);
+
+        for i in 0..5 {
+            tx.send_nowait(counter.add(i)).expect("tx.send-nowait");
+        }
+        std::mem::drop(rx);
+
+        assert_eq!(tx.reclaim().map(Counted::unwrap).collect::<Vec<_>>(), [2, 3, 4]);
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[test]
+fn t_20() {
+    let counter = Counter::new();
+    {
+        let buffer = make_buffer::<BUFFER_SIZE>();
+        let link = Link::<Value, _>::new(&buffer);
+
+        for session in 0..3 {
+            let mut tx = Tx::new(&link);
+            let mut rx = Rx::new(&link);
+
+            for i in 0..5 {
+                tx.send_nowait(counter.add(session * 10 + i)).expect("tx.send-nowait");
+            }
+            assert_eq!(rx.recv_nowait().expect("rx.recv-nowait").unwrap(), session * 10);
+
+            std::mem::drop(rx);
+            assert_eq!(link.try_reset(), Err(InUse));
+            std::mem::drop(tx);
+
+            assert_eq!(counter.count(), 4);
+            link.try_reset().expect("link.try-reset");
+            assert_eq!(counter.count(), 0);
+        }
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[test]
+fn t_21() {
+    let counter = Counter::new();
+    {
+        let buffer = make_buffer::<BUFFER_SIZE>();
+        let mut link = Link::<Value, _>::new(&buffer);
+        {
+            let mut tx = Tx::new(&link);
+            let mut rx = Rx::new(&link);
+            for i in 0..5 {
+                tx.send_nowait(counter.add(i)).expect("tx.send-nowait");
+            }
+            rx.close();
+        }
+        link.reset();
+        assert_eq!(counter.count(), 0);
+
+        let mut tx = Tx::new(&link);
+        let mut rx = Rx::new(&link);
+        tx.send_nowait(counter.add(1)).expect("tx.send-nowait");
+        assert_eq!(rx.recv_nowait().expect("rx.recv-nowait").unwrap(), 1);
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[test]
+fn t_22() {
+    let counter = Counter::new();
+
+    let buffer = make_buffer::<BUFFER_SIZE>();
+    let received = Link::<Value, _>::scoped(&buffer, |mut tx, mut rx| {
+        for i in 0..5 {
+            tx.send_nowait(counter.add(i)).expect("tx.send-nowait");
+        }
+        let received = rx.recv_nowait().expect("rx.recv-nowait").unwrap();
+        std::mem::forget(tx);
+        std::mem::forget(rx);
+        received
+    });
+    assert_eq!(received, 0);
+    assert_eq!(counter.count(), 0);
+}
+
+#[test]
+fn t_23() {
+    let counter = Counter::new();
+    {
+        let buffer = make_buffer::<BUFFER_SIZE>();
+        let link = Link::<Value, _>::new(&buffer);
+        let mut tx = Tx::new(&link);
+        let mut rx = Rx::new(&link);
+
+        for i in 0..3 {
+            tx.send_nowait(counter.add(i)).expect("tx.send-nowait");
+        }
+        rx.close();
+        assert!(tx.send_nowait(counter.add(3)).expect_err("tx.send-nowait").is_closed());
+        assert_eq!(tx.reclaim().count(), 0);
+
+        for i in 0..3 {
+            assert_eq!(rx.recv_nowait().expect("rx.recv-nowait").unwrap(), i);
+        }
+        assert!(rx.recv_nowait().expect_err("rx.recv-nowait").is_closed());
+    }
+    assert_eq!(counter.count(), 0);
+}
+
 fn make_buffer<const SIZE: usize>() -> [Slot<Value>; SIZE] {
     core::array::from_fn(|_| Default::default())
 }
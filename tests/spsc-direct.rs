@@ -1,5 +1,6 @@
 use std::sync::Arc;
 
+use airlock::error::{InUse, RecvErrorNoWait};
 use airlock::spsc::direct::*;
 
 mod utils;
@@ -293,3 +294,141 @@ fn t_13() {
     }
     assert_eq!(counter.count(), 0);
 }
+
+#[test]
+fn t_14() {
+    let counter = Counter::new();
+    {
+        let link = Link::<Value, &str>::new();
+        let mut tx = Tx::new(&link);
+        let mut rx = Rx::new(&link);
+
+        tx.send_nowait(counter.add(1)).expect("tx.send-nowait");
+        tx.close_with("done");
+        rx.close_with("ignored");
+        assert_eq!(rx.recv_nowait().expect("rx.recv-nowait").unwrap(), 1);
+        assert_eq!(
+            rx.recv_nowait().expect_err("rx.recv-nowait"),
+            RecvErrorNoWait::ClosedWith("done")
+        );
+        assert_eq!(
+            tx.send_nowait(counter.add(2)).expect_err("tx.send-nowait").reason(),
+            Some(&"done")
+        );
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[tokio::test]
+async fn t_15() {
+    let counter = Counter::new();
+    {
+        let link = Arc::new(Link::<Value, &str>::new());
+        let mut tx = Tx::new(Arc::clone(&link));
+        let mut rx = Rx::new(Arc::clone(&link));
+
+        let consumer = async move {
+            assert_eq!(rx.recv().await.expect("rx.recv").unwrap(), 1);
+            rx.close_with("enough");
+        };
+        let producer = {
+            let counter = counter.clone();
+            async move {
+                tx.send(counter.add(1)).await.expect("tx.send");
+                let err = tx.send(counter.add(2)).await.expect_err("tx.send");
+                assert!(err.is_closed());
+                assert_eq!(err.reason(), Some(&"enough"));
+            }
+        };
+        future::join(producer, consumer).await;
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[test]
+fn t_16() {
+    let counter = Counter::new();
+    {
+        let link = Link::<Value, &str>::new();
+
+        for session in 0..3 {
+            let mut tx = Tx::new(&link);
+            let mut rx = Rx::new(&link);
+
+            assert_eq!(link.try_reset(), Err(InUse));
+
+            tx.send_nowait(counter.add(session)).expect("tx.send-nowait");
+            assert_eq!(rx.recv_nowait().expect("rx.recv-nowait").unwrap(), session);
+            tx.send_nowait(counter.add(session)).expect("tx.send-nowait");
+            tx.close_with("done");
+
+            std::mem::drop(tx);
+            assert_eq!(link.try_reset(), Err(InUse));
+            std::mem::drop(rx);
+
+            assert_eq!(counter.count(), 1);
+            link.try_reset().expect("link.try-reset");
+            assert_eq!(counter.count(), 0);
+        }
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[test]
+fn t_17() {
+    let counter = Counter::new();
+    {
+        let mut link = Link::<Value>::new();
+        {
+            let mut tx = Tx::new(&link);
+            tx.send_nowait(counter.add(1)).expect("tx.send-nowait");
+        }
+        link.reset();
+        assert_eq!(counter.count(), 0);
+
+        let mut tx = Tx::new(&link);
+        let mut rx = Rx::new(&link);
+        tx.send_nowait(counter.add(2)).expect("tx.send-nowait");
+        assert_eq!(rx.recv_nowait().expect("rx.recv-nowait").unwrap(), 2);
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[test]
+fn t_18() {
+    let counter = Counter::new();
+
+    let received = Link::<Value>::scoped(|mut tx, mut rx| {
+        tx.send_nowait(counter.add(1)).expect("tx.send-nowait");
+        let received = rx.recv_nowait().expect("rx.recv-nowait").unwrap();
+        tx.send_nowait(counter.add(2)).expect("tx.send-nowait");
+        std::mem::forget(tx);
+        received
+    });
+    assert_eq!(received, 1);
+    assert_eq!(counter.count(), 0);
+}
+
+#[test]
+fn t_19() {
+    let counter = Counter::new();
+    {
+        let link = Link::<Value, &str>::new();
+
+        for session in 0..3 {
+            let (mut tx, mut rx) = link.try_reuse().expect("link.try-reuse");
+            assert!(matches!(link.try_reuse(), Err(InUse)));
+
+            tx.send_nowait(counter.add(session)).expect("tx.send-nowait");
+            assert_eq!(rx.recv_nowait().expect("rx.recv-nowait").unwrap(), session);
+            tx.send_nowait(counter.add(session)).expect("tx.send-nowait");
+            tx.close_with("done");
+
+            std::mem::drop(tx);
+            assert!(matches!(link.try_reuse(), Err(InUse)));
+            std::mem::drop(rx);
+            assert_eq!(counter.count(), 1);
+        }
+    }
+    assert_eq!(counter.count(), 0);
+}
@@ -0,0 +1,283 @@
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use std::time::Duration;
+
+use airlock::atomic_waker::AtomicWaker;
+use airlock::error::LimitReached;
+use airlock::sync::*;
+
+mod utils;
+use futures::future;
+use utils::Counter;
+
+const WAKERS_COUNT: usize = 4;
+
+#[test]
+fn t_00() {
+    let counter = Counter::new();
+
+    {
+        let wakers = make_wakers::<WAKERS_COUNT>();
+        let mutex = Mutex::new(counter.add(1), &wakers);
+
+        let mut guard = mutex.try_lock().expect("mutex.try-lock");
+        assert!(mutex.try_lock().is_none());
+        *guard = counter.add(2);
+        drop(guard);
+
+        assert_eq!(*mutex.try_lock().expect("mutex.try-lock"), counter.add(2));
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[test]
+fn t_01() {
+    let counter = Counter::new();
+
+    {
+        let wakers = make_wakers::<WAKERS_COUNT>();
+        let mut mutex = Mutex::new(counter.add(1), &wakers);
+        *mutex.get_mut() = counter.add(2);
+        assert_eq!(mutex.into_inner().unwrap(), 2);
+
+        let wakers = make_wakers::<WAKERS_COUNT>();
+        let mut lock = RwLock::new(counter.add(3), &wakers);
+        *lock.get_mut() = counter.add(4);
+        assert_eq!(lock.into_inner().unwrap(), 4);
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[tokio::test]
+async fn t_02() {
+    let wakers = make_wakers::<16>();
+    let mutex = Mutex::new(0usize, &wakers);
+
+    future::join_all((0..16).map(|_| async {
+        for _ in 0..100 {
+            let mut guard = mutex.lock().await.expect("mutex.lock");
+            let value = *guard;
+            tokio::task::yield_now().await;
+            *guard = value + 1;
+        }
+    }))
+    .await;
+
+    assert_eq!(*mutex.lock().await.expect("mutex.lock"), 1_600);
+}
+
+#[test]
+fn t_03() {
+    let counter = Counter::new();
+
+    {
+        let wakers = make_wakers::<WAKERS_COUNT>();
+        let lock = RwLock::new(counter.add(1), &wakers);
+
+        let r_1 = lock.try_read().expect("lock.try-read");
+        let r_2 = lock.try_read().expect("lock.try-read");
+        assert!(lock.try_write().is_none());
+        assert_eq!(*r_1, counter.add(1));
+        assert_eq!(*r_2, counter.add(1));
+        drop(r_1);
+        assert!(lock.try_write().is_none());
+        drop(r_2);
+
+        let mut w = lock.try_write().expect("lock.try-write");
+        assert!(lock.try_read().is_none());
+        assert!(lock.try_write().is_none());
+        *w = counter.add(2);
+        drop(w);
+
+        assert_eq!(*lock.try_read().expect("lock.try-read"), counter.add(2));
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[tokio::test]
+async fn t_04() {
+    let wakers = make_wakers::<WAKERS_COUNT>();
+    let lock = RwLock::new(0usize, &wakers);
+
+    let read = lock.read().await.expect("lock.read");
+    let writer = async {
+        *lock.write().await.expect("lock.write") += 1;
+    };
+    let reader = async {
+        tokio::task::yield_now().await;
+        assert_eq!(*read, 0);
+        drop(read);
+    };
+    future::join(writer, reader).await;
+
+    assert_eq!(*lock.read().await.expect("lock.read"), 1);
+}
+
+#[tokio::test]
+async fn t_05() {
+    let wakers = make_wakers::<16>();
+    let lock = Arc::new(RwLock::new(0usize, wakers));
+
+    let writers = (0..8)
+        .map(|_| {
+            let lock = Arc::clone(&lock);
+            async move {
+                for _ in 0..100 {
+                    let mut guard = lock.write().await.expect("lock.write");
+                    let value = *guard;
+                    tokio::task::yield_now().await;
+                    *guard = value + 1;
+                }
+            }
+        })
+        .map(tokio::spawn);
+    let readers = (0..8)
+        .map(|_| {
+            let lock = Arc::clone(&lock);
+            async move {
+                for _ in 0..100 {
+                    let guard = lock.read().await.expect("lock.read");
+                    let value = *guard;
+                    tokio::task::yield_now().await;
+                    assert_eq!(*guard, value);
+                }
+            }
+        })
+        .map(tokio::spawn);
+
+    let (writers, readers) =
+        future::join(future::try_join_all(writers), future::try_join_all(readers)).await;
+    writers.expect("writers");
+    readers.expect("readers");
+
+    assert_eq!(*lock.read().await.expect("lock.read"), 800);
+}
+
+#[tokio::test]
+async fn t_06() {
+    const PARTIES: usize = 4;
+
+    let wakers = make_wakers::<WAKERS_COUNT>();
+    let barrier = Barrier::new(PARTIES, &wakers);
+
+    for _round in 0..3 {
+        let results = future::join_all((0..PARTIES).map(|_| barrier.wait())).await;
+        let results = results.into_iter().map(|r| r.expect("barrier.wait")).collect::<Vec<_>>();
+        assert_eq!(results.iter().filter(|r| r.is_leader()).count(), 1);
+    }
+
+    let timeout = tokio::time::timeout(
+        Duration::from_millis(10),
+        future::join_all((0..PARTIES - 1).map(|_| barrier.wait())),
+    )
+    .await;
+    assert!(timeout.is_err());
+}
+
+#[tokio::test]
+async fn t_07() {
+    let wakers = make_wakers::<WAKERS_COUNT>();
+    let latch = CountDownLatch::new(2, &wakers);
+
+    let waiting = future::join_all((0..WAKERS_COUNT).map(|_| latch.wait()));
+    let counting = async {
+        latch.count_down();
+        tokio::task::yield_now().await;
+        assert_eq!(latch.count(), 1);
+        latch.count_down();
+    };
+    let (waited, ()) = future::join(waiting, counting).await;
+    for waited in waited {
+        waited.expect("latch.wait");
+    }
+
+    latch.count_down();
+    assert_eq!(latch.count(), 0);
+    latch.wait().await.expect("latch.wait");
+}
+
+#[tokio::test]
+async fn t_08() {
+    let wakers = make_wakers::<WAKERS_COUNT>();
+    let wait_group = WaitGroup::new(&wakers);
+
+    wait_group.wait().await.expect("wait-group.wait");
+
+    wait_group.add(3);
+    let waiting = wait_group.wait();
+    let working = future::join_all((0..3).map(|_| async {
+        tokio::task::yield_now().await;
+        wait_group.done();
+    }));
+    let (waited, _) = future::join(waiting, working).await;
+    waited.expect("wait-group.wait");
+    assert_eq!(wait_group.count(), 0);
+}
+
+#[test]
+#[should_panic]
+fn t_09() {
+    let wakers = make_wakers::<WAKERS_COUNT>();
+    let wait_group = WaitGroup::new(&wakers);
+    wait_group.done();
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn t_10() {
+    const THREADS: usize = 4;
+
+    let barrier = Barrier::new(THREADS, make_wakers::<WAKERS_COUNT>());
+    let latch = CountDownLatch::new(THREADS, make_wakers::<WAKERS_COUNT>());
+    let wait_group = WaitGroup::new(make_wakers::<WAKERS_COUNT>());
+    wait_group.add(THREADS);
+
+    let leaders = std::thread::scope(|s| {
+        let threads = (0..THREADS)
+            .map(|_| {
+                s.spawn(|| {
+                    latch.count_down();
+                    latch.wait_blocking().expect("latch.wait");
+                    wait_group.done();
+                    barrier.wait_blocking().expect("barrier.wait").is_leader()
+                })
+            })
+            .collect::<Vec<_>>();
+        wait_group.wait_blocking().expect("wait-group.wait");
+        threads.into_iter().map(|t| t.join().expect("thread")).filter(|l| *l).count()
+    });
+    assert_eq!(leaders, 1);
+}
+
+#[tokio::test]
+async fn t_11() {
+    let wakers = make_wakers::<1>();
+    let mutex = Mutex::new(0usize, &wakers);
+
+    let guard = mutex.lock().await.expect("mutex.lock");
+    let mut waiting = std::pin::pin!(mutex.lock());
+    assert!(future::poll_immediate(&mut waiting).await.is_none());
+
+    assert_eq!(mutex.lock().await.expect_err("mutex.lock"), LimitReached);
+
+    drop(guard);
+    assert_eq!(*waiting.await.expect("mutex.lock"), 0);
+}
+
+#[tokio::test]
+async fn t_12() {
+    let wakers = make_wakers::<1>();
+    let latch = CountDownLatch::new(1, &wakers);
+
+    let mut waiting = std::pin::pin!(latch.wait());
+    assert!(future::poll_immediate(&mut waiting).await.is_none());
+
+    assert_eq!(latch.wait().await.expect_err("latch.wait"), LimitReached);
+
+    latch.count_down();
+    waiting.await.expect("latch.wait");
+}
+
+fn make_wakers<const SIZE: usize>() -> [(AtomicBool, AtomicWaker); SIZE] {
+    core::array::from_fn(|_| Default::default())
+}
@@ -0,0 +1,211 @@
+#![cfg(feature = "alloc")]
+
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+use airlock::atomic_waker::AtomicWaker;
+use airlock::error::{RecvError, SendErrorNoWait};
+use airlock::unbounded::*;
+
+mod utils;
+use futures::future;
+use utils::{Counted, Counter};
+
+type Value = Counted<usize>;
+
+const WAKERS_COUNT: usize = 8;
+
+#[test]
+fn t_00() {
+    let rx_wakers = make_wakers::<WAKERS_COUNT>();
+    let _link = Link::<Value, _>::new(&rx_wakers);
+}
+
+#[test]
+fn t_01() {
+    let rx_wakers = make_wakers::<WAKERS_COUNT>();
+    let link = Link::<Value, _>::new(&rx_wakers);
+
+    let _tx_1 = Tx::new(&link);
+    let _tx_2 = _tx_1.clone();
+
+    let _rx_1 = Rx::new(&link);
+    let _rx_2 = _rx_1.try_clone().expect("rx.try-clone");
+}
+
+#[test]
+fn t_02() {
+    let counter = Counter::new();
+
+    {
+        let rx_wakers = make_wakers::<WAKERS_COUNT>();
+        let link = Link::<Value, _>::new(&rx_wakers);
+
+        let mut tx = Tx::new(&link);
+        let mut rx = Rx::new(&link);
+
+        for i in 0..1_000 {
+            tx.send_nowait(counter.add(i)).expect("tx.send-nowait");
+        }
+        for i in 0..1_000 {
+            assert_eq!(rx.recv_nowait().expect("rx.recv-nowait").unwrap(), i);
+        }
+        assert!(rx.recv_nowait().expect_err("rx.recv-nowait").is_empty());
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[test]
+fn t_03() {
+    let counter = Counter::new();
+
+    {
+        let rx_wakers = make_wakers::<WAKERS_COUNT>();
+        let link = Link::<Value, _>::new(&rx_wakers);
+
+        let mut tx = Tx::new(&link);
+        let mut rx = Rx::new(&link);
+
+        for i in 0..100 {
+            tx.send_nowait(counter.add(i)).expect("tx.send-nowait");
+        }
+        for i in 0..40 {
+            assert_eq!(rx.recv_nowait().expect("rx.recv-nowait").unwrap(), i);
+        }
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[test]
+fn t_04() {
+    let counter = Counter::new();
+
+    {
+        let rx_wakers = make_wakers::<WAKERS_COUNT>();
+        let link = Link::<Value, _>::new(&rx_wakers);
+
+        let mut tx_1 = Tx::new(&link);
+        let tx_2 = tx_1.clone();
+        let mut rx = Rx::new(&link);
+
+        tx_1.send_nowait(counter.add(1)).expect("tx.send-nowait");
+        drop(tx_1);
+        assert_eq!(rx.recv_nowait().expect("rx.recv-nowait").unwrap(), 1);
+        assert!(rx.recv_nowait().expect_err("rx.recv-nowait").is_empty());
+
+        drop(tx_2);
+        assert!(rx.recv_nowait().expect_err("rx.recv-nowait").is_closed());
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[tokio::test]
+async fn t_05() {
+    let counter = Counter::new();
+
+    {
+        let rx_wakers = make_wakers::<WAKERS_COUNT>();
+        let link = Link::<Value, _, &str>::new(&rx_wakers);
+
+        let mut tx = Tx::new(&link);
+        let mut rx = Rx::new(&link);
+
+        tx.send(counter.add(1)).await.expect("tx.send");
+        rx.close_with("done");
+
+        assert!(matches!(
+            tx.send_nowait(counter.add(2)),
+            Err(SendErrorNoWait::ClosedWith(_, "done"))
+        ));
+        assert_eq!(rx.recv().await.expect("rx.recv").unwrap(), 1);
+        assert!(matches!(rx.recv().await, Err(RecvError::ClosedWith("done"))));
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[tokio::test]
+async fn t_06() {
+    let counter = Counter::new();
+
+    {
+        let rx_wakers = make_wakers::<WAKERS_COUNT>();
+        let link = Link::<Value, _>::new(&rx_wakers);
+
+        let mut tx = Tx::new(&link);
+        let mut rx = Rx::new(&link);
+
+        let (_, received) = future::join(
+            async {
+                for i in 0..100 {
+                    tx.send(counter.add(i)).await.expect("tx.send");
+                    tokio::task::yield_now().await;
+                }
+                tx.close();
+            },
+            async {
+                let mut received = vec![];
+                while let Ok(value) = rx.recv().await {
+                    received.push(value.unwrap());
+                }
+                received
+            },
+        )
+        .await;
+        assert_eq!(received, (0..100).collect::<Vec<_>>());
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+#[test]
+fn t_07() {
+    let counter = Counter::new();
+
+    const ITERATIONS: usize = 10_000;
+    const PRODUCERS: usize = 4;
+
+    {
+        let rx_wakers = make_wakers::<WAKERS_COUNT>();
+        let link = Arc::new(Link::<Value, _>::new(rx_wakers));
+
+        let tx = Tx::new(Arc::clone(&link));
+        let producers = (0..PRODUCERS)
+            .map(|_| {
+                let counter = counter.clone();
+                let mut tx = tx.clone();
+                std::thread::spawn(move || {
+                    for i in 0..ITERATIONS {
+                        tx.send_nowait(counter.add(i)).expect("tx.send-nowait");
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        drop(tx);
+
+        let consumers = (0..PRODUCERS)
+            .map(|_| {
+                let mut rx = Rx::new(Arc::clone(&link));
+                std::thread::spawn(move || {
+                    let mut received = 0;
+                    loop {
+                        match rx.recv_nowait() {
+                            Ok(_) => received += 1,
+                            Err(e) if e.is_closed() => break received,
+                            Err(_) => std::thread::yield_now(),
+                        }
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for producer in producers {
+            producer.join().expect("producer.join");
+        }
+        let received: usize = consumers.into_iter().map(|c| c.join().expect("consumer.join")).sum();
+        assert_eq!(received, ITERATIONS * PRODUCERS);
+    }
+    assert_eq!(counter.count(), 0);
+}
+
+fn make_wakers<const SIZE: usize>() -> [(AtomicBool, AtomicWaker); SIZE] {
+    core::array::from_fn(|_| Default::default())
+}